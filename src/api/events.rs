@@ -0,0 +1,59 @@
+//! Server-Sent Events stream of pipeline events.
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use futures_util::stream;
+use http_body_util::{BodyExt, StreamBody};
+use hyper::body::{Bytes, Frame};
+use hyper::{header, Response, StatusCode};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tracing::debug;
+
+use super::ApiResponse;
+use crate::notify::{EventBus, PipelineEvent};
+
+/// Interval between keep-alive comments on an idle stream.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Builds a streaming `text/event-stream` response for all pipeline events.
+///
+/// Each event is sent as `event: <name>` with its JSON in `data:`. Clients that
+/// fall behind receive a `lagged` event with the number of dropped events.
+pub fn stream_events(events: &EventBus) -> ApiResponse {
+    let rx = events.subscribe();
+
+    let body = stream::unfold(rx, |mut rx| async move {
+        let chunk = next_chunk(&mut rx).await?;
+        Some((Ok::<_, Infallible>(Frame::data(Bytes::from(chunk))), rx))
+    });
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(StreamBody::new(body).boxed())
+        .unwrap()
+}
+
+/// Waits for the next event and formats it as an SSE chunk.
+///
+/// Returns `None` when the event bus is closed, which ends the stream.
+async fn next_chunk(rx: &mut Receiver<PipelineEvent>) -> Option<String> {
+    match tokio::time::timeout(KEEPALIVE_INTERVAL, rx.recv()).await {
+        Err(_) => Some(": keep-alive\n\n".to_string()),
+        Ok(Ok(event)) => {
+            let data = serde_json::to_string(&event).ok()?;
+            Some(format!("event: {}\ndata: {}\n\n", event.name(), data))
+        }
+        Ok(Err(RecvError::Lagged(skipped))) => {
+            debug!(skipped, "SSE client lagged behind event bus");
+            Some(format!(
+                "event: lagged\ndata: {{\"skipped\":{}}}\n\n",
+                skipped
+            ))
+        }
+        Ok(Err(RecvError::Closed)) => None,
+    }
+}
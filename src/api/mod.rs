@@ -0,0 +1,251 @@
+//! HTTP control API for the running pipeline.
+
+pub mod events;
+pub mod routes;
+pub mod webhooks;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use std::convert::Infallible;
+
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper::{header, Response, StatusCode};
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tracing::{error, info};
+
+use crate::config::model::AppConfig;
+use crate::error::ApiError;
+use crate::notify::{EventBus, HookRunner};
+use crate::queue::QueueManager;
+use crate::validation::SystemCapabilities;
+
+/// Response type produced by all API handlers.
+pub type ApiResponse = Response<BoxBody<Bytes, Infallible>>;
+
+/// Shared state available to request handlers.
+pub struct ApiState {
+    /// Queue manager (cloned per request).
+    pub queue: QueueManager,
+    /// Current configuration.
+    pub config: Arc<RwLock<AppConfig>>,
+    /// Capabilities detected at startup.
+    pub capabilities: SystemCapabilities,
+    /// Runner for lifecycle hooks (used on enqueue).
+    pub hooks: Arc<HookRunner>,
+    /// Event bus streamed to `/api/events` subscribers.
+    pub events: EventBus,
+    /// Bearer token required on requests, if any.
+    pub token: Option<String>,
+}
+
+/// HTTP server exposing the control API.
+pub struct ApiServer {
+    /// Shared handler state.
+    state: Arc<ApiState>,
+    /// Port to listen on.
+    port: u16,
+}
+
+impl ApiServer {
+    /// Creates a new API server.
+    pub fn new(state: ApiState, port: u16) -> Self {
+        Self {
+            state: Arc::new(state),
+            port,
+        }
+    }
+
+    /// Starts the API HTTP server.
+    pub async fn start(self) -> Result<(), ApiError> {
+        use hyper::server::conn::http1;
+        use hyper::service::service_fn;
+        use hyper::{body::Incoming, Request};
+        use hyper_util::rt::TokioIo;
+
+        let addr: SocketAddr = ([0, 0, 0, 0], self.port).into();
+        let listener =
+            tokio::net::TcpListener::bind(addr)
+                .await
+                .map_err(|e| ApiError::BindFailed {
+                    port: self.port,
+                    message: e.to_string(),
+                })?;
+
+        info!(port = self.port, "Starting control API server");
+
+        loop {
+            let (stream, _) = listener
+                .accept()
+                .await
+                .map_err(|e| ApiError::AcceptFailed(e.to_string()))?;
+
+            let io = TokioIo::new(stream);
+            let state = self.state.clone();
+
+            tokio::spawn(async move {
+                let service = service_fn(|req: Request<Incoming>| {
+                    let state = state.clone();
+                    async move {
+                        if !is_authorized(state.token.as_deref(), &req) {
+                            return Ok::<_, hyper::Error>(error_response(
+                                StatusCode::UNAUTHORIZED,
+                                "Missing or invalid bearer token",
+                            ));
+                        }
+                        Ok(routes::route(state, req).await)
+                    }
+                });
+
+                if let Err(e) = http1::Builder::new().serve_connection(io, service).await {
+                    error!(error = %e, "Error serving API connection");
+                }
+            });
+        }
+    }
+}
+
+/// Checks the request's credentials against the configured token.
+///
+/// The token is accepted as a bearer token, or as the password of HTTP basic
+/// auth (with any username) for webhook senders such as Sonarr and Radarr.
+fn is_authorized<B>(token: Option<&str>, req: &hyper::Request<B>) -> bool {
+    let expected = match token {
+        Some(token) => token,
+        None => return true,
+    };
+
+    let value = match req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(value) => value,
+        None => return false,
+    };
+
+    let provided = if let Some(token) = value.strip_prefix("Bearer ") {
+        token.as_bytes().to_vec()
+    } else if let Some(encoded) = value.strip_prefix("Basic ") {
+        match basic_auth_password(encoded) {
+            Some(password) => password,
+            None => return false,
+        }
+    } else {
+        return false;
+    };
+
+    constant_time_eq(&provided, expected.as_bytes())
+}
+
+/// Extracts the password from a base64 `user:password` basic auth value.
+fn basic_auth_password(encoded: &str) -> Option<Vec<u8>> {
+    use base64::Engine;
+
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()?;
+    let split = decoded.iter().position(|&b| b == b':')?;
+    Some(decoded[split + 1..].to_vec())
+}
+
+/// Compares two byte strings without short-circuiting on the first difference.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Builds a JSON response with the given status.
+pub fn json_response<T: Serialize>(status: StatusCode, body: &T) -> ApiResponse {
+    match serde_json::to_vec(body) {
+        Ok(json) => Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Full::new(Bytes::from(json)).boxed())
+            .unwrap(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+/// Builds a JSON error response (`{"error": "..."}`).
+pub fn error_response(status: StatusCode, message: &str) -> ApiResponse {
+    let body = serde_json::json!({ "error": message }).to_string();
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body)).boxed())
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(authorization: Option<&str>) -> hyper::Request<()> {
+        let mut builder = hyper::Request::builder().uri("/api/jobs");
+        if let Some(value) = authorization {
+            builder = builder.header(header::AUTHORIZATION, value);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn constant_time_eq_compares_contents_and_length() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(constant_time_eq(b"", b""));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn bearer_token_must_match() {
+        assert!(is_authorized(
+            Some("secret"),
+            &request(Some("Bearer secret"))
+        ));
+        assert!(!is_authorized(
+            Some("secret"),
+            &request(Some("Bearer wrong"))
+        ));
+        assert!(!is_authorized(
+            Some("secret"),
+            &request(Some("bearer secret"))
+        ));
+        assert!(!is_authorized(Some("secret"), &request(None)));
+    }
+
+    #[test]
+    fn basic_auth_password_is_the_token_for_any_user() {
+        // "sonarr:secret" and ":secret"
+        assert!(is_authorized(
+            Some("secret"),
+            &request(Some("Basic c29uYXJyOnNlY3JldA=="))
+        ));
+        assert!(is_authorized(
+            Some("secret"),
+            &request(Some("Basic OnNlY3JldA=="))
+        ));
+        // "sonarr:wrong", "secret" without a colon, and invalid base64
+        assert!(!is_authorized(
+            Some("secret"),
+            &request(Some("Basic c29uYXJyOndyb25n"))
+        ));
+        assert!(!is_authorized(
+            Some("secret"),
+            &request(Some("Basic c2VjcmV0"))
+        ));
+        assert!(!is_authorized(Some("secret"), &request(Some("Basic !!!"))));
+    }
+
+    #[test]
+    fn no_token_allows_every_request() {
+        assert!(is_authorized(None, &request(None)));
+        assert!(is_authorized(None, &request(Some("Bearer anything"))));
+    }
+}
@@ -0,0 +1,287 @@
+//! Inbound import webhooks (Sonarr, Radarr and a generic payload).
+
+use std::path::{Path, PathBuf};
+
+use hyper::body::Incoming;
+use hyper::{Request, StatusCode};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use super::json_response;
+use super::routes::{read_json, HandlerError, HandlerResult};
+use super::ApiState;
+use crate::config::model::{PathMapping, PathProfileRule};
+use crate::error::QueueError;
+use crate::queue::job::JobOverrides;
+use crate::queue::submit::{submit, SubmitRequest};
+use crate::queue::QueueManager;
+
+/// Sonarr/Radarr event type sent after a file is imported.
+const IMPORT_EVENT: &str = "Download";
+
+/// A file reference in a Sonarr/Radarr payload.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArrFile {
+    /// Absolute path of the imported file (newer versions).
+    #[serde(default)]
+    path: Option<PathBuf>,
+    /// Path relative to the series or movie folder.
+    #[serde(default)]
+    relative_path: Option<PathBuf>,
+}
+
+/// Sonarr series details.
+#[derive(Deserialize)]
+struct SonarrSeries {
+    path: PathBuf,
+}
+
+/// Sonarr webhook payload.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SonarrPayload {
+    event_type: String,
+    #[serde(default)]
+    series: Option<SonarrSeries>,
+    #[serde(default)]
+    episode_file: Option<ArrFile>,
+    /// Present instead of `episodeFile` for multi-file imports.
+    #[serde(default)]
+    episode_files: Vec<ArrFile>,
+}
+
+/// Radarr movie details.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RadarrMovie {
+    folder_path: PathBuf,
+}
+
+/// Radarr webhook payload.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RadarrPayload {
+    event_type: String,
+    #[serde(default)]
+    movie: Option<RadarrMovie>,
+    #[serde(default)]
+    movie_file: Option<ArrFile>,
+}
+
+/// Generic webhook payload.
+#[derive(Deserialize)]
+struct GenericPayload {
+    path: PathBuf,
+    #[serde(default)]
+    profile: Option<String>,
+    #[serde(default)]
+    priority: i32,
+    #[serde(default)]
+    overrides: JobOverrides,
+}
+
+/// Outcome for a single file of a webhook delivery.
+#[derive(Serialize)]
+struct IngestResult {
+    path: PathBuf,
+    status: &'static str,
+    job_id: String,
+}
+
+/// Handles a Sonarr "On Import" webhook.
+pub(super) async fn sonarr(
+    state: &ApiState,
+    queue: &mut QueueManager,
+    query: Option<&str>,
+    req: Request<Incoming>,
+) -> HandlerResult {
+    let payload: SonarrPayload = read_json(req).await?;
+
+    if payload.event_type != IMPORT_EVENT {
+        return Ok(ignored(&payload.event_type));
+    }
+
+    let folder = payload.series.map(|s| s.path);
+    let files: Vec<PathBuf> = payload
+        .episode_file
+        .into_iter()
+        .chain(payload.episode_files)
+        .filter_map(|f| resolve_file(f, folder.as_deref()))
+        .collect();
+
+    ingest(state, queue, query, files).await
+}
+
+/// Handles a Radarr "On Import" webhook.
+pub(super) async fn radarr(
+    state: &ApiState,
+    queue: &mut QueueManager,
+    query: Option<&str>,
+    req: Request<Incoming>,
+) -> HandlerResult {
+    let payload: RadarrPayload = read_json(req).await?;
+
+    if payload.event_type != IMPORT_EVENT {
+        return Ok(ignored(&payload.event_type));
+    }
+
+    let folder = payload.movie.map(|m| m.folder_path);
+    let files: Vec<PathBuf> = payload
+        .movie_file
+        .into_iter()
+        .filter_map(|f| resolve_file(f, folder.as_deref()))
+        .collect();
+
+    ingest(state, queue, query, files).await
+}
+
+/// Handles a generic `{path, profile, priority, overrides}` webhook.
+pub(super) async fn generic(
+    state: &ApiState,
+    queue: &mut QueueManager,
+    req: Request<Incoming>,
+) -> HandlerResult {
+    let payload: GenericPayload = read_json(req).await?;
+    let path = map_incoming_path(state, &payload.path).await?;
+
+    let profile = match payload.profile {
+        Some(profile) => Some(profile),
+        None => rule_profile(state, &path).await,
+    };
+
+    let request = SubmitRequest {
+        path,
+        profile,
+        priority: payload.priority,
+        overrides: payload.overrides,
+    };
+
+    let job = submit(queue, &state.config, &state.hooks, request).await?;
+    Ok(json_response(StatusCode::CREATED, &job))
+}
+
+/// Maps and enqueues the files of a Sonarr/Radarr delivery.
+///
+/// Files that are already queued are reported as duplicates rather than
+/// errors, so redelivered webhooks are harmless. The profile is chosen by
+/// the configured profile rules or the watch folders, unless a `profile`
+/// query parameter is given.
+async fn ingest(
+    state: &ApiState,
+    queue: &mut QueueManager,
+    query: Option<&str>,
+    files: Vec<PathBuf>,
+) -> HandlerResult {
+    if files.is_empty() {
+        return Err(HandlerError::new(
+            StatusCode::BAD_REQUEST,
+            "Payload does not reference any imported file",
+        ));
+    }
+
+    let profile = query
+        .unwrap_or_default()
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("profile="))
+        .map(str::to_string);
+
+    let mut results = Vec::new();
+
+    for file in files {
+        let path = map_incoming_path(state, &file).await?;
+
+        let profile = match &profile {
+            Some(profile) => Some(profile.clone()),
+            None => rule_profile(state, &path).await,
+        };
+
+        let request = SubmitRequest {
+            profile,
+            ..SubmitRequest::new(path.clone())
+        };
+
+        match submit(queue, &state.config, &state.hooks, request).await {
+            Ok(job) => results.push(IngestResult {
+                path,
+                status: "queued",
+                job_id: job.id,
+            }),
+            Err(QueueError::DuplicateJob { job_id, .. }) => {
+                debug!(?path, %job_id, "Webhook file is already queued");
+                results.push(IngestResult {
+                    path,
+                    status: "duplicate",
+                    job_id,
+                });
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(json_response(StatusCode::OK, &results))
+}
+
+/// Builds the response for events other than imports.
+fn ignored(event_type: &str) -> super::ApiResponse {
+    info!(event_type, "Ignoring non-import webhook event");
+    json_response(
+        StatusCode::OK,
+        &serde_json::json!({ "status": "ignored", "event_type": event_type }),
+    )
+}
+
+/// Returns the absolute path of an imported file.
+fn resolve_file(file: ArrFile, folder: Option<&Path>) -> Option<PathBuf> {
+    match (file.path, file.relative_path, folder) {
+        (Some(path), _, _) => Some(path),
+        (None, Some(relative), Some(folder)) => Some(folder.join(relative)),
+        _ => None,
+    }
+}
+
+/// Applies the configured path mappings and checks the file exists locally.
+async fn map_incoming_path(state: &ApiState, path: &Path) -> Result<PathBuf, HandlerError> {
+    let mapped = {
+        let config = state.config.read().await;
+        map_path(path, &config.global.api.path_mappings)
+    };
+
+    if !mapped.is_file() {
+        return Err(HandlerError::new(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "File not found: '{}' (received '{}')",
+                mapped.display(),
+                path.display()
+            ),
+        ));
+    }
+
+    Ok(mapped)
+}
+
+/// Returns the profile of the longest profile rule prefix matching a local path.
+async fn rule_profile(state: &ApiState, path: &Path) -> Option<String> {
+    let config = state.config.read().await;
+    match_profile_rule(path, &config.global.api.profile_rules)
+}
+
+/// Picks the profile of the longest rule prefix that contains a path.
+fn match_profile_rule(path: &Path, rules: &[PathProfileRule]) -> Option<String> {
+    rules
+        .iter()
+        .filter(|rule| path.starts_with(&rule.path))
+        .max_by_key(|rule| rule.path.components().count())
+        .map(|rule| rule.profile.clone())
+}
+
+/// Rewrites a caller path using the longest matching mapping prefix.
+fn map_path(path: &Path, mappings: &[PathMapping]) -> PathBuf {
+    mappings
+        .iter()
+        .filter_map(|m| path.strip_prefix(&m.remote).ok().map(|rest| (m, rest)))
+        .max_by_key(|(m, _)| m.remote.components().count())
+        .map(|(m, rest)| m.local.join(rest))
+        .unwrap_or_else(|| path.to_path_buf())
+}
@@ -1,20 +1,39 @@
 //! Command-line interface definitions.
 
-use clap::{Args, Parser, Subcommand};
+use chrono::{DateTime, Days, Local, NaiveDate, TimeZone, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
+use crate::queue::JobStatus;
+
 /// A VMAF-targeted video encoding pipeline using av1an.
 #[derive(Parser, Debug)]
-#[command(name = "encode-pipeline", version, about, long_about = None)]
+#[command(
+    name = "encode-pipeline",
+    version,
+    about,
+    long_about = None,
+    after_help = "Exit codes: 0 success, 1 failure or config errors, 2 usage error, 3 config warnings"
+)]
 pub struct Cli {
     /// Path to the configuration file.
-    #[arg(short, long, default_value = "/config/pipeline.yaml", env = "CONFIG_PATH", global = true)]
+    #[arg(
+        short,
+        long,
+        default_value = "/config/pipeline.yaml",
+        env = "CONFIG_PATH",
+        global = true
+    )]
     pub config: PathBuf,
 
     /// Increase logging verbosity (-v, -vv, -vvv).
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     pub verbose: u8,
 
+    /// Output format for command results.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    pub output: OutputFormat,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -30,6 +49,17 @@ impl Cli {
     }
 }
 
+/// Format of command results on stdout.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    Text,
+    /// Pretty-printed JSON.
+    Json,
+    /// YAML.
+    Yaml,
+}
+
 /// Available subcommands for the encoding pipeline.
 #[derive(Subcommand, Debug)]
 pub enum Commands {
@@ -52,6 +82,45 @@ pub enum Commands {
     #[command(name = "queue-clear")]
     QueueClear,
 
+    /// Queue files, directories or glob patterns for encoding.
+    Enqueue(EnqueueArgs),
+
+    /// Show how a file would be encoded without encoding it.
+    Plan {
+        /// The file to plan.
+        file: PathBuf,
+
+        /// Profile to use (default: the profile whose input folder contains the file).
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Print the plan as JSON (same as `--output json`).
+        #[arg(long, default_value = "false")]
+        json: bool,
+    },
+
+    /// Move existing Redis keys into the configured namespace (stop pipelines first).
+    #[command(name = "redis-migrate")]
+    RedisMigrate {
+        /// Namespace to move keys from (default: the unprefixed keys).
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Show what would move without changing anything.
+        #[arg(long, default_value = "false")]
+        dry_run: bool,
+    },
+
+    /// Search finished jobs and show encode statistics.
+    History(HistoryArgs),
+
+    /// Show a live full-screen view of the queue and watchers.
+    #[command(alias = "top")]
+    Dashboard,
+
+    /// List live encoding nodes and the jobs they are running.
+    Nodes,
+
     /// Retry a job from the dead letter queue.
     #[command(name = "retry-dead-letter")]
     RetryDeadLetter {
@@ -70,4 +139,148 @@ pub struct RunArgs {
     /// Process existing files in watch folders on startup.
     #[arg(long, default_value = "false")]
     pub process_existing: bool,
+
+    /// Only encode jobs from the shared queue, using the configuration cached in Redis.
+    ///
+    /// No folders are watched and no API is served. Input and output paths must
+    /// be mounted at the same locations as on the pipeline that queued the jobs.
+    #[arg(long, default_value = "false")]
+    pub worker_only: bool,
+
+    /// ID this node registers under (default: the host name).
+    #[arg(long, env = "NODE_ID")]
+    pub node_id: Option<String>,
+
+    /// Redis URL for worker-only mode (default: the Redis settings of the config file).
+    #[arg(long, env = "REDIS_URL")]
+    pub redis_url: Option<String>,
+
+    /// Redis key namespace for worker-only mode, used with --redis-url.
+    #[arg(long, env = "REDIS_NAMESPACE")]
+    pub redis_namespace: Option<String>,
+
+    /// Discord webhook URL for worker-only mode (default: the config file's webhook).
+    ///
+    /// The webhook in the configuration cached in Redis is redacted, so worker
+    /// nodes need their own to report failures.
+    #[arg(long, env = "DISCORD_WEBHOOK_URL")]
+    pub discord_webhook_url: Option<String>,
+}
+
+/// Arguments for the enqueue subcommand.
+#[derive(Args, Debug)]
+pub struct EnqueueArgs {
+    /// Files, directories or glob patterns to enqueue.
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+
+    /// Profile to use (default: the profile whose input folder contains each file).
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Descend into subdirectories of directory arguments.
+    #[arg(short, long, default_value = "false")]
+    pub recursive: bool,
+
+    /// Queue priority; higher values are encoded first.
+    #[arg(long, default_value = "0", allow_hyphen_values = true)]
+    pub priority: i32,
+
+    /// VMAF target to use instead of the profile's.
+    #[arg(long)]
+    pub vmaf_target: Option<f32>,
+
+    /// av1an encoder parameters to use instead of the profile's.
+    #[arg(long, allow_hyphen_values = true)]
+    pub encoder_params: Option<String>,
+
+    /// Output file path (only valid when enqueuing a single file).
+    #[arg(long)]
+    pub output_path: Option<PathBuf>,
+
+    /// Audio language priority, in order (repeatable, e.g. --audio-language eng).
+    #[arg(long = "audio-language")]
+    pub audio_languages: Vec<String>,
+
+    /// Subtitle languages to keep (repeatable, e.g. --subtitle-language eng).
+    #[arg(long = "subtitle-language")]
+    pub subtitle_languages: Vec<String>,
+}
+
+/// Arguments for the history subcommand.
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    /// Only jobs of this profile.
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Only jobs with this outcome.
+    #[arg(long, value_enum)]
+    pub status: Option<HistoryStatus>,
+
+    /// Only jobs finished on or after this date (YYYY-MM-DD or RFC 3339).
+    #[arg(long, value_parser = parse_since)]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only jobs finished on or before this date (YYYY-MM-DD or RFC 3339).
+    #[arg(long, value_parser = parse_until)]
+    pub until: Option<DateTime<Utc>>,
+
+    /// Only jobs whose source path contains this text (case-insensitive).
+    #[arg(long)]
+    pub path: Option<String>,
+
+    /// Maximum number of jobs to list (statistics cover all matches).
+    #[arg(long, default_value = "50")]
+    pub limit: usize,
+}
+
+/// Outcome of a finished job.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryStatus {
+    /// Encoded successfully.
+    Completed,
+    /// Not encoded because a skip rule matched.
+    Skipped,
+    /// Failed after exhausting retries.
+    DeadLetter,
+    /// Cancelled by the user.
+    Cancelled,
+}
+
+impl From<HistoryStatus> for JobStatus {
+    fn from(status: HistoryStatus) -> Self {
+        match status {
+            HistoryStatus::Completed => JobStatus::Completed,
+            HistoryStatus::Skipped => JobStatus::Skipped,
+            HistoryStatus::DeadLetter => JobStatus::DeadLetter,
+            HistoryStatus::Cancelled => JobStatus::Cancelled,
+        }
+    }
+}
+
+/// Parses a start time; a bare date means the start of that local day.
+fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_time(value, 0)
+}
+
+/// Parses an end time; a bare date includes the whole local day.
+fn parse_until(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_time(value, 1)
+}
+
+fn parse_time(value: &str, extra_days: u64) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{}' (expected YYYY-MM-DD or RFC 3339)", value))?;
+    let midnight = date
+        .checked_add_days(Days::new(extra_days))
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .and_then(|t| Local.from_local_datetime(&t).earliest())
+        .ok_or_else(|| format!("invalid date '{}'", value))?;
+
+    Ok(midnight.with_timezone(&Utc))
 }
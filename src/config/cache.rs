@@ -7,33 +7,33 @@ use sha2::{Digest, Sha256};
 
 use super::model::AppConfig;
 use crate::error::ConfigError;
-
-const CONFIG_KEY: &str = "config:current";
-const CONFIG_HASH_KEY: &str = "config:hash";
-const CONFIG_TIMESTAMP_KEY: &str = "config:last_validated";
+use crate::queue::connection::RedisConnection;
+use crate::queue::KeySpace;
 
 /// Stores the validated configuration in Redis.
 pub async fn store_config(
-    redis: &mut redis::aio::ConnectionManager,
+    redis: &mut RedisConnection,
+    keys: &KeySpace,
     config: &AppConfig,
 ) -> Result<(), ConfigError> {
-    let json = serde_json::to_string(config).map_err(|e| ConfigError::CacheFailed(e.to_string()))?;
+    let json =
+        serde_json::to_string(config).map_err(|e| ConfigError::CacheFailed(e.to_string()))?;
 
     let hash = compute_hash(&json);
     let timestamp = Utc::now().timestamp();
 
     redis
-        .set::<_, _, ()>(CONFIG_KEY, &json)
+        .set::<_, _, ()>(keys.config(), &json)
         .await
         .map_err(|e| ConfigError::CacheFailed(e.to_string()))?;
 
     redis
-        .set::<_, _, ()>(CONFIG_HASH_KEY, &hash)
+        .set::<_, _, ()>(keys.config_hash(), &hash)
         .await
         .map_err(|e| ConfigError::CacheFailed(e.to_string()))?;
 
     redis
-        .set::<_, _, ()>(CONFIG_TIMESTAMP_KEY, timestamp)
+        .set::<_, _, ()>(keys.config_timestamp(), timestamp)
         .await
         .map_err(|e| ConfigError::CacheFailed(e.to_string()))?;
 
@@ -42,10 +42,11 @@ pub async fn store_config(
 
 /// Loads the cached configuration from Redis.
 pub async fn load_config(
-    redis: &mut redis::aio::ConnectionManager,
+    redis: &mut RedisConnection,
+    keys: &KeySpace,
 ) -> Result<Option<AppConfig>, ConfigError> {
     let json: Option<String> = redis
-        .get(CONFIG_KEY)
+        .get(keys.config())
         .await
         .map_err(|e| ConfigError::CacheFailed(e.to_string()))?;
 
@@ -59,26 +60,74 @@ pub async fn load_config(
     }
 }
 
+/// Returns the hash of the cached configuration, if any.
+pub async fn cached_hash(
+    redis: &mut RedisConnection,
+    keys: &KeySpace,
+) -> Result<Option<String>, ConfigError> {
+    redis
+        .get(keys.config_hash())
+        .await
+        .map_err(|e| ConfigError::CacheFailed(e.to_string()))
+}
+
 /// Checks if the cached configuration matches the given config by hash.
 pub async fn config_matches(
-    redis: &mut redis::aio::ConnectionManager,
+    redis: &mut RedisConnection,
+    keys: &KeySpace,
     config: &AppConfig,
 ) -> Result<bool, ConfigError> {
-    let json = serde_json::to_string(config).map_err(|e| ConfigError::CacheFailed(e.to_string()))?;
-
-    let current_hash = compute_hash(&json);
+    let current_hash = config_hash(config)?;
 
     let cached_hash: Option<String> = redis
-        .get(CONFIG_HASH_KEY)
+        .get(keys.config_hash())
         .await
         .map_err(|e| ConfigError::CacheFailed(e.to_string()))?;
 
     Ok(cached_hash.as_ref() == Some(&current_hash))
 }
 
+/// Returns the hash under which a configuration is cached.
+pub fn config_hash(config: &AppConfig) -> Result<String, ConfigError> {
+    let json =
+        serde_json::to_string(config).map_err(|e| ConfigError::CacheFailed(e.to_string()))?;
+    Ok(compute_hash(&json))
+}
+
 /// Computes the SHA256 hash of the given content.
 fn compute_hash(content: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(content.as_bytes());
     hex::encode(hasher.finalize())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::redact::redact_config;
+
+    fn config(vmaf_target: f32) -> AppConfig {
+        let mut config: AppConfig = serde_yaml::from_str(
+            "global:\n  redis: {password: secret}\nprofiles:\n  - name: movies\n    input_path: /in\n    output_path: /out\n    encoder: svt-av1\n    audio: {rules: []}\n    subtitles: {tracks: []}\n",
+        )
+        .unwrap();
+        config.profiles[0].vmaf_target = vmaf_target;
+        config
+    }
+
+    #[test]
+    fn cached_hash_changes_when_a_reload_changes_a_profile() {
+        let before = config_hash(&redact_config(&config(93.0))).unwrap();
+        let after = config_hash(&redact_config(&config(95.0))).unwrap();
+
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn cached_hash_is_stable_for_an_unchanged_reload() {
+        let before = config_hash(&redact_config(&config(93.0))).unwrap();
+        let after = config_hash(&redact_config(&config(93.0))).unwrap();
+
+        assert_eq!(before, after);
+    }
+}
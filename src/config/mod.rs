@@ -4,6 +4,7 @@ pub mod cache;
 pub mod hot_reload;
 pub mod loader;
 pub mod model;
+pub mod redact;
 
 use std::path::Path;
 use std::sync::Arc;
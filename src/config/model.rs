@@ -1,7 +1,11 @@
 //! Configuration data structures.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::error::ErrorCategory;
 
 /// Root configuration structure containing all settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,13 +39,45 @@ pub struct GlobalConfig {
     #[serde(default)]
     pub retry: RetryConfig,
 
+    /// Retention of finished jobs.
+    #[serde(default)]
+    pub history: HistoryConfig,
+
+    /// Limits on jobs encoded at the same time.
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+
+    /// Time windows and system conditions that hold back new jobs.
+    #[serde(default)]
+    pub throttle: ThrottleConfig,
+
+    /// Scheduling and resource limits for encoder subprocesses.
+    #[serde(default)]
+    pub process: ProcessLimits,
+
+    /// Detection of hung encoder subprocesses.
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+
+    /// On-disk buffer for queue writes while Redis is unreachable.
+    #[serde(default)]
+    pub spool: SpoolConfig,
+
     /// Prometheus metrics settings.
     #[serde(default)]
     pub prometheus: PrometheusConfig,
 
+    /// HTTP control API settings.
+    #[serde(default)]
+    pub api: ApiConfig,
+
     /// Notification settings.
     #[serde(default)]
     pub notifications: NotificationConfig,
+
+    /// Lifecycle hooks run for jobs of every profile.
+    #[serde(default)]
+    pub hooks: Vec<HookConfig>,
 }
 
 /// Redis connection configuration.
@@ -59,9 +95,73 @@ pub struct RedisConfig {
     #[serde(default)]
     pub db: u8,
 
+    /// Optional ACL username (Redis 6+).
+    #[serde(default)]
+    pub username: Option<String>,
+
     /// Optional Redis password.
     #[serde(default)]
     pub password: Option<String>,
+
+    /// Connection URL (`redis://`, `rediss://` or `redis+unix://`), replacing host, port and db.
+    ///
+    /// `username` and `password` still apply when the URL has no credentials.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Unix socket path, replacing host and port.
+    #[serde(default)]
+    pub socket: Option<PathBuf>,
+
+    /// TLS settings.
+    #[serde(default)]
+    pub tls: RedisTlsConfig,
+
+    /// Sentinel discovery, replacing host and port with the current master.
+    #[serde(default)]
+    pub sentinel: Option<SentinelConfig>,
+
+    /// Optional namespace prefixed to every key, so several pipelines can share a database.
+    #[serde(default)]
+    pub namespace: Option<String>,
+}
+
+/// TLS settings for the Redis connection.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedisTlsConfig {
+    /// Whether to connect with TLS (implied by a `rediss://` URL).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// CA certificate (PEM) to trust instead of the system roots.
+    #[serde(default)]
+    pub ca_cert: Option<PathBuf>,
+
+    /// Client certificate (PEM) for mutual TLS.
+    #[serde(default)]
+    pub client_cert: Option<PathBuf>,
+
+    /// Client private key (PEM) for mutual TLS.
+    #[serde(default)]
+    pub client_key: Option<PathBuf>,
+
+    /// Skip server certificate verification (testing only).
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+/// Redis Sentinel discovery settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentinelConfig {
+    /// Name of the monitored master.
+    pub master_name: String,
+
+    /// Sentinel addresses as `host:port` (port defaults to 26379).
+    pub nodes: Vec<String>,
+
+    /// Optional password of the sentinels themselves.
+    #[serde(default)]
+    pub password: Option<String>,
 }
 
 /// File stability detection configuration.
@@ -82,6 +182,274 @@ pub struct RetryConfig {
     /// Maximum number of attempts (1 = no retry, 2 = one retry).
     #[serde(default = "default_max_attempts")]
     pub max_attempts: u32,
+
+    /// Seconds a failed job waits before its first retry (0 = retry at once).
+    ///
+    /// The wait doubles with every further attempt, up to `max_delay_secs`.
+    #[serde(default = "default_retry_delay_secs")]
+    pub delay_secs: u64,
+
+    /// Longest wait before a retry, in seconds.
+    #[serde(default = "default_retry_max_delay_secs")]
+    pub max_delay_secs: u64,
+
+    /// Attempts per failure category, in place of `max_attempts`.
+    #[serde(default)]
+    pub policies: RetryPolicies,
+}
+
+impl RetryConfig {
+    /// Returns the maximum number of attempts for failures of a category.
+    pub fn max_attempts_for(&self, category: ErrorCategory) -> u32 {
+        self.policy(category)
+            .max_attempts
+            .unwrap_or(self.max_attempts)
+    }
+
+    /// Returns how long a job waits before the given attempt after failing
+    /// with an error of a category.
+    pub fn delay_for(&self, category: ErrorCategory, attempt: u32) -> Duration {
+        let base = self.policy(category).delay_secs.unwrap_or(self.delay_secs);
+        let factor = 1u64
+            .checked_shl(attempt.saturating_sub(2))
+            .unwrap_or(u64::MAX);
+        Duration::from_secs(base.saturating_mul(factor).min(self.max_delay_secs))
+    }
+
+    fn policy(&self, category: ErrorCategory) -> &RetryPolicy {
+        match category {
+            ErrorCategory::Permanent => &self.policies.permanent,
+            ErrorCategory::Transient => &self.policies.transient,
+            ErrorCategory::Resource => &self.policies.resource,
+        }
+    }
+}
+
+/// Retry policies keyed by failure category.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicies {
+    /// Missing inputs, unsupported codecs, unknown profiles.
+    #[serde(default = "default_permanent_policy")]
+    pub permanent: RetryPolicy,
+
+    /// I/O errors and encoder tools that crashed.
+    #[serde(default)]
+    pub transient: RetryPolicy,
+
+    /// Memory limits, timeouts and stalled subprocesses.
+    #[serde(default)]
+    pub resource: RetryPolicy,
+}
+
+/// How often to attempt a job whose failures fall in one category.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts (defaults to `retry.max_attempts`).
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+
+    /// Seconds before the first retry (defaults to `retry.delay_secs`).
+    #[serde(default)]
+    pub delay_secs: Option<u64>,
+}
+
+/// Retention of finished (completed, dead-lettered or cancelled) jobs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    /// Maximum number of finished jobs kept (0 = unlimited).
+    ///
+    /// Retention never deletes dead-lettered jobs, and they are not counted.
+    #[serde(default = "default_history_max_entries")]
+    pub max_entries: usize,
+
+    /// Days a finished job is kept (0 = forever).
+    #[serde(default = "default_history_max_age_days")]
+    pub max_age_days: u32,
+}
+
+/// Limits on jobs encoded at the same time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcurrencyConfig {
+    /// Maximum number of jobs running at once.
+    #[serde(default = "default_max_jobs")]
+    pub max_jobs: usize,
+
+    /// Total av1an workers shared by running jobs (0 = unlimited).
+    ///
+    /// Each job uses its profile's `workers`.
+    #[serde(default)]
+    pub worker_budget: usize,
+
+    /// Maximum number of running jobs per profile name.
+    #[serde(default)]
+    pub profile_limits: HashMap<String, usize>,
+}
+
+/// Conditions under which new jobs are held back or given fewer workers.
+///
+/// Running jobs are never interrupted; only the start of the next job is affected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThrottleConfig {
+    /// Weekly windows during which no new jobs start.
+    #[serde(default)]
+    pub pause_windows: Vec<TimeWindow>,
+
+    /// 1-minute load average above which the system is considered busy.
+    #[serde(default)]
+    pub max_load_average: Option<f64>,
+
+    /// Available memory in MiB below which the system is considered busy.
+    #[serde(default)]
+    pub min_free_memory_mb: Option<u64>,
+
+    /// Command that exits with 0 while media playback is active (program followed by arguments).
+    #[serde(default)]
+    pub playback_command: Option<Vec<String>>,
+
+    /// What to do while the system is busy.
+    #[serde(default)]
+    pub action: ThrottleAction,
+
+    /// av1an workers for jobs started while busy, with the `reduce` action.
+    #[serde(default = "default_reduced_workers")]
+    pub reduced_workers: usize,
+
+    /// Seconds between checks of load, memory and playback.
+    #[serde(default = "default_throttle_check_interval")]
+    pub check_interval_seconds: u64,
+}
+
+/// What to do while the system is busy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThrottleAction {
+    /// Start no new jobs.
+    #[default]
+    Pause,
+    /// Start new jobs with `reduced_workers`.
+    Reduce,
+}
+
+/// A recurring weekly time window in local time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeWindow {
+    /// Days the window starts on (e.g. `mon`, `sat`); empty means every day.
+    #[serde(default)]
+    pub days: Vec<chrono::Weekday>,
+
+    /// Start time as `HH:MM`.
+    pub start: String,
+
+    /// End time as `HH:MM`; earlier than `start` means the next day.
+    pub end: String,
+}
+
+/// Scheduling and resource limits applied to every encoder subprocess.
+///
+/// Profile settings take precedence over the global ones, field by field.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessLimits {
+    /// Niceness (-20 to 19; negative values need privileges).
+    #[serde(default)]
+    pub nice: Option<i32>,
+
+    /// I/O scheduling class.
+    #[serde(default)]
+    pub ionice: Option<IoniceClass>,
+
+    /// Priority within the `best_effort` class (0 highest to 7 lowest).
+    #[serde(default)]
+    pub ionice_level: Option<u8>,
+
+    /// CPUs the processes may run on, as a list like `0-7,16`.
+    #[serde(default)]
+    pub cpu_affinity: Option<String>,
+
+    /// Memory in MiB above which the job's processes are killed and the job fails.
+    #[serde(default)]
+    pub memory_ceiling_mb: Option<u64>,
+
+    /// cgroup v2 limits, applied through a cgroup per job.
+    #[serde(default)]
+    pub cgroup: Option<CgroupLimits>,
+}
+
+/// Detection of hung encoder subprocesses.
+///
+/// A subprocess that produces no output for the stall period, or a phase that
+/// runs past its time limit, is killed and the job fails as stalled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchdogConfig {
+    /// Seconds without output after which a subprocess is considered hung (0 = never).
+    #[serde(default = "default_stall_timeout")]
+    pub stall_timeout_seconds: u64,
+
+    /// Wall-clock limits per phase.
+    #[serde(default)]
+    pub phase_timeouts: PhaseTimeouts,
+}
+
+/// Wall-clock limits in seconds for each phase that runs subprocesses.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PhaseTimeouts {
+    /// Extracting subtitle streams with FFmpeg.
+    #[serde(default)]
+    pub extracting_subtitles: Option<u64>,
+
+    /// Encoding video with av1an.
+    #[serde(default)]
+    pub encoding_video: Option<u64>,
+
+    /// Burning subtitles into the video with FFmpeg.
+    #[serde(default)]
+    pub burning_subtitles: Option<u64>,
+
+    /// Processing audio with FFmpeg.
+    #[serde(default)]
+    pub processing_audio: Option<u64>,
+
+    /// Muxing the output with mkvmerge.
+    #[serde(default)]
+    pub muxing: Option<u64>,
+}
+
+/// I/O scheduling class for encoder subprocesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IoniceClass {
+    /// Normal I/O priority, tuned with `ionice_level`.
+    BestEffort,
+    /// I/O only when no other process needs the disk.
+    Idle,
+}
+
+/// cgroup v2 limits for the processes of one job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CgroupLimits {
+    /// Dedicated cgroup under which a cgroup per job is created.
+    #[serde(default = "default_cgroup_root")]
+    pub root: PathBuf,
+
+    /// CPU time available to the job, in cores (e.g. `4.5`).
+    #[serde(default)]
+    pub cpu_cores: Option<f64>,
+
+    /// Hard memory limit in MiB (`memory.max`).
+    #[serde(default)]
+    pub memory_max_mb: Option<u64>,
+}
+
+/// Spool of queue writes made while Redis is unreachable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpoolConfig {
+    /// Whether to spool writes instead of failing them.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Directory holding spooled writes; should survive restarts. Entries that
+    /// cannot be replayed are moved to its `quarantine` subdirectory.
+    #[serde(default = "default_spool_dir")]
+    pub dir: PathBuf,
 }
 
 /// Prometheus metrics configuration.
@@ -96,6 +464,51 @@ pub struct PrometheusConfig {
     pub port: u16,
 }
 
+/// HTTP control API configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    /// Whether to enable the control API.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Port for the API HTTP server.
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+
+    /// Bearer token required on every request (no auth if unset).
+    #[serde(default)]
+    pub token: Option<String>,
+
+    /// Path prefix rewrites applied to files received by import webhooks.
+    #[serde(default)]
+    pub path_mappings: Vec<PathMapping>,
+
+    /// Profiles for webhook files by local path prefix, for libraries outside
+    /// the watch folders.
+    #[serde(default)]
+    pub profile_rules: Vec<PathProfileRule>,
+}
+
+/// Selects a profile for webhook files under a path prefix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathProfileRule {
+    /// Local path prefix (after path mappings are applied).
+    pub path: PathBuf,
+
+    /// Profile used for files under the prefix.
+    pub profile: String,
+}
+
+/// Maps a path prefix on a webhook caller's filesystem to ours.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathMapping {
+    /// Prefix as seen by the caller (e.g., "/tv" inside the Sonarr container).
+    pub remote: PathBuf,
+
+    /// Corresponding local prefix (e.g., "/media/tv").
+    pub local: PathBuf,
+}
+
 /// Notification configuration.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct NotificationConfig {
@@ -139,6 +552,81 @@ pub struct DiscordEvents {
     pub on_queue_empty: bool,
 }
 
+/// A user-defined hook triggered by job lifecycle events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookConfig {
+    /// Name used in logs and error messages.
+    pub name: String,
+
+    /// Lifecycle events that trigger this hook.
+    pub events: Vec<HookEvent>,
+
+    /// Local command to run (program followed by arguments).
+    /// The job is passed as JSON on stdin.
+    #[serde(default)]
+    pub command: Option<Vec<String>>,
+
+    /// HTTP endpoint to POST the event and job to.
+    #[serde(default)]
+    pub http: Option<HttpHookConfig>,
+
+    /// Timeout for a single attempt in seconds.
+    #[serde(default = "default_hook_timeout")]
+    pub timeout_seconds: u64,
+
+    /// Number of additional attempts after a failure.
+    #[serde(default)]
+    pub retries: u32,
+
+    /// Delay between attempts in seconds.
+    #[serde(default = "default_hook_retry_delay")]
+    pub retry_delay_seconds: u64,
+
+    /// Whether a hook failure fails the job.
+    /// Only applies to the `started` and `succeeded` events.
+    #[serde(default)]
+    pub fail_job: bool,
+}
+
+/// Job lifecycle events that can trigger hooks.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// Job was added to the queue.
+    Enqueued,
+    /// Worker started processing the job.
+    Started,
+    /// Job completed successfully.
+    Succeeded,
+    /// An encode attempt failed.
+    Failed,
+    /// Job was moved to the dead letter queue.
+    DeadLettered,
+}
+
+impl std::fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Enqueued => write!(f, "enqueued"),
+            Self::Started => write!(f, "started"),
+            Self::Succeeded => write!(f, "succeeded"),
+            Self::Failed => write!(f, "failed"),
+            Self::DeadLettered => write!(f, "dead_lettered"),
+        }
+    }
+}
+
+/// HTTP hook target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpHookConfig {
+    /// URL to POST to.
+    pub url: String,
+
+    /// Extra request headers (e.g., authorization).
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
 /// An encoding profile with associated watch folder.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
@@ -163,6 +651,10 @@ pub struct Profile {
     #[serde(default)]
     pub output_naming: OutputNaming,
 
+    /// Whether to encode video or only remux and clean up tracks.
+    #[serde(default)]
+    pub mode: ProfileMode,
+
     /// Video encoder to use.
     pub encoder: Encoder,
 
@@ -178,11 +670,51 @@ pub struct Profile {
     #[serde(default = "default_workers")]
     pub workers: usize,
 
+    /// How av1an splits the source into chunks.
+    #[serde(default)]
+    pub chunk_method: ChunkMethod,
+
+    /// Settings changed on each retry, in order; later retries reuse the last step.
+    #[serde(default)]
+    pub retry_ladder: Vec<RetryStep>,
+
+    /// Rules rejecting encodes that save too little space.
+    #[serde(default)]
+    pub size_guard: Option<SizeGuard>,
+
+    /// Conditions under which a source is not re-encoded; the first match applies.
+    #[serde(default)]
+    pub skip_rules: Vec<SkipRule>,
+
     /// Audio processing configuration.
     pub audio: AudioConfig,
 
     /// Subtitle processing configuration.
     pub subtitles: SubtitleConfig,
+
+    /// Lifecycle hooks run for jobs of this profile (after global hooks).
+    #[serde(default)]
+    pub hooks: Vec<HookConfig>,
+
+    /// Weekly windows during which no new jobs of this profile start.
+    #[serde(default)]
+    pub pause_windows: Vec<TimeWindow>,
+
+    /// Subprocess limits overriding the global ones.
+    #[serde(default)]
+    pub process: ProcessLimits,
+}
+
+/// How a profile processes its sources.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileMode {
+    /// Re-encode video with av1an.
+    #[default]
+    Encode,
+    /// Keep the source video, apply audio and subtitle handling, and name and
+    /// flag the output tracks.
+    Remux,
 }
 
 /// Output file naming configuration.
@@ -256,6 +788,211 @@ impl std::fmt::Display for Encoder {
     }
 }
 
+/// av1an chunking methods.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkMethod {
+    /// L-SMASH indexing (best for MKV).
+    #[default]
+    Lsmash,
+    /// FFMS2 indexing.
+    Ffms2,
+    /// BestSource indexing (slow but accurate).
+    Bestsource,
+    /// Segmenting with FFmpeg and piping frames.
+    Hybrid,
+    /// Seeking with FFmpeg per chunk.
+    Select,
+    /// Splitting the source into files up front.
+    Segment,
+}
+
+impl std::fmt::Display for ChunkMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lsmash => write!(f, "lsmash"),
+            Self::Ffms2 => write!(f, "ffms2"),
+            Self::Bestsource => write!(f, "bestsource"),
+            Self::Hybrid => write!(f, "hybrid"),
+            Self::Select => write!(f, "select"),
+            Self::Segment => write!(f, "segment"),
+        }
+    }
+}
+
+/// Encode settings changed for a retry of a failed job.
+///
+/// Unset fields keep the profile's value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetryStep {
+    /// Chunking method to switch to.
+    #[serde(default)]
+    pub chunk_method: Option<ChunkMethod>,
+
+    /// Number of av1an workers to use.
+    #[serde(default)]
+    pub workers: Option<usize>,
+
+    /// VMAF target to use.
+    #[serde(default)]
+    pub vmaf_target: Option<f32>,
+
+    /// Video encoder to fall back to.
+    #[serde(default)]
+    pub encoder: Option<Encoder>,
+
+    /// Encoder parameters to use, usually set together with `encoder`.
+    #[serde(default)]
+    pub encoder_params: Option<String>,
+}
+
+impl RetryStep {
+    /// Returns a copy of the profile with the step's settings applied.
+    pub fn apply(&self, profile: &Profile) -> Profile {
+        let mut profile = profile.clone();
+        if let Some(chunk_method) = self.chunk_method {
+            profile.chunk_method = chunk_method;
+        }
+        if let Some(workers) = self.workers {
+            profile.workers = workers;
+        }
+        if let Some(vmaf_target) = self.vmaf_target {
+            profile.vmaf_target = vmaf_target;
+        }
+        if let Some(encoder) = &self.encoder {
+            profile.encoder = encoder.clone();
+        }
+        if let Some(encoder_params) = &self.encoder_params {
+            profile.encoder_params = encoder_params.clone();
+        }
+        profile
+    }
+}
+
+impl Profile {
+    /// Returns the av1an workers a job of this profile takes from the budget.
+    pub fn worker_cost(&self) -> usize {
+        match self.mode {
+            ProfileMode::Encode => self.workers,
+            ProfileMode::Remux => 1,
+        }
+    }
+
+    /// Returns the retry step for an attempt (1 = first attempt) and its index.
+    ///
+    /// The first attempt uses the profile as is; retries past the end of the
+    /// ladder keep using its last step.
+    pub fn retry_step(&self, attempt: u32) -> Option<(usize, &RetryStep)> {
+        let retry = usize::try_from(attempt.checked_sub(2)?).ok()?;
+        let index = retry.min(self.retry_ladder.len().checked_sub(1)?);
+        Some((index, &self.retry_ladder[index]))
+    }
+}
+
+/// Rules an encode's size must meet to replace the source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizeGuard {
+    /// Largest allowed output/input size ratio (e.g. 0.9).
+    #[serde(default)]
+    pub max_ratio: Option<f64>,
+
+    /// Smallest allowed saving in MiB.
+    #[serde(default)]
+    pub min_saving_mb: Option<u64>,
+
+    /// What to do with an encode that breaks a rule.
+    #[serde(default)]
+    pub action: SizeGuardAction,
+
+    /// VMAF points to lower the target by on each re-encode.
+    #[serde(default = "default_retarget_step")]
+    pub vmaf_step: f32,
+
+    /// Lowest VMAF target to re-encode with; below it the source is kept.
+    #[serde(default = "default_retarget_floor")]
+    pub vmaf_floor: f32,
+}
+
+impl SizeGuard {
+    /// Returns whether an output of the given size breaks a rule.
+    pub fn violated(&self, input_size: u64, output_size: u64) -> bool {
+        let ratio_exceeded = self
+            .max_ratio
+            .is_some_and(|max| input_size == 0 || output_size as f64 / input_size as f64 > max);
+        let saving_too_small = self.min_saving_mb.is_some_and(|min| {
+            input_size.saturating_sub(output_size) < min.saturating_mul(1024 * 1024)
+        });
+        ratio_exceeded || saving_too_small
+    }
+}
+
+/// Action taken when an encode breaks a size rule.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeGuardAction {
+    /// Remux the source unchanged to the output path instead.
+    #[default]
+    KeepSource,
+    /// Mux the source video with the processed audio and subtitles.
+    RemuxOnly,
+    /// Re-encode with lower VMAF targets until the rules are met.
+    Retarget,
+    /// Fail the job and move it to the dead letter queue.
+    DeadLetter,
+}
+
+/// Source conditions under which a file is not re-encoded.
+///
+/// Every condition set must hold for the rule to match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkipRule {
+    /// Name reported when the rule matches.
+    pub name: String,
+
+    /// Source video codecs (ffprobe names, e.g. "av1", "hevc").
+    #[serde(default)]
+    pub codecs: Vec<String>,
+
+    /// Source video bitrate per pixel per frame below which the rule matches.
+    #[serde(default)]
+    pub max_bits_per_pixel: Option<f64>,
+
+    /// Source height in pixels below which the rule matches.
+    #[serde(default)]
+    pub below_height: Option<u32>,
+
+    /// Match sources carrying this pipeline's provenance tag.
+    #[serde(default)]
+    pub provenance: bool,
+
+    /// What to do with a matching source.
+    #[serde(default)]
+    pub action: SkipAction,
+}
+
+/// What to do with a source matched by a skip rule.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipAction {
+    /// Leave the source alone and write no output.
+    #[default]
+    Skip,
+    /// Remux the source to the output unchanged.
+    RemuxOnly,
+    /// Keep the source video and apply the profile's audio and subtitle handling.
+    CleanupOnly,
+}
+
+impl std::fmt::Display for SkipAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skip => write!(f, "skip"),
+            Self::RemuxOnly => write!(f, "remux_only"),
+            Self::CleanupOnly => write!(f, "cleanup_only"),
+        }
+    }
+}
+
 /// Audio processing configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioConfig {
@@ -535,10 +1272,69 @@ fn default_max_attempts() -> u32 {
     2
 }
 
+fn default_retarget_step() -> f32 {
+    1.0
+}
+
+fn default_retarget_floor() -> f32 {
+    90.0
+}
+
+fn default_permanent_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: Some(1),
+        delay_secs: None,
+    }
+}
+
+fn default_retry_delay_secs() -> u64 {
+    30
+}
+
+fn default_retry_max_delay_secs() -> u64 {
+    3600
+}
+
+fn default_history_max_entries() -> usize {
+    10_000
+}
+
+fn default_history_max_age_days() -> u32 {
+    90
+}
+
+fn default_reduced_workers() -> usize {
+    2
+}
+
+fn default_throttle_check_interval() -> u64 {
+    30
+}
+
+fn default_max_jobs() -> usize {
+    1
+}
+
+fn default_stall_timeout() -> u64 {
+    1800
+}
+
+fn default_cgroup_root() -> PathBuf {
+    PathBuf::from("/sys/fs/cgroup/encoding-pipeline")
+}
+
+fn default_spool_dir() -> PathBuf {
+    PathBuf::from("/var/lib/encoding-pipeline/spool")
+}
+
 fn default_prometheus_port() -> u16 {
     9090
 }
 
+fn default_api_port() -> u16 {
+    8080
+}
+
 fn default_true() -> bool {
     true
 }
@@ -563,6 +1359,14 @@ fn default_downmix_bitrate() -> String {
     "160k".to_string()
 }
 
+fn default_hook_timeout() -> u64 {
+    60
+}
+
+fn default_hook_retry_delay() -> u64 {
+    5
+}
+
 impl Default for StabilityConfig {
     fn default() -> Self {
         Self {
@@ -576,6 +1380,87 @@ impl Default for RetryConfig {
     fn default() -> Self {
         Self {
             max_attempts: default_max_attempts(),
+            delay_secs: default_retry_delay_secs(),
+            max_delay_secs: default_retry_max_delay_secs(),
+            policies: RetryPolicies::default(),
+        }
+    }
+}
+
+impl Default for RetryPolicies {
+    fn default() -> Self {
+        Self {
+            permanent: default_permanent_policy(),
+            transient: RetryPolicy::default(),
+            resource: RetryPolicy::default(),
+        }
+    }
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: default_history_max_entries(),
+            max_age_days: default_history_max_age_days(),
+        }
+    }
+}
+
+impl Default for RedisConfig {
+    fn default() -> Self {
+        Self {
+            host: default_redis_host(),
+            port: default_redis_port(),
+            db: 0,
+            username: None,
+            password: None,
+            url: None,
+            socket: None,
+            tls: RedisTlsConfig::default(),
+            sentinel: None,
+            namespace: None,
+        }
+    }
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            max_jobs: default_max_jobs(),
+            worker_budget: 0,
+            profile_limits: HashMap::new(),
+        }
+    }
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            pause_windows: Vec::new(),
+            max_load_average: None,
+            min_free_memory_mb: None,
+            playback_command: None,
+            action: ThrottleAction::default(),
+            reduced_workers: default_reduced_workers(),
+            check_interval_seconds: default_throttle_check_interval(),
+        }
+    }
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            stall_timeout_seconds: default_stall_timeout(),
+            phase_timeouts: PhaseTimeouts::default(),
+        }
+    }
+}
+
+impl Default for SpoolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: default_spool_dir(),
         }
     }
 }
@@ -589,6 +1474,18 @@ impl Default for PrometheusConfig {
     }
 }
 
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_api_port(),
+            token: None,
+            path_mappings: Vec::new(),
+            profile_rules: Vec::new(),
+        }
+    }
+}
+
 impl Default for DiscordEvents {
     fn default() -> Self {
         Self {
@@ -599,3 +1496,105 @@ impl Default for DiscordEvents {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(retry_ladder: Vec<RetryStep>) -> Profile {
+        let mut profile: Profile = serde_yaml::from_str(
+            "name: test\ninput_path: /in\noutput_path: /out\nencoder: svt-av1\naudio: {rules: []}\nsubtitles: {tracks: []}\n",
+        )
+        .unwrap();
+        profile.retry_ladder = retry_ladder;
+        profile
+    }
+
+    fn step(workers: usize) -> RetryStep {
+        RetryStep {
+            workers: Some(workers),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn retry_step_skips_the_first_attempt() {
+        let profile = profile(vec![step(4), step(2)]);
+
+        assert!(profile.retry_step(0).is_none());
+        assert!(profile.retry_step(1).is_none());
+        assert_eq!(
+            profile.retry_step(2).map(|(i, s)| (i, s.workers)),
+            Some((0, Some(4)))
+        );
+        assert_eq!(
+            profile.retry_step(3).map(|(i, s)| (i, s.workers)),
+            Some((1, Some(2)))
+        );
+    }
+
+    #[test]
+    fn retry_step_past_the_end_reuses_the_last_step() {
+        let profile = profile(vec![step(4), step(2)]);
+
+        for attempt in [4, 10, u32::MAX] {
+            assert_eq!(
+                profile.retry_step(attempt).map(|(i, s)| (i, s.workers)),
+                Some((1, Some(2))),
+                "attempt {}",
+                attempt
+            );
+        }
+    }
+
+    #[test]
+    fn retry_step_without_ladder_is_none() {
+        let profile = profile(Vec::new());
+
+        for attempt in [1, 2, u32::MAX] {
+            assert!(profile.retry_step(attempt).is_none());
+        }
+    }
+
+    fn guard(max_ratio: Option<f64>, min_saving_mb: Option<u64>) -> SizeGuard {
+        SizeGuard {
+            max_ratio,
+            min_saving_mb,
+            action: SizeGuardAction::default(),
+            vmaf_step: default_retarget_step(),
+            vmaf_floor: default_retarget_floor(),
+        }
+    }
+
+    #[test]
+    fn size_guard_with_a_huge_min_saving_does_not_overflow() {
+        assert!(guard(None, Some(u64::MAX)).violated(1 << 40, 0));
+        assert!(guard(None, Some(u64::MAX / 1024)).violated(10 << 30, 1 << 30));
+    }
+
+    #[test]
+    fn size_guard_rejects_any_output_of_an_empty_input() {
+        assert!(guard(Some(0.9), None).violated(0, 0));
+        assert!(guard(Some(0.9), None).violated(0, 1));
+        assert!(guard(None, Some(1)).violated(0, 0));
+        assert!(guard(Some(0.9), Some(1)).violated(0, 0));
+    }
+
+    #[test]
+    fn size_guard_without_rules_accepts_everything() {
+        assert!(!guard(None, None).violated(0, 0));
+        assert!(!guard(None, None).violated(100, 200));
+    }
+
+    #[test]
+    fn size_guard_checks_ratio_and_saving() {
+        let mib = 1024 * 1024;
+
+        assert!(!guard(Some(0.9), None).violated(100 * mib, 90 * mib));
+        assert!(guard(Some(0.9), None).violated(100 * mib, 91 * mib));
+        assert!(!guard(None, Some(10)).violated(100 * mib, 90 * mib));
+        assert!(guard(None, Some(10)).violated(100 * mib, 90 * mib + 1));
+        // Larger outputs save nothing rather than underflowing
+        assert!(guard(None, Some(1)).violated(mib, 2 * mib));
+    }
+}
@@ -0,0 +1,295 @@
+//! Interactive terminal dashboard.
+//!
+//! Polls the same Redis state as the queue CLI and renders it full-screen,
+//! with keybindings to cancel, retry and reprioritize jobs.
+
+mod ui;
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::DefaultTerminal;
+
+use crate::queue::job::EncodeJob;
+use crate::queue::QueueManager;
+use crate::watcher::stability::WatcherSnapshot;
+
+/// How often queue state is reloaded from Redis.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to wait for a key press before redrawing.
+const INPUT_POLL: Duration = Duration::from_millis(100);
+
+/// Number of recent completions shown.
+const RECENT_LIMIT: usize = 20;
+
+/// A selectable dashboard panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Panel {
+    Active,
+    Pending,
+    Recent,
+    DeadLetter,
+}
+
+impl Panel {
+    /// Panels in focus order.
+    const ALL: [Panel; 4] = [
+        Panel::Active,
+        Panel::Pending,
+        Panel::Recent,
+        Panel::DeadLetter,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|p| *p == self).unwrap_or(0)
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Panel::Active => "Active",
+            Panel::Pending => "Pending",
+            Panel::Recent => "Recent",
+            Panel::DeadLetter => "Dead letter",
+        }
+    }
+
+    fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Queue state loaded from Redis.
+#[derive(Debug, Default)]
+struct Snapshot {
+    processing: Vec<EncodeJob>,
+    pending: Vec<EncodeJob>,
+    recent: Vec<EncodeJob>,
+    dead_letter: Vec<EncodeJob>,
+    watcher: Option<WatcherSnapshot>,
+}
+
+/// Dashboard state.
+struct App {
+    queue: QueueManager,
+    snapshot: Snapshot,
+    focus: Panel,
+    selected: [usize; 4],
+    status: Option<String>,
+    /// Job awaiting confirmation before it is cancelled.
+    pending_cancel: Option<String>,
+    last_refresh: Option<Instant>,
+    quit: bool,
+}
+
+impl App {
+    fn new(queue: QueueManager) -> Self {
+        Self {
+            queue,
+            snapshot: Snapshot::default(),
+            focus: Panel::Active,
+            selected: [0; 4],
+            status: None,
+            pending_cancel: None,
+            last_refresh: None,
+            quit: false,
+        }
+    }
+
+    /// Jobs listed in a panel.
+    fn jobs(&self, panel: Panel) -> &[EncodeJob] {
+        match panel {
+            Panel::Active => &self.snapshot.processing,
+            Panel::Pending => &self.snapshot.pending,
+            Panel::Recent => &self.snapshot.recent,
+            Panel::DeadLetter => &self.snapshot.dead_letter,
+        }
+    }
+
+    /// Selected row of a panel, if it has any jobs.
+    fn selection(&self, panel: Panel) -> Option<usize> {
+        let len = self.jobs(panel).len();
+        (len > 0).then(|| self.selected[panel.index()].min(len - 1))
+    }
+
+    /// The selected job of the focused panel.
+    fn selected_job(&self) -> Option<&EncodeJob> {
+        self.selection(self.focus)
+            .map(|i| &self.jobs(self.focus)[i])
+    }
+
+    /// Reloads queue state from Redis.
+    async fn refresh(&mut self) {
+        match self.load().await {
+            Ok(snapshot) => self.snapshot = snapshot,
+            Err(e) => self.status = Some(format!("Refresh failed: {}", e)),
+        }
+        self.last_refresh = Some(Instant::now());
+    }
+
+    async fn load(&mut self) -> Result<Snapshot> {
+        Ok(Snapshot {
+            processing: self.queue.list_processing().await?,
+            pending: self.queue.list_queue().await?,
+            recent: self.queue.list_recent_completed(RECENT_LIMIT).await?,
+            dead_letter: self.queue.list_dead_letter().await?,
+            watcher: self.queue.watcher_state().await?,
+        })
+    }
+
+    /// Handles a key press.
+    async fn on_key(&mut self, key: KeyEvent) {
+        if let Some(job_id) = self.pending_cancel.take() {
+            if key.code == KeyCode::Char('y') {
+                self.cancel(job_id).await;
+            } else {
+                self.status = Some(format!("Kept {}", job_id));
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::BackTab => self.focus = self.focus.previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Char('c') => self.confirm_cancel(),
+            KeyCode::Char('r') => self.retry().await,
+            KeyCode::Char('+') | KeyCode::Char('=') => self.reprioritize(1).await,
+            KeyCode::Char('-') => self.reprioritize(-1).await,
+            _ => {}
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let Some(current) = self.selection(self.focus) else {
+            return;
+        };
+        let last = self.jobs(self.focus).len() - 1;
+        self.selected[self.focus.index()] = current.saturating_add_signed(delta).min(last);
+    }
+
+    /// Asks to confirm cancelling the selected active or pending job.
+    ///
+    /// Cancelling stops a running encode, so it needs a second key press.
+    fn confirm_cancel(&mut self) {
+        if !matches!(self.focus, Panel::Active | Panel::Pending) {
+            self.status = Some("Only active or pending jobs can be cancelled".to_string());
+            return;
+        }
+        let Some(job_id) = self.selected_job().map(|j| j.id.clone()) else {
+            return;
+        };
+
+        self.status = Some(format!(
+            "Cancel {}? Press y to confirm, any other key to keep it",
+            job_id
+        ));
+        self.pending_cancel = Some(job_id);
+    }
+
+    /// Cancels a job.
+    async fn cancel(&mut self, job_id: String) {
+        self.status = Some(match self.queue.cancel_job(&job_id).await {
+            Ok(_) => format!("Cancel requested for {}", job_id),
+            Err(e) => format!("Cancel failed: {}", e),
+        });
+        self.refresh().await;
+    }
+
+    /// Requeues the selected dead-lettered job.
+    ///
+    /// Completed jobs are not retried from here, so a stray key press cannot
+    /// re-encode a finished file.
+    async fn retry(&mut self) {
+        if self.focus != Panel::DeadLetter {
+            self.status = Some("Only dead-lettered jobs can be retried".to_string());
+            return;
+        }
+        let Some(job_id) = self.selected_job().map(|j| j.id.clone()) else {
+            return;
+        };
+
+        self.status = Some(match self.queue.retry_dead_letter(&job_id).await {
+            Ok(_) => format!("Requeued {}", job_id),
+            Err(e) => format!("Retry failed: {}", e),
+        });
+        self.refresh().await;
+    }
+
+    /// Raises or lowers the priority of the selected pending job.
+    async fn reprioritize(&mut self, delta: i32) {
+        if self.focus != Panel::Pending {
+            self.status = Some("Only pending jobs can be reprioritized".to_string());
+            return;
+        }
+        let Some((job_id, priority)) = self.selected_job().map(|j| (j.id.clone(), j.priority))
+        else {
+            return;
+        };
+
+        self.status = Some(
+            match self
+                .queue
+                .reprioritize(&job_id, priority.saturating_add(delta))
+                .await
+            {
+                Ok(job) => {
+                    self.refresh().await;
+                    // Keep the moved job selected
+                    if let Some(i) = self.snapshot.pending.iter().position(|j| j.id == job.id) {
+                        self.selected[Panel::Pending.index()] = i;
+                    }
+                    format!("Priority of {} set to {}", job.id, job.priority)
+                }
+                Err(e) => format!("Reprioritize failed: {}", e),
+            },
+        );
+    }
+}
+
+/// Runs the dashboard until the user quits.
+pub async fn run(queue: QueueManager) -> Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, App::new(queue)).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(terminal: &mut DefaultTerminal, mut app: App) -> Result<()> {
+    while !app.quit {
+        if app
+            .last_refresh
+            .is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL)
+        {
+            app.refresh().await;
+        }
+
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        // Terminal input blocks, so it is read off the async runtime
+        if let Some(key) = tokio::task::spawn_blocking(read_key).await?? {
+            app.on_key(key).await;
+        }
+    }
+    Ok(())
+}
+
+/// Waits up to `INPUT_POLL` for a key press.
+fn read_key() -> std::io::Result<Option<KeyEvent>> {
+    if event::poll(INPUT_POLL)? {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                return Ok(Some(key));
+            }
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,274 @@
+//! Dashboard rendering.
+
+use chrono::Utc;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+
+use super::{App, Panel};
+use crate::queue::job::EncodeJob;
+
+/// Width of the text progress bar in the active panel.
+const BAR_WIDTH: usize = 20;
+
+/// Draws the whole dashboard.
+pub(super) fn draw(frame: &mut Frame, app: &App) {
+    let active_height = app.snapshot.processing.len().max(1) as u16 + 3;
+    let [active, middle, bottom, status] = Layout::vertical([
+        Constraint::Length(active_height),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+    let [pending, watcher] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(middle);
+    let [recent, dead_letter] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(bottom);
+
+    draw_active(frame, app, active);
+    draw_pending(frame, app, pending);
+    draw_watcher(frame, app, watcher);
+    draw_recent(frame, app, recent);
+    draw_dead_letter(frame, app, dead_letter);
+    draw_status(frame, app, status);
+}
+
+fn draw_active(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.snapshot.processing.iter().map(|job| {
+        let percent = job.progress.unwrap_or(0.0);
+        let detail = job.progress_detail.as_ref();
+        Row::new(vec![
+            Cell::from(file_name(job)),
+            Cell::from(detail.map(|d| d.phase.clone()).unwrap_or_default()),
+            Cell::from(progress_bar(percent)).green(),
+            Cell::from(
+                detail
+                    .and_then(|d| d.speed.clone())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Cell::from(
+                detail
+                    .and_then(|d| d.eta.clone())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+        ])
+    });
+
+    let widths = [
+        Constraint::Fill(1),
+        Constraint::Length(20),
+        Constraint::Length(BAR_WIDTH as u16 + 8),
+        Constraint::Length(12),
+        Constraint::Length(10),
+    ];
+    let header = ["File", "Phase", "Progress", "Speed", "ETA"];
+    let table = Table::new(rows, widths).header(header_row(&header));
+    render_table(frame, app, Panel::Active, area, table);
+}
+
+fn draw_pending(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.snapshot.pending.iter().enumerate().map(|(i, job)| {
+        Row::new(vec![
+            Cell::from((i + 1).to_string()),
+            Cell::from(job.priority.to_string()),
+            Cell::from(file_name(job)),
+            Cell::from(job.profile_name.clone()),
+        ])
+    });
+
+    let widths = [
+        Constraint::Length(4),
+        Constraint::Length(4),
+        Constraint::Fill(1),
+        Constraint::Length(12),
+    ];
+    let header = ["#", "Pri", "File", "Profile"];
+    let table = Table::new(rows, widths).header(header_row(&header));
+    render_table(frame, app, Panel::Pending, area, table);
+}
+
+fn draw_recent(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.snapshot.recent.iter().map(|job| {
+        let result = job.result_metadata.as_ref();
+        let finished = job.completed_at.unwrap_or(job.updated_at);
+        Row::new(vec![
+            Cell::from(
+                finished
+                    .with_timezone(&chrono::Local)
+                    .format("%m-%d %H:%M")
+                    .to_string(),
+            ),
+            Cell::from(file_name(job)),
+            Cell::from(match result {
+                Some(r) => format!("{:.1}%", r.size_reduction_percent()),
+                None => "-".to_string(),
+            }),
+            Cell::from(
+                result
+                    .and_then(|r| r.vmaf_score)
+                    .map(|v| format!("{:.2}", v))
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Cell::from(
+                result
+                    .map(|r| format!("{:.2}x", r.encoding_speed))
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+        ])
+    });
+
+    let widths = [
+        Constraint::Length(11),
+        Constraint::Fill(1),
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Length(7),
+    ];
+    let header = ["Finished", "File", "Saved", "VMAF", "Speed"];
+    let table = Table::new(rows, widths).header(header_row(&header));
+    render_table(frame, app, Panel::Recent, area, table);
+}
+
+fn draw_dead_letter(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.snapshot.dead_letter.iter().map(|job| {
+        Row::new(vec![
+            Cell::from(file_name(job)),
+            Cell::from(
+                job.error_message
+                    .clone()
+                    .unwrap_or_else(|| "Unknown error".to_string()),
+            )
+            .red(),
+        ])
+    });
+
+    let widths = [Constraint::Percentage(50), Constraint::Percentage(50)];
+    let header = ["File", "Error"];
+    let table = Table::new(rows, widths).header(header_row(&header));
+    render_table(frame, app, Panel::DeadLetter, area, table);
+}
+
+fn draw_watcher(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::bordered().title(" Watcher ");
+
+    let Some(watcher) = &app.snapshot.watcher else {
+        let text = Paragraph::new("No watcher state (is the pipeline running?)")
+            .dark_gray()
+            .block(block);
+        frame.render_widget(text, area);
+        return;
+    };
+
+    let age = (Utc::now() - watcher.updated_at).num_seconds().max(0);
+    let block = block.title(format!(
+        " {} tracked, updated {}s ago ",
+        watcher.files.len(),
+        age
+    ));
+
+    let rows = watcher.files.iter().map(|file| {
+        let name = file
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.path.display().to_string());
+        let stability = match file.stable_seconds {
+            Some(secs) => format!("{}/{}s", secs, watcher.stability_seconds),
+            None => "growing".to_string(),
+        };
+        Row::new(vec![
+            Cell::from(name),
+            Cell::from(format_bytes(file.size)),
+            Cell::from(stability),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Length(10),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header_row(&["File", "Size", "Stable"]))
+    .block(block);
+    frame.render_widget(table, area);
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let counts = Line::from(vec![
+        Span::raw(format!(
+            " {} active  {} pending  {} dead letter ",
+            app.snapshot.processing.len(),
+            app.snapshot.pending.len(),
+            app.snapshot.dead_letter.len()
+        )),
+        Span::styled(
+            app.status.clone().unwrap_or_default(),
+            Style::default().fg(Color::Yellow),
+        ),
+    ]);
+    let help =
+        Line::from(" Tab focus  j/k select  c cancel  r retry  +/- priority  q quit ".dark_gray());
+    frame.render_widget(Paragraph::new(vec![counts, help]), area);
+}
+
+/// Renders a job table, highlighting the selection when the panel has focus.
+fn render_table(frame: &mut Frame, app: &App, panel: Panel, area: Rect, table: Table) {
+    let focused = app.focus == panel;
+    let border_style = if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+    let block = Block::bordered()
+        .title(format!(" {} ({}) ", panel.title(), app.jobs(panel).len()))
+        .border_style(border_style);
+
+    let table = table
+        .block(block)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    let mut state =
+        TableState::default().with_selected(if focused { app.selection(panel) } else { None });
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn header_row<'a>(titles: &[&'a str]) -> Row<'a> {
+    Row::new(titles.iter().copied()).bold()
+}
+
+/// File name of a job's input.
+fn file_name(job: &EncodeJob) -> String {
+    job.input_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| job.input_path.display().to_string())
+}
+
+/// Renders a text progress bar with its percentage.
+fn progress_bar(percent: f32) -> String {
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * BAR_WIDTH as f32).round() as usize;
+    format!(
+        "{}{} {:5.1}%",
+        "█".repeat(filled),
+        "░".repeat(BAR_WIDTH - filled),
+        percent
+    )
+}
+
+/// Formats a byte count with a binary unit.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
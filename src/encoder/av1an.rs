@@ -3,12 +3,14 @@
 use std::path::Path;
 use std::process::Stdio;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
 use tokio::sync::mpsc;
-use tracing::{debug, error, info};
+use tracing::{debug, info};
 
+use super::command::CommandLine;
+use super::limits::ProcessControl;
+use super::watchdog::Activity;
 use crate::config::model::{Encoder, Profile};
 use crate::error::EncoderError;
 
@@ -27,39 +29,25 @@ pub struct EncodeProgress {
     pub total_frames: Option<u64>,
 }
 
-/// Encodes video using av1an with VMAF targeting.
-pub async fn encode(
+/// Builds the av1an command line for encoding video with VMAF targeting.
+pub fn encode_command(
     input: &Path,
     output: &Path,
+    temp_dir: &Path,
     profile: &Profile,
-    progress_tx: Option<mpsc::Sender<EncodeProgress>>,
-) -> Result<(), EncoderError> {
-    let temp_dir = std::env::temp_dir().join(format!(
-        "av1an_{}",
-        uuid::Uuid::new_v4()
-    ));
-
-    std::fs::create_dir_all(&temp_dir).map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
-
-    // Build av1an command
-    let mut cmd = Command::new("av1an");
+) -> CommandLine {
+    let mut cmd = CommandLine::new("av1an");
 
     cmd.arg("-i").arg(input);
     cmd.arg("-o").arg(output);
-    cmd.arg("--temp").arg(&temp_dir);
+    cmd.arg("--temp").arg(temp_dir);
 
     // Set encoder
-    let encoder_name = match profile.encoder {
-        Encoder::X265 => "x265",
-        Encoder::X264 => "x264",
-        Encoder::SvtAv1 => "svt-av1",
-        Encoder::Aomenc => "aom",
-        Encoder::Rav1e => "rav1e",
-    };
-    cmd.arg("--encoder").arg(encoder_name);
+    cmd.arg("--encoder").arg(encoder_name(&profile.encoder));
 
     // Set VMAF target
-    cmd.arg("--target-quality").arg(profile.vmaf_target.to_string());
+    cmd.arg("--target-quality")
+        .arg(profile.vmaf_target.to_string());
     cmd.arg("--target-metric").arg("vmaf");
 
     // Set workers
@@ -70,29 +58,77 @@ pub async fn encode(
         cmd.arg("-v").arg(&profile.encoder_params);
     }
 
-    // Use lsmash for chunking (best for MKV)
-    cmd.arg("--chunk-method").arg("lsmash");
+    // Set chunking method (lsmash unless the profile or a retry step changes it)
+    cmd.arg("--chunk-method")
+        .arg(profile.chunk_method.to_string());
 
     // Enable resume in case of interruption
     cmd.arg("--resume");
 
+    cmd
+}
+
+/// Returns av1an's name for an encoder.
+fn encoder_name(encoder: &Encoder) -> &'static str {
+    match encoder {
+        Encoder::X265 => "x265",
+        Encoder::X264 => "x264",
+        Encoder::SvtAv1 => "svt-av1",
+        Encoder::Aomenc => "aom",
+        Encoder::Rav1e => "rav1e",
+    }
+}
+
+/// Encodes video using av1an with VMAF targeting.
+///
+/// `temp_dir` holds av1an's chunks and is removed afterwards.
+pub async fn encode(
+    input: &Path,
+    output: &Path,
+    temp_dir: &Path,
+    profile: &Profile,
+    limits: &ProcessControl,
+    progress_tx: Option<mpsc::Sender<EncodeProgress>>,
+) -> Result<(), EncoderError> {
+    std::fs::create_dir_all(temp_dir).map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+
+    let mut cmd = encode_command(input, output, temp_dir, profile).to_command();
+
     // Configure output
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
+    // Kill av1an if the encode is abandoned (e.g., job cancelled)
+    cmd.kill_on_drop(true);
+    limits.apply(&mut cmd);
+
     info!(
         input = ?input,
         output = ?output,
-        encoder = encoder_name,
+        encoder = encoder_name(&profile.encoder),
         vmaf_target = profile.vmaf_target,
+        chunk_method = %profile.chunk_method,
+        workers = profile.workers,
         "Starting av1an encode"
     );
 
-    let mut child = cmd.spawn().map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+    let activity = Activity::new();
+
+    // Drain stdout so av1an never blocks on a full pipe
+    if let Some(stdout) = child.stdout.take() {
+        let mut stdout = activity.track(stdout);
+        tokio::spawn(async move {
+            let _ = tokio::io::copy(&mut stdout, &mut tokio::io::sink()).await;
+        });
+    }
 
     // Read stderr for progress
     if let Some(stderr) = child.stderr.take() {
         let progress_tx = progress_tx.clone();
+        let stderr = activity.track(stderr);
         tokio::spawn(async move {
             let reader = BufReader::new(stderr);
             let mut lines = reader.lines();
@@ -109,13 +145,10 @@ pub async fn encode(
         });
     }
 
-    let status = child
-        .wait()
-        .await
-        .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+    let status = limits.wait(&mut child, "encoding_video", &activity).await?;
 
     // Clean up temp directory
-    if let Err(e) = std::fs::remove_dir_all(&temp_dir) {
+    if let Err(e) = std::fs::remove_dir_all(temp_dir) {
         debug!(error = %e, "Failed to clean up temp directory");
     }
 
@@ -146,12 +179,11 @@ fn parse_progress(line: &str) -> Option<EncodeProgress> {
     let percent = extract_percentage(line)?;
 
     // Try to extract speed
-    let speed = extract_pattern(line, "speed:", "x")
-        .or_else(|| extract_pattern(line, "fps:", " "));
+    let speed = extract_pattern(line, "speed:", "x").or_else(|| extract_pattern(line, "fps:", " "));
 
     // Try to extract ETA
-    let eta = extract_pattern(line, "eta:", " ")
-        .or_else(|| extract_pattern(line, "remaining:", " "));
+    let eta =
+        extract_pattern(line, "eta:", " ").or_else(|| extract_pattern(line, "remaining:", " "));
 
     // Try to extract frame info
     let frame = extract_number_after(line, "frame");
@@ -0,0 +1,84 @@
+//! External command lines that can be both run and displayed.
+
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+use tokio::process::Command;
+
+/// A program and its arguments.
+///
+/// The subprocess wrappers build one of these and run it, so the plan
+/// command prints exactly what a real encode would execute.
+#[derive(Debug, Clone)]
+pub struct CommandLine {
+    /// Program name.
+    program: String,
+    /// Arguments in order.
+    args: Vec<OsString>,
+}
+
+impl CommandLine {
+    /// Creates a command line with no arguments.
+    pub fn new(program: &str) -> Self {
+        Self {
+            program: program.to_string(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Appends an argument.
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    /// Returns the program name.
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    /// Builds a tokio command ready to spawn.
+    pub fn to_command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(&self.args);
+        cmd
+    }
+}
+
+impl fmt::Display for CommandLine {
+    /// Formats the command line with POSIX shell quoting.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", shell_quote(&self.program))?;
+        for arg in &self.args {
+            write!(f, " {}", shell_quote(&arg.to_string_lossy()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for CommandLine {
+    /// Serializes as an argv-style list of strings.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.args.len() + 1))?;
+        seq.serialize_element(&self.program)?;
+        for arg in &self.args {
+            seq.serialize_element(&arg.to_string_lossy())?;
+        }
+        seq.end()
+    }
+}
+
+/// Quotes a word for a POSIX shell if it contains special characters.
+fn shell_quote(word: &str) -> String {
+    let is_safe = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:=,+@%".contains(c));
+
+    if is_safe {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
@@ -1,23 +1,19 @@
 //! FFmpeg subprocess wrapper for audio processing.
 
-use std::path::Path;
-use std::process::Stdio;
-
 use anyhow::Result;
-use tokio::process::Command;
+use serde::Serialize;
+use std::path::Path;
 use tracing::{debug, info};
 
+use super::command::CommandLine;
+use super::limits::ProcessControl;
 use crate::error::EncoderError;
 use crate::media::audio::{AudioDecision, AudioTrackAction};
 use crate::media::subtitle::{SubtitleDecision, SubtitleTrackAction};
 
-/// Processes audio tracks according to the given decisions.
-pub async fn process_audio(
-    input: &Path,
-    output: &Path,
-    decisions: &[AudioDecision],
-) -> Result<(), EncoderError> {
-    let mut cmd = Command::new("ffmpeg");
+/// Builds the FFmpeg command line that produces the audio tracks for the decisions.
+pub fn audio_command(input: &Path, output: &Path, decisions: &[AudioDecision]) -> CommandLine {
+    let mut cmd = CommandLine::new("ffmpeg");
 
     cmd.arg("-y"); // Overwrite output
     cmd.arg("-i").arg(input);
@@ -37,12 +33,16 @@ pub async fn process_audio(
 
             AudioTrackAction::Transcode { codec, bitrate } => {
                 cmd.arg("-map").arg(format!("0:{}", decision.stream.index));
-                cmd.arg(format!("-c:a:{}", audio_index)).arg(normalize_codec(codec));
+                cmd.arg(format!("-c:a:{}", audio_index))
+                    .arg(normalize_codec(codec));
                 cmd.arg(format!("-b:a:{}", audio_index)).arg(bitrate);
                 audio_index += 1;
             }
 
-            AudioTrackAction::PassthroughWithDownmix { downmix_codec, downmix_bitrate } => {
+            AudioTrackAction::PassthroughWithDownmix {
+                downmix_codec,
+                downmix_bitrate,
+            } => {
                 // Original track
                 cmd.arg("-map").arg(format!("0:{}", decision.stream.index));
                 cmd.arg(format!("-c:a:{}", audio_index)).arg("copy");
@@ -50,24 +50,34 @@ pub async fn process_audio(
 
                 // Downmixed stereo track
                 cmd.arg("-map").arg(format!("0:{}", decision.stream.index));
-                cmd.arg(format!("-c:a:{}", audio_index)).arg(normalize_codec(downmix_codec));
+                cmd.arg(format!("-c:a:{}", audio_index))
+                    .arg(normalize_codec(downmix_codec));
                 cmd.arg(format!("-ac:{}", audio_index)).arg("2");
-                cmd.arg(format!("-b:a:{}", audio_index)).arg(downmix_bitrate);
+                cmd.arg(format!("-b:a:{}", audio_index))
+                    .arg(downmix_bitrate);
                 audio_index += 1;
             }
 
-            AudioTrackAction::TranscodeWithDownmix { codec, bitrate, downmix_codec, downmix_bitrate } => {
+            AudioTrackAction::TranscodeWithDownmix {
+                codec,
+                bitrate,
+                downmix_codec,
+                downmix_bitrate,
+            } => {
                 // Transcoded track
                 cmd.arg("-map").arg(format!("0:{}", decision.stream.index));
-                cmd.arg(format!("-c:a:{}", audio_index)).arg(normalize_codec(codec));
+                cmd.arg(format!("-c:a:{}", audio_index))
+                    .arg(normalize_codec(codec));
                 cmd.arg(format!("-b:a:{}", audio_index)).arg(bitrate);
                 audio_index += 1;
 
                 // Downmixed stereo track
                 cmd.arg("-map").arg(format!("0:{}", decision.stream.index));
-                cmd.arg(format!("-c:a:{}", audio_index)).arg(normalize_codec(downmix_codec));
+                cmd.arg(format!("-c:a:{}", audio_index))
+                    .arg(normalize_codec(downmix_codec));
                 cmd.arg(format!("-ac:{}", audio_index)).arg("2");
-                cmd.arg(format!("-b:a:{}", audio_index)).arg(downmix_bitrate);
+                cmd.arg(format!("-b:a:{}", audio_index))
+                    .arg(downmix_bitrate);
                 audio_index += 1;
             }
         }
@@ -78,14 +88,21 @@ pub async fn process_audio(
 
     cmd.arg(output);
 
-    debug!(cmd = ?cmd, "Running FFmpeg for audio");
+    cmd
+}
+
+/// Processes audio tracks according to the given decisions.
+pub async fn process_audio(
+    input: &Path,
+    output: &Path,
+    decisions: &[AudioDecision],
+    limits: &ProcessControl,
+) -> Result<(), EncoderError> {
+    let cmd = audio_command(input, output, decisions);
+
+    debug!(cmd = %cmd, "Running FFmpeg for audio");
 
-    let output_result = cmd
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .await
-        .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+    let output_result = limits.output(cmd.to_command(), "processing_audio").await?;
 
     if !output_result.status.success() {
         let stderr = String::from_utf8_lossy(&output_result.stderr);
@@ -99,54 +116,69 @@ pub async fn process_audio(
     Ok(())
 }
 
+/// Returns the subtitle files extraction will produce for the decisions.
+pub fn planned_subtitles(
+    output_dir: &Path,
+    decisions: &[SubtitleDecision],
+) -> Vec<ExtractedSubtitle> {
+    decisions
+        .iter()
+        .filter(|d| !matches!(d.action, SubtitleTrackAction::Exclude))
+        .map(|decision| {
+            let stream = &decision.stream;
+            let ext = if stream.is_image_based { "sup" } else { "srt" };
+            let path = output_dir.join(format!(
+                "sub_{}_{}.{}",
+                stream.index,
+                stream.language.as_deref().unwrap_or("und"),
+                ext
+            ));
+
+            ExtractedSubtitle {
+                path,
+                stream_index: stream.index,
+                language: stream.language.clone(),
+                is_forced: stream.is_forced,
+                is_default: stream.is_default,
+                should_burn_in: matches!(decision.action, SubtitleTrackAction::BurnIn),
+            }
+        })
+        .collect()
+}
+
+/// Builds the FFmpeg command line that extracts one subtitle stream.
+pub fn extract_subtitle_command(input: &Path, subtitle: &ExtractedSubtitle) -> CommandLine {
+    let mut cmd = CommandLine::new("ffmpeg");
+    cmd.arg("-y");
+    cmd.arg("-i").arg(input);
+    cmd.arg("-map").arg(format!("0:{}", subtitle.stream_index));
+    cmd.arg("-c:s").arg("copy");
+    cmd.arg(&subtitle.path);
+    cmd
+}
+
 /// Extracts subtitles to separate files.
 pub async fn extract_subtitles(
     input: &Path,
     output_dir: &Path,
     decisions: &[SubtitleDecision],
+    limits: &ProcessControl,
 ) -> Result<Vec<ExtractedSubtitle>, EncoderError> {
     let mut extracted = Vec::new();
 
-    for decision in decisions {
-        if matches!(decision.action, SubtitleTrackAction::Exclude) {
-            continue;
-        }
-
-        let stream = &decision.stream;
-        let ext = if stream.is_image_based { "sup" } else { "srt" };
-        let output_file = output_dir.join(format!(
-            "sub_{}_{}.{}",
-            stream.index,
-            stream.language.as_deref().unwrap_or("und"),
-            ext
-        ));
-
-        let mut cmd = Command::new("ffmpeg");
-        cmd.arg("-y");
-        cmd.arg("-i").arg(input);
-        cmd.arg("-map").arg(format!("0:{}", stream.index));
-        cmd.arg("-c:s").arg("copy");
-        cmd.arg(&output_file);
-
-        let output_result = cmd
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await
-            .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+    for subtitle in planned_subtitles(output_dir, decisions) {
+        let output_result = limits
+            .output(
+                extract_subtitle_command(input, &subtitle).to_command(),
+                "extracting_subtitles",
+            )
+            .await?;
 
         if output_result.status.success() {
-            extracted.push(ExtractedSubtitle {
-                path: output_file,
-                stream_index: stream.index,
-                language: stream.language.clone(),
-                is_forced: stream.is_forced,
-                is_default: stream.is_default,
-                should_burn_in: matches!(decision.action, SubtitleTrackAction::BurnIn),
-            });
+            extracted.push(subtitle);
         } else {
             debug!(
-                stream_index = stream.index,
+                stream_index = subtitle.stream_index,
                 "Failed to extract subtitle stream"
             );
         }
@@ -156,7 +188,7 @@ pub async fn extract_subtitles(
 }
 
 /// An extracted subtitle file.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ExtractedSubtitle {
     /// Path to the extracted subtitle file.
     pub path: std::path::PathBuf,
@@ -172,14 +204,14 @@ pub struct ExtractedSubtitle {
     pub should_burn_in: bool,
 }
 
-/// Burns subtitles into a video.
-pub async fn burn_subtitles(
+/// Builds the FFmpeg command line that burns subtitles into a video.
+pub fn burn_subtitles_command(
     input: &Path,
     subtitle: &Path,
     output: &Path,
     is_image_based: bool,
-) -> Result<(), EncoderError> {
-    let mut cmd = Command::new("ffmpeg");
+) -> CommandLine {
+    let mut cmd = CommandLine::new("ffmpeg");
     cmd.arg("-y");
     cmd.arg("-i").arg(input);
 
@@ -192,7 +224,10 @@ pub async fn burn_subtitles(
         cmd.arg("-c:a").arg("copy");
     } else {
         // For text subtitles, use subtitles filter
-        let subtitle_path = subtitle.to_string_lossy().replace('\\', "/").replace(':', "\\:");
+        let subtitle_path = subtitle
+            .to_string_lossy()
+            .replace('\\', "/")
+            .replace(':', "\\:");
         cmd.arg("-vf").arg(format!("subtitles='{}'", subtitle_path));
         cmd.arg("-map").arg("0:a");
         cmd.arg("-c:a").arg("copy");
@@ -200,12 +235,23 @@ pub async fn burn_subtitles(
 
     cmd.arg(output);
 
-    let output_result = cmd
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .await
-        .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+    cmd
+}
+
+/// Burns subtitles into a video.
+pub async fn burn_subtitles(
+    input: &Path,
+    subtitle: &Path,
+    output: &Path,
+    is_image_based: bool,
+    limits: &ProcessControl,
+) -> Result<(), EncoderError> {
+    let output_result = limits
+        .output(
+            burn_subtitles_command(input, subtitle, output, is_image_based).to_command(),
+            "burning_subtitles",
+        )
+        .await?;
 
     if !output_result.status.success() {
         let stderr = String::from_utf8_lossy(&output_result.stderr);
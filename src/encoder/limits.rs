@@ -0,0 +1,528 @@
+//! Scheduling and resource limits for encoder subprocesses.
+//!
+//! Niceness, I/O class, CPU affinity and cgroup membership are set in the
+//! child before it runs the program, so every process it starts inherits
+//! them. The memory ceiling is enforced by polling the job's memory use and
+//! killing its processes, so the job fails on its own instead of the OOM
+//! killer taking down the container. Subprocesses that stop producing output
+//! are killed by the watchdog.
+
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output, Stdio};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::{Child, Command};
+use tracing::{debug, warn};
+
+use super::watchdog::Activity;
+use crate::config::model::{CgroupLimits, IoniceClass, ProcessLimits};
+use crate::error::EncoderError;
+
+/// How often memory use is checked against the ceiling.
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Scheduling period used for the cgroup CPU quota, in microseconds.
+const CPU_PERIOD_US: u64 = 100_000;
+
+/// `ioprio_set` target type for a single process.
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+
+/// Bit offset of the class in an I/O priority.
+const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+/// Limits for the subprocesses of one job.
+#[derive(Debug, Default)]
+pub struct ProcessControl {
+    /// Effective limits (profile over global).
+    limits: ProcessLimits,
+    /// Parsed CPU affinity set.
+    cpus: Option<Vec<usize>>,
+    /// The job's cgroup, once created.
+    cgroup: Option<PathBuf>,
+    /// `cgroup.procs` of the job's cgroup, for joining it in the child.
+    cgroup_procs: Option<CString>,
+    /// Time without output after which a subprocess is killed.
+    stall_timeout: Option<Duration>,
+}
+
+impl ProcessControl {
+    /// Combines the global limits with a profile's, the profile taking precedence.
+    pub fn new(global: &ProcessLimits, profile: &ProcessLimits) -> Result<Self, EncoderError> {
+        let limits = ProcessLimits {
+            nice: profile.nice.or(global.nice),
+            ionice: profile.ionice.or(global.ionice),
+            ionice_level: profile.ionice_level.or(global.ionice_level),
+            cpu_affinity: profile
+                .cpu_affinity
+                .clone()
+                .or_else(|| global.cpu_affinity.clone()),
+            memory_ceiling_mb: profile.memory_ceiling_mb.or(global.memory_ceiling_mb),
+            cgroup: profile.cgroup.clone().or_else(|| global.cgroup.clone()),
+        };
+
+        let cpus = limits
+            .cpu_affinity
+            .as_deref()
+            .map(parse_cpu_list)
+            .transpose()
+            .map_err(EncoderError::SpawnFailed)?;
+
+        Ok(Self {
+            limits,
+            cpus,
+            cgroup: None,
+            cgroup_procs: None,
+            stall_timeout: None,
+        })
+    }
+
+    /// Kills subprocesses silent for the given number of seconds (0 = never).
+    pub fn with_stall_timeout(mut self, seconds: u64) -> Self {
+        self.stall_timeout = (seconds > 0).then(|| Duration::from_secs(seconds));
+        self
+    }
+
+    /// Creates the job's cgroup, if cgroup limits are set.
+    pub fn create_cgroup(&mut self, job_id: &str) -> Result<(), EncoderError> {
+        let Some(limits) = &self.limits.cgroup else {
+            return Ok(());
+        };
+
+        let path = setup_cgroup(limits, job_id).map_err(|e| {
+            EncoderError::SpawnFailed(format!(
+                "Failed to set up cgroup under '{}': {}",
+                limits.root.display(),
+                e
+            ))
+        })?;
+        let procs = CString::new(path.join("cgroup.procs").as_os_str().as_bytes())
+            .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+
+        debug!(cgroup = ?path, "Created job cgroup");
+        self.cgroup = Some(path);
+        self.cgroup_procs = Some(procs);
+        Ok(())
+    }
+
+    /// Removes the job's cgroup, killing any process left in it.
+    pub async fn remove_cgroup(&mut self) {
+        self.cgroup_procs = None;
+        let Some(path) = self.cgroup.take() else {
+            return;
+        };
+
+        let _ = std::fs::write(path.join("cgroup.kill"), "1");
+        // Killed processes leave the cgroup once they are reaped
+        for _ in 0..20 {
+            if std::fs::remove_dir(&path).is_ok() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        warn!(cgroup = ?path, "Failed to remove job cgroup");
+    }
+
+    /// Applies the limits to a command before it is spawned.
+    ///
+    /// The child also gets its own process group so all of its processes
+    /// can be measured and killed together.
+    pub fn apply(&self, cmd: &mut Command) {
+        cmd.process_group(0);
+
+        let nice = self.limits.nice;
+        let ioprio = self.limits.ionice.map(|class| match class {
+            IoniceClass::BestEffort => {
+                (2 << IOPRIO_CLASS_SHIFT) | libc::c_int::from(self.limits.ionice_level.unwrap_or(4))
+            }
+            IoniceClass::Idle => 3 << IOPRIO_CLASS_SHIFT,
+        });
+        let cpus = self.cpus.as_ref().map(|cpus| {
+            // SAFETY: cpu_set_t is a plain bit set, valid when zeroed
+            let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+            for &cpu in cpus {
+                // SAFETY: parse_cpu_list keeps CPUs below CPU_SETSIZE
+                unsafe { libc::CPU_SET(cpu, &mut set) };
+            }
+            set
+        });
+        let procs = self.cgroup_procs.clone();
+
+        if nice.is_none() && ioprio.is_none() && cpus.is_none() && procs.is_none() {
+            return;
+        }
+
+        // SAFETY: the closure only makes async-signal-safe system calls and
+        // does not allocate.
+        unsafe {
+            cmd.pre_exec(move || {
+                if let Some(procs) = &procs {
+                    let fd = libc::open(procs.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+                    if fd < 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+                    libc::close(fd);
+                    if written != 1 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if let Some(nice) = nice {
+                    if libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if let Some(ioprio) = ioprio {
+                    if libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if let Some(set) = &cpus {
+                    if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), set) != 0
+                    {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+
+    /// Runs a command of the given phase with the limits applied and collects its output.
+    pub async fn output(&self, mut cmd: Command, phase: &str) -> Result<Output, EncoderError> {
+        self.apply(&mut cmd);
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+        let mut group = GroupGuard(child.id());
+        let activity = Activity::new();
+        let stdout = child.stdout.take().map(|s| activity.track(s));
+        let stderr = child.stderr.take().map(|s| activity.track(s));
+
+        let collect = async {
+            let (status, stdout, stderr) =
+                tokio::join!(child.wait(), read_all(stdout), read_all(stderr));
+            let spawn_failed = |e: std::io::Error| EncoderError::SpawnFailed(e.to_string());
+            Ok::<_, EncoderError>(Output {
+                status: status.map_err(spawn_failed)?,
+                stdout: stdout.map_err(spawn_failed)?,
+                stderr: stderr.map_err(spawn_failed)?,
+            })
+        };
+
+        let output = tokio::select! {
+            output = collect => output?,
+            error = self.supervise(group.0, phase, &activity) => {
+                self.kill(group.0);
+                return Err(error);
+            }
+        };
+
+        group.disarm();
+        self.check_oom(&output.status)?;
+        Ok(output)
+    }
+
+    /// Waits for a child spawned with [`apply`](Self::apply), enforcing the
+    /// memory ceiling and stall timeout.
+    ///
+    /// The caller reads the child's output through `activity`.
+    pub async fn wait(
+        &self,
+        child: &mut Child,
+        phase: &str,
+        activity: &Activity,
+    ) -> Result<ExitStatus, EncoderError> {
+        let mut group = GroupGuard(child.id());
+
+        let status = tokio::select! {
+            status = child.wait() => {
+                status.map_err(|e| EncoderError::SpawnFailed(e.to_string()))?
+            }
+            error = self.supervise(group.0, phase, activity) => {
+                self.kill(group.0);
+                return Err(error);
+            }
+        };
+
+        group.disarm();
+        self.check_oom(&status)?;
+        Ok(status)
+    }
+
+    /// Resolves with the error to fail the job with once a limit is hit.
+    async fn supervise(&self, pid: Option<u32>, phase: &str, activity: &Activity) -> EncoderError {
+        tokio::select! {
+            limit_mb = self.watch_memory(pid) => EncoderError::MemoryLimitExceeded { limit_mb },
+            _ = activity.stalled(self.stall_timeout) => {
+                let seconds = self.stall_timeout.map_or(0, |t| t.as_secs());
+                warn!(phase, seconds, "Subprocess produced no output, killing it");
+                EncoderError::Stalled {
+                    phase: phase.to_string(),
+                    reason: format!("no output for {} seconds", seconds),
+                }
+            }
+        }
+    }
+
+    /// Resolves with the ceiling in MiB once the job's memory use exceeds it.
+    async fn watch_memory(&self, pid: Option<u32>) -> u64 {
+        let (Some(limit_mb), Some(pid)) = (self.limits.memory_ceiling_mb, pid) else {
+            return std::future::pending().await;
+        };
+
+        let mut interval = tokio::time::interval(MEMORY_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let used = match &self.cgroup {
+                Some(cgroup) => cgroup_memory(cgroup),
+                None => process_group_memory(pid),
+            };
+            if let Some(used_mb) = used.map(|bytes| bytes / (1024 * 1024)) {
+                if used_mb > limit_mb {
+                    warn!(
+                        used_mb,
+                        limit_mb, "Job exceeded its memory ceiling, killing it"
+                    );
+                    return limit_mb;
+                }
+            }
+        }
+    }
+
+    /// Kills every process of the child's process group and the job's cgroup.
+    fn kill(&self, pid: Option<u32>) {
+        if let Some(pid) = pid.and_then(|pid| libc::pid_t::try_from(pid).ok()) {
+            // SAFETY: signals the process group created for the child
+            unsafe { libc::kill(-pid, libc::SIGKILL) };
+        }
+        if let Some(cgroup) = &self.cgroup {
+            let _ = std::fs::write(cgroup.join("cgroup.kill"), "1");
+        }
+    }
+
+    /// Reports a failed process killed by the cgroup's memory limit.
+    fn check_oom(&self, status: &ExitStatus) -> Result<(), EncoderError> {
+        if status.success() {
+            return Ok(());
+        }
+        let (Some(cgroup), Some(limit_mb)) = (
+            &self.cgroup,
+            self.limits.cgroup.as_ref().and_then(|c| c.memory_max_mb),
+        ) else {
+            return Ok(());
+        };
+
+        if cgroup_oom_kills(cgroup) > 0 {
+            return Err(EncoderError::MemoryLimitExceeded { limit_mb });
+        }
+        Ok(())
+    }
+}
+
+/// Kills a child's process group when dropped, unless the child finished.
+///
+/// Dropping a subprocess future (cancellation, phase timeout) otherwise
+/// only kills the direct child and leaves its own subprocesses running.
+struct GroupGuard(Option<u32>);
+
+impl GroupGuard {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        if let Some(pid) = self.0.and_then(|pid| libc::pid_t::try_from(pid).ok()) {
+            // SAFETY: signals the process group created for the child
+            unsafe { libc::kill(-pid, libc::SIGKILL) };
+        }
+    }
+}
+
+/// Reads a subprocess stream to the end.
+async fn read_all<R: AsyncRead + Unpin>(reader: Option<R>) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    if let Some(mut reader) = reader {
+        reader.read_to_end(&mut buf).await?;
+    }
+    Ok(buf)
+}
+
+/// Parses a CPU list like `0-3,8,10-11`.
+pub fn parse_cpu_list(list: &str) -> Result<Vec<usize>, String> {
+    let mut cpus = Vec::new();
+
+    for part in list.split(',').map(str::trim) {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (first.trim(), last.trim()),
+            None => (part, part),
+        };
+        let parse = |value: &str| {
+            value.parse::<usize>().map_err(|_| {
+                format!(
+                    "Invalid CPU list '{}': '{}' is not a CPU number",
+                    list, value
+                )
+            })
+        };
+        let (first, last) = (parse(first)?, parse(last)?);
+
+        if first > last {
+            return Err(format!(
+                "Invalid CPU list '{}': range '{}' is reversed",
+                list, part
+            ));
+        }
+        if last >= libc::CPU_SETSIZE as usize {
+            return Err(format!(
+                "Invalid CPU list '{}': CPU {} is out of range",
+                list, last
+            ));
+        }
+        cpus.extend(first..=last);
+    }
+
+    Ok(cpus)
+}
+
+/// Creates the job's cgroup under the root and writes its limits.
+fn setup_cgroup(limits: &CgroupLimits, job_id: &str) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(&limits.root)?;
+
+    // Controllers must be enabled on the root for its children to use them
+    let mut controllers = Vec::new();
+    if limits.cpu_cores.is_some() {
+        controllers.push("+cpu");
+    }
+    if limits.memory_max_mb.is_some() {
+        controllers.push("+memory");
+    }
+    if !controllers.is_empty() {
+        std::fs::write(
+            limits.root.join("cgroup.subtree_control"),
+            controllers.join(" "),
+        )?;
+    }
+
+    let path = limits.root.join(format!("job-{}", job_id));
+    std::fs::create_dir_all(&path)?;
+
+    if let Some(cores) = limits.cpu_cores {
+        let quota = (cores * CPU_PERIOD_US as f64).round() as u64;
+        std::fs::write(path.join("cpu.max"), format!("{} {}", quota, CPU_PERIOD_US))?;
+    }
+    if let Some(mb) = limits.memory_max_mb {
+        std::fs::write(path.join("memory.max"), (mb * 1024 * 1024).to_string())?;
+        // Kill the whole job on OOM rather than a single encoder process
+        let _ = std::fs::write(path.join("memory.oom.group"), "1");
+    }
+
+    Ok(path)
+}
+
+/// Reads the memory used by a cgroup, in bytes.
+fn cgroup_memory(cgroup: &Path) -> Option<u64> {
+    std::fs::read_to_string(cgroup.join("memory.current"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Reads how many processes the cgroup's memory limit has killed.
+fn cgroup_oom_kills(cgroup: &Path) -> u64 {
+    std::fs::read_to_string(cgroup.join("memory.events"))
+        .ok()
+        .and_then(|events| {
+            events
+                .lines()
+                .find_map(|line| line.strip_prefix("oom_kill "))
+                .and_then(|count| count.trim().parse().ok())
+        })
+        .unwrap_or(0)
+}
+
+/// Sums the resident memory of every process in a process group, in bytes.
+fn process_group_memory(pgid: u32) -> Option<u64> {
+    // SAFETY: sysconf has no preconditions
+    let page_size = u64::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).ok()?;
+    let mut pages = 0;
+
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        if !entry.file_name().as_bytes().iter().all(u8::is_ascii_digit) {
+            continue;
+        }
+        let Ok(stat) = std::fs::read_to_string(entry.path().join("stat")) else {
+            continue;
+        };
+        // Fields after the command name, which may contain spaces
+        let Some((_, fields)) = stat.rsplit_once(')') else {
+            continue;
+        };
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        if fields.get(2).and_then(|pgrp| pgrp.parse::<u32>().ok()) == Some(pgid) {
+            pages += fields
+                .get(21)
+                .and_then(|rss| rss.parse::<u64>().ok())
+                .unwrap_or(0);
+        }
+    }
+
+    Some(pages * page_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cpu_list_expands_ranges_and_singles() {
+        assert_eq!(
+            parse_cpu_list("0-3,8,10-11").unwrap(),
+            vec![0, 1, 2, 3, 8, 10, 11]
+        );
+        assert_eq!(parse_cpu_list(" 2 , 4 - 5 ").unwrap(), vec![2, 4, 5]);
+        assert_eq!(parse_cpu_list("7-7").unwrap(), vec![7]);
+    }
+
+    #[test]
+    fn parse_cpu_list_rejects_reversed_ranges() {
+        let error = parse_cpu_list("0,5-2").unwrap_err();
+        assert!(error.contains("'5-2' is reversed"), "{}", error);
+    }
+
+    #[test]
+    fn parse_cpu_list_rejects_out_of_range_cpus() {
+        let max = libc::CPU_SETSIZE as usize;
+
+        assert_eq!(
+            parse_cpu_list(&(max - 1).to_string()).unwrap(),
+            vec![max - 1]
+        );
+
+        let error = parse_cpu_list(&max.to_string()).unwrap_err();
+        assert!(error.contains("out of range"), "{}", error);
+
+        let error = parse_cpu_list(&format!("0-{}", max)).unwrap_err();
+        assert!(error.contains("out of range"), "{}", error);
+    }
+
+    #[test]
+    fn parse_cpu_list_rejects_malformed_entries() {
+        for list in ["", "a", "1,,2", "-3", "1-", "-1-2", "1.5"] {
+            assert!(
+                parse_cpu_list(list).is_err(),
+                "'{}' should be rejected",
+                list
+            );
+        }
+    }
+}
@@ -1,27 +1,34 @@
 //! mkvmerge subprocess wrapper for MKV muxing.
 
-use std::path::Path;
-use std::process::Stdio;
-
 use anyhow::Result;
-use tokio::process::Command;
+use std::path::Path;
 use tracing::{debug, info};
 
 use crate::error::EncoderError;
+use crate::media::audio::{AudioDecision, AudioTrackAction};
 
+use super::command::CommandLine;
 use super::ffmpeg::ExtractedSubtitle;
+use super::limits::ProcessControl;
 
-/// Muxes video, audio, and subtitles into an MKV file.
-pub async fn mux(
+/// Builds the mkvmerge command line that muxes video, audio, and subtitles.
+///
+/// `tags` is a global tags file, used to mark the output with its provenance.
+pub fn mux_command(
     video: &Path,
     audio: &Path,
     subtitles: &[ExtractedSubtitle],
     output: &Path,
-) -> Result<(), EncoderError> {
-    let mut cmd = Command::new("mkvmerge");
+    tags: Option<&Path>,
+) -> CommandLine {
+    let mut cmd = CommandLine::new("mkvmerge");
 
     cmd.arg("-o").arg(output);
 
+    if let Some(tags) = tags {
+        cmd.arg("--global-tags").arg(tags);
+    }
+
     // Add video (no audio from video file)
     cmd.arg("--no-audio").arg("--no-subtitles").arg(video);
 
@@ -48,14 +55,23 @@ pub async fn mux(
         cmd.arg(&sub.path);
     }
 
-    debug!(cmd = ?cmd, "Running mkvmerge");
+    cmd
+}
+
+/// Muxes video, audio, and subtitles into an MKV file.
+pub async fn mux(
+    video: &Path,
+    audio: &Path,
+    subtitles: &[ExtractedSubtitle],
+    output: &Path,
+    tags: Option<&Path>,
+    limits: &ProcessControl,
+) -> Result<(), EncoderError> {
+    let cmd = mux_command(video, audio, subtitles, output, tags);
+
+    debug!(cmd = %cmd, "Running mkvmerge");
 
-    let output_result = cmd
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .await
-        .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+    let output_result = limits.output(cmd.to_command(), "muxing").await?;
 
     // mkvmerge returns 0 for success, 1 for warnings, 2 for errors
     if output_result.status.code().unwrap_or(2) >= 2 {
@@ -70,19 +86,31 @@ pub async fn mux(
     Ok(())
 }
 
-/// Remuxes a file to MKV without re-encoding.
-pub async fn remux(input: &Path, output: &Path) -> Result<(), EncoderError> {
-    let mut cmd = Command::new("mkvmerge");
+/// Builds the mkvmerge command line that remuxes a file unchanged.
+///
+/// `tags` is a global tags file, as for [`mux_command`].
+pub fn remux_command(input: &Path, output: &Path, tags: Option<&Path>) -> CommandLine {
+    let mut cmd = CommandLine::new("mkvmerge");
 
     cmd.arg("-o").arg(output);
+    if let Some(tags) = tags {
+        cmd.arg("--global-tags").arg(tags);
+    }
     cmd.arg(input);
 
-    let output_result = cmd
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .await
-        .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+    cmd
+}
+
+/// Remuxes a file to MKV without re-encoding.
+pub async fn remux(
+    input: &Path,
+    output: &Path,
+    tags: Option<&Path>,
+    limits: &ProcessControl,
+) -> Result<(), EncoderError> {
+    let output_result = limits
+        .output(remux_command(input, output, tags).to_command(), "muxing")
+        .await?;
 
     if output_result.status.code().unwrap_or(2) >= 2 {
         let stderr = String::from_utf8_lossy(&output_result.stderr);
@@ -95,37 +123,190 @@ pub async fn remux(input: &Path, output: &Path) -> Result<(), EncoderError> {
     Ok(())
 }
 
-/// Sets track properties on an existing MKV file.
-pub async fn set_track_properties(
-    file: &Path,
-    track_index: usize,
-    language: Option<&str>,
-    name: Option<&str>,
-    is_default: bool,
-    is_forced: bool,
-) -> Result<(), EncoderError> {
-    let mut cmd = Command::new("mkvpropedit");
+/// Type of a labelled track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackKind {
+    /// Audio track.
+    Audio,
+    /// Subtitle track.
+    Subtitle,
+}
+
+/// Language, name and flags to set on a track of a muxed file.
+#[derive(Debug, Clone)]
+pub struct TrackLabel {
+    /// Type of the track.
+    pub kind: TrackKind,
+    /// One-based position among the tracks of its type.
+    pub number: usize,
+    /// Language code.
+    pub language: Option<String>,
+    /// Track name.
+    pub name: Option<String>,
+    /// Whether the track is the default of its type.
+    pub is_default: bool,
+    /// Whether the track is forced.
+    pub is_forced: bool,
+}
+
+/// Labels the audio and subtitle tracks a mux produces.
+///
+/// Tracks are numbered per type, in mux order: the processed audio tracks,
+/// then the subtitles that are not burned in. The first audio track becomes
+/// the default.
+pub fn output_track_labels(
+    audio: &[AudioDecision],
+    subtitles: &[ExtractedSubtitle],
+) -> Vec<TrackLabel> {
+    let mut labels: Vec<TrackLabel> = Vec::new();
+    let mut add = |kind,
+                   language: Option<&String>,
+                   name: Option<String>,
+                   is_default: bool,
+                   is_forced: bool| {
+        let number = labels.iter().filter(|l| l.kind == kind).count() + 1;
+        labels.push(TrackLabel {
+            kind,
+            number,
+            language: language.cloned(),
+            name,
+            is_default,
+            is_forced,
+        });
+    };
+
+    let mut first_audio = true;
+    for decision in audio {
+        let stream = &decision.stream;
+        let (codec, downmix_codec) = match &decision.action {
+            AudioTrackAction::Exclude => continue,
+            AudioTrackAction::Passthrough => (stream.codec.as_str(), None),
+            AudioTrackAction::Transcode { codec, .. } => (codec.as_str(), None),
+            AudioTrackAction::PassthroughWithDownmix { downmix_codec, .. } => {
+                (stream.codec.as_str(), Some(downmix_codec.as_str()))
+            }
+            AudioTrackAction::TranscodeWithDownmix {
+                codec,
+                downmix_codec,
+                ..
+            } => (codec.as_str(), Some(downmix_codec.as_str())),
+        };
+
+        // Commentary and similar titles say more than the codec does
+        let name = match &stream.title {
+            Some(title) if stream.is_commentary => title.clone(),
+            _ => audio_track_name(codec, stream.channels),
+        };
+        add(
+            TrackKind::Audio,
+            stream.language.as_ref(),
+            Some(name),
+            first_audio,
+            false,
+        );
+        first_audio = false;
+
+        if let Some(downmix_codec) = downmix_codec {
+            let name = audio_track_name(downmix_codec, 2);
+            add(
+                TrackKind::Audio,
+                stream.language.as_ref(),
+                Some(name),
+                false,
+                false,
+            );
+        }
+    }
+
+    for sub in subtitles.iter().filter(|s| !s.should_burn_in) {
+        let name = sub.is_forced.then(|| "Forced".to_string());
+        add(
+            TrackKind::Subtitle,
+            sub.language.as_ref(),
+            name,
+            sub.is_default,
+            sub.is_forced,
+        );
+    }
+
+    labels
+}
+
+/// Names an audio track after its codec and channel layout (e.g. "E-AC-3 5.1").
+fn audio_track_name(codec: &str, channels: u8) -> String {
+    let codec = codec.to_lowercase();
+    let codec = match codec.trim_start_matches("lib") {
+        "aac" | "fdk_aac" => "AAC".to_string(),
+        "ac3" => "AC-3".to_string(),
+        "eac3" => "E-AC-3".to_string(),
+        "truehd" => "TrueHD".to_string(),
+        "opus" => "Opus".to_string(),
+        "vorbis" => "Vorbis".to_string(),
+        other => other.to_uppercase(),
+    };
+    let layout = match channels {
+        1 => "Mono".to_string(),
+        2 => "Stereo".to_string(),
+        6 => "5.1".to_string(),
+        8 => "7.1".to_string(),
+        n => format!("{}ch", n),
+    };
+    format!("{} {}", codec, layout)
+}
+
+/// Builds the mkvpropedit command line that sets the properties of tracks.
+///
+/// Tracks are selected by type (`track:a1`, `track:s1`), as video tracks may
+/// be counted differently by ffprobe, e.g. cover art.
+pub fn track_properties_command(file: &Path, tracks: &[TrackLabel]) -> CommandLine {
+    let mut cmd = CommandLine::new("mkvpropedit");
 
     cmd.arg(file);
-    cmd.arg("--edit").arg(format!("track:{}", track_index + 1));
 
-    if let Some(lang) = language {
-        cmd.arg("--set").arg(format!("language={}", lang));
+    for track in tracks {
+        let kind = match track.kind {
+            TrackKind::Audio => 'a',
+            TrackKind::Subtitle => 's',
+        };
+        cmd.arg("--edit")
+            .arg(format!("track:{}{}", kind, track.number));
+
+        if let Some(lang) = &track.language {
+            cmd.arg("--set").arg(format!("language={}", lang));
+        }
+
+        if let Some(name) = &track.name {
+            cmd.arg("--set").arg(format!("name={}", name));
+        }
+
+        cmd.arg("--set").arg(format!(
+            "flag-default={}",
+            if track.is_default { "1" } else { "0" }
+        ));
+        cmd.arg("--set").arg(format!(
+            "flag-forced={}",
+            if track.is_forced { "1" } else { "0" }
+        ));
     }
 
-    if let Some(n) = name {
-        cmd.arg("--set").arg(format!("name={}", n));
+    cmd
+}
+
+/// Sets track properties on an existing MKV file.
+pub async fn set_track_properties(
+    file: &Path,
+    tracks: &[TrackLabel],
+    limits: &ProcessControl,
+) -> Result<(), EncoderError> {
+    if tracks.is_empty() {
+        return Ok(());
     }
 
-    cmd.arg("--set").arg(format!("flag-default={}", if is_default { "1" } else { "0" }));
-    cmd.arg("--set").arg(format!("flag-forced={}", if is_forced { "1" } else { "0" }));
+    let cmd = track_properties_command(file, tracks);
+
+    debug!(cmd = %cmd, "Running mkvpropedit");
 
-    let output_result = cmd
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .await
-        .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+    let output_result = limits.output(cmd.to_command(), "muxing").await?;
 
     if !output_result.status.success() {
         let stderr = String::from_utf8_lossy(&output_result.stderr);
@@ -1,8 +1,17 @@
 //! Video and audio encoding pipeline.
 
 pub mod av1an;
+pub mod command;
 pub mod ffmpeg;
+pub mod limits;
 pub mod mkvmerge;
+pub mod phases;
+pub mod plan;
+pub mod scheduler;
+pub mod throttle;
+pub mod watchdog;
 pub mod worker;
 
+pub use plan::EncodePlan;
+pub use scheduler::Scheduler;
 pub use worker::EncodeWorker;
@@ -0,0 +1,231 @@
+//! Progress of encode phases, some of which run at the same time.
+
+use std::future::Future;
+
+use tokio::sync::mpsc;
+
+use super::worker::EncodePhase;
+
+/// Change in the state of a running phase.
+#[derive(Debug, Clone, Copy)]
+pub enum PhaseEvent {
+    /// The phase started.
+    Started(EncodePhase),
+    /// The phase finished successfully.
+    Finished(EncodePhase),
+}
+
+/// Runs a phase, reporting when it starts and finishes.
+pub async fn tracked<T, E>(
+    phase: EncodePhase,
+    events: &mpsc::Sender<PhaseEvent>,
+    fut: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let _ = events.send(PhaseEvent::Started(phase)).await;
+    let result = fut.await;
+    if result.is_ok() {
+        let _ = events.send(PhaseEvent::Finished(phase)).await;
+    }
+    result
+}
+
+/// Share of a job's progress a phase accounts for, relative to the others.
+fn weight(phase: EncodePhase) -> f32 {
+    match phase {
+        EncodePhase::Analyzing => 1.0,
+        EncodePhase::ExtractingSubtitles => 2.0,
+        EncodePhase::EncodingVideo => 80.0,
+        EncodePhase::BurningSubtitles => 8.0,
+        EncodePhase::ProcessingAudio => 5.0,
+        EncodePhase::Muxing => 3.0,
+        EncodePhase::Verifying => 1.0,
+    }
+}
+
+/// State of one tracked phase.
+#[derive(Debug, Clone)]
+struct PhaseState {
+    phase: EncodePhase,
+    running: bool,
+    /// Completed fraction (0-1).
+    done: f32,
+}
+
+/// Overall progress of a job, shared among its phases by weight.
+#[derive(Debug, Clone)]
+pub struct PhaseProgress {
+    phases: Vec<PhaseState>,
+    /// Most recently started or finished phase.
+    last: EncodePhase,
+}
+
+impl PhaseProgress {
+    /// Tracks the phases a job runs; phases not listed are ignored.
+    pub fn new(phases: &[EncodePhase]) -> Self {
+        Self {
+            phases: phases
+                .iter()
+                .map(|&phase| PhaseState {
+                    phase,
+                    running: false,
+                    done: 0.0,
+                })
+                .collect(),
+            last: phases.first().copied().unwrap_or(EncodePhase::Analyzing),
+        }
+    }
+
+    /// Applies an event reported by a running phase.
+    pub fn apply(&mut self, event: PhaseEvent) {
+        match event {
+            PhaseEvent::Started(phase) => self.start(phase),
+            PhaseEvent::Finished(phase) => self.finish(phase),
+        }
+    }
+
+    /// Marks a phase as running; a phase that runs again starts from zero.
+    pub fn start(&mut self, phase: EncodePhase) {
+        if let Some(state) = self.state(phase) {
+            state.running = true;
+            state.done = 0.0;
+            self.last = phase;
+        }
+    }
+
+    /// Sets the completed fraction (0-1) of a running phase.
+    pub fn update(&mut self, phase: EncodePhase, done: f32) {
+        if let Some(state) = self.state(phase) {
+            state.done = done.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Marks a phase as finished.
+    pub fn finish(&mut self, phase: EncodePhase) {
+        if let Some(state) = self.state(phase) {
+            state.running = false;
+            state.done = 1.0;
+            self.last = phase;
+        }
+    }
+
+    /// Marks every phase as finished, including ones that turned out not to be needed.
+    pub fn complete(&mut self) {
+        for state in &mut self.phases {
+            state.running = false;
+            state.done = 1.0;
+        }
+    }
+
+    /// Overall progress percentage (0-100).
+    pub fn percent(&self) -> f32 {
+        let total: f32 = self.phases.iter().map(|s| weight(s.phase)).sum();
+        if total == 0.0 {
+            return 0.0;
+        }
+        let done: f32 = self.phases.iter().map(|s| weight(s.phase) * s.done).sum();
+        done / total * 100.0
+    }
+
+    /// Phase to report: the heaviest running phase, or the last one to change.
+    pub fn current(&self) -> EncodePhase {
+        self.phases
+            .iter()
+            .filter(|s| s.running)
+            .max_by(|a, b| weight(a.phase).total_cmp(&weight(b.phase)))
+            .map(|s| s.phase)
+            .unwrap_or(self.last)
+    }
+
+    fn state(&mut self, phase: EncodePhase) -> Option<&mut PhaseState> {
+        self.phases.iter_mut().find(|s| s.phase == phase)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [EncodePhase; 7] = [
+        EncodePhase::Analyzing,
+        EncodePhase::ExtractingSubtitles,
+        EncodePhase::EncodingVideo,
+        EncodePhase::BurningSubtitles,
+        EncodePhase::ProcessingAudio,
+        EncodePhase::Muxing,
+        EncodePhase::Verifying,
+    ];
+
+    fn assert_percent(progress: &PhaseProgress, expected: f32) {
+        let percent = progress.percent();
+        assert!(
+            (percent - expected).abs() < 0.001,
+            "expected {}%, got {}%",
+            expected,
+            percent
+        );
+    }
+
+    #[test]
+    fn weights_of_all_phases_add_up_to_one_hundred() {
+        let total: f32 = ALL.iter().map(|&phase| weight(phase)).sum();
+        assert_eq!(total, 100.0);
+    }
+
+    #[test]
+    fn concurrent_phases_add_their_shares() {
+        let mut progress = PhaseProgress::new(&ALL);
+        progress.finish(EncodePhase::Analyzing);
+        progress.start(EncodePhase::EncodingVideo);
+        progress.start(EncodePhase::ProcessingAudio);
+        progress.start(EncodePhase::ExtractingSubtitles);
+
+        progress.update(EncodePhase::EncodingVideo, 0.5);
+        progress.update(EncodePhase::ProcessingAudio, 1.0);
+        progress.finish(EncodePhase::ExtractingSubtitles);
+
+        // 1 (analysis) + 40 (half the video) + 5 (audio) + 2 (subtitles)
+        assert_percent(&progress, 48.0);
+        assert_eq!(progress.current(), EncodePhase::EncodingVideo);
+    }
+
+    #[test]
+    fn shares_are_relative_to_the_tracked_phases() {
+        let mut progress = PhaseProgress::new(&[EncodePhase::ProcessingAudio, EncodePhase::Muxing]);
+        progress.finish(EncodePhase::ProcessingAudio);
+        assert_percent(&progress, 62.5);
+
+        // Phases the job does not run are ignored
+        progress.finish(EncodePhase::EncodingVideo);
+        assert_percent(&progress, 62.5);
+
+        progress.finish(EncodePhase::Muxing);
+        assert_percent(&progress, 100.0);
+    }
+
+    #[test]
+    fn complete_reaches_one_hundred_with_unused_phases() {
+        let mut progress = PhaseProgress::new(&ALL);
+        progress.start(EncodePhase::EncodingVideo);
+        progress.update(EncodePhase::EncodingVideo, 2.0);
+        assert_percent(&progress, 80.0);
+
+        progress.complete();
+        assert_percent(&progress, 100.0);
+    }
+
+    #[test]
+    fn restarted_phase_starts_from_zero() {
+        let mut progress = PhaseProgress::new(&ALL);
+        progress.start(EncodePhase::EncodingVideo);
+        progress.update(EncodePhase::EncodingVideo, 0.75);
+        progress.start(EncodePhase::EncodingVideo);
+        assert_percent(&progress, 0.0);
+    }
+
+    #[test]
+    fn without_phases_progress_is_zero() {
+        let progress = PhaseProgress::new(&[]);
+        assert_eq!(progress.percent(), 0.0);
+        assert_eq!(progress.current(), EncodePhase::Analyzing);
+    }
+}
@@ -0,0 +1,209 @@
+//! Encode plans: every decision and command for a file, computed without running anything.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::command::CommandLine;
+use super::worker::EncodePhase;
+use super::{av1an, ffmpeg, mkvmerge};
+use crate::config::model::{Profile, ProfileMode, SkipAction};
+use crate::error::EncoderError;
+use crate::media::audio::{self, AudioDecision};
+use crate::media::probe::{self, MediaInfo, ProbeResult, VideoStream};
+use crate::media::skip::{self, SkipDecision};
+use crate::media::subtitle::{self, SubtitleDecision};
+
+/// Intermediate files of an encode, relative to the job's temp directory.
+#[derive(Debug, Clone)]
+pub struct WorkFiles {
+    /// av1an chunk directory.
+    pub av1an_temp: PathBuf,
+    /// Encoded video without audio.
+    pub video: PathBuf,
+    /// Encoded video with subtitles burned in.
+    pub burned_video: PathBuf,
+    /// Processed audio tracks.
+    pub audio: PathBuf,
+    /// Directory for extracted subtitles.
+    pub subtitle_dir: PathBuf,
+    /// Global tags file marking the output's provenance.
+    pub tags: PathBuf,
+}
+
+impl WorkFiles {
+    /// Lays out intermediate files under a temp directory.
+    pub fn new(temp_dir: &Path) -> Self {
+        Self {
+            av1an_temp: temp_dir.join("av1an"),
+            video: temp_dir.join("video.mkv"),
+            burned_video: temp_dir.join("video_burned.mkv"),
+            audio: temp_dir.join("audio.mka"),
+            subtitle_dir: temp_dir.to_path_buf(),
+            tags: temp_dir.join("tags.xml"),
+        }
+    }
+}
+
+/// A command the pipeline would run, and the phase it runs in.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedCommand {
+    /// Encoding phase.
+    pub phase: EncodePhase,
+    /// Command line.
+    pub command: CommandLine,
+}
+
+/// Everything an encode of one file would do.
+#[derive(Debug, Clone, Serialize)]
+pub struct EncodePlan {
+    /// Source file.
+    pub input: PathBuf,
+    /// Final output file.
+    pub output: PathBuf,
+    /// Profile name.
+    pub profile: String,
+    /// Source media information.
+    pub media: MediaInfo,
+    /// Source video streams.
+    pub video_streams: Vec<VideoStream>,
+    /// Decision for every source audio stream.
+    pub audio: Vec<AudioDecision>,
+    /// Decision for every source subtitle stream.
+    pub subtitles: Vec<SubtitleDecision>,
+    /// Skip rule matching the source, if any.
+    pub skip: Option<SkipDecision>,
+    /// Commands in execution order.
+    pub commands: Vec<PlannedCommand>,
+}
+
+impl EncodePlan {
+    /// Probes the input and plans its encode with the profile.
+    pub fn build(
+        input: &Path,
+        output: &Path,
+        profile: &Profile,
+        temp_dir: &Path,
+    ) -> Result<Self, EncoderError> {
+        let probe_result = probe_input(input)?;
+        Ok(Self::from_probe(
+            probe_result,
+            input,
+            output,
+            profile,
+            temp_dir,
+        ))
+    }
+
+    /// Plans an encode from an existing probe result.
+    pub fn from_probe(
+        probe_result: ProbeResult,
+        input: &Path,
+        output: &Path,
+        profile: &Profile,
+        temp_dir: &Path,
+    ) -> Self {
+        let files = WorkFiles::new(temp_dir);
+        let audio_decisions =
+            audio::process_audio_streams(&probe_result.audio_streams, &profile.audio);
+        let subtitle_decisions =
+            subtitle::process_subtitle_streams(&probe_result.subtitle_streams, &profile.subtitles);
+        let mut subtitles = ffmpeg::planned_subtitles(&files.subtitle_dir, &subtitle_decisions);
+
+        let mut commands = Vec::new();
+        let mut add = |phase, command| commands.push(PlannedCommand { phase, command });
+
+        let skip = skip::evaluate(&profile.skip_rules, &probe_result);
+        match skip.as_ref().map(|d| d.action) {
+            Some(SkipAction::Skip) => {}
+            Some(SkipAction::RemuxOnly) => add(
+                EncodePhase::Muxing,
+                mkvmerge::remux_command(input, output, Some(&files.tags)),
+            ),
+            action => {
+                for sub in &subtitles {
+                    add(
+                        EncodePhase::ExtractingSubtitles,
+                        ffmpeg::extract_subtitle_command(input, sub),
+                    );
+                }
+
+                let keep_video =
+                    profile.mode == ProfileMode::Remux || action == Some(SkipAction::CleanupOnly);
+                let final_video = if keep_video {
+                    // Burning in needs a re-encode, so such tracks are kept as regular tracks
+                    for sub in &mut subtitles {
+                        sub.should_burn_in = false;
+                    }
+                    input
+                } else {
+                    add(
+                        EncodePhase::EncodingVideo,
+                        av1an::encode_command(input, &files.video, &files.av1an_temp, profile),
+                    );
+
+                    match subtitles.iter().find(|s| s.should_burn_in) {
+                        Some(sub) => {
+                            add(
+                                EncodePhase::BurningSubtitles,
+                                ffmpeg::burn_subtitles_command(
+                                    &files.video,
+                                    &sub.path,
+                                    &files.burned_video,
+                                    true,
+                                ),
+                            );
+                            &files.burned_video
+                        }
+                        None => &files.video,
+                    }
+                };
+
+                add(
+                    EncodePhase::ProcessingAudio,
+                    ffmpeg::audio_command(input, &files.audio, &audio_decisions),
+                );
+                add(
+                    EncodePhase::Muxing,
+                    mkvmerge::mux_command(
+                        final_video,
+                        &files.audio,
+                        &subtitles,
+                        output,
+                        Some(&files.tags),
+                    ),
+                );
+
+                let labels = mkvmerge::output_track_labels(&audio_decisions, &subtitles);
+                if profile.mode == ProfileMode::Remux && !labels.is_empty() {
+                    add(
+                        EncodePhase::Muxing,
+                        mkvmerge::track_properties_command(output, &labels),
+                    );
+                }
+            }
+        }
+
+        Self {
+            input: input.to_path_buf(),
+            output: output.to_path_buf(),
+            profile: profile.name.clone(),
+            media: probe_result.info,
+            video_streams: probe_result.video_streams,
+            audio: audio_decisions,
+            subtitles: subtitle_decisions,
+            skip,
+            commands,
+        }
+    }
+}
+
+/// Probes a job's input, failing permanently when it is missing or unreadable.
+pub fn probe_input(input: &Path) -> Result<ProbeResult, EncoderError> {
+    if !input.exists() {
+        return Err(EncoderError::InputNotFound {
+            path: input.to_path_buf(),
+        });
+    }
+    probe::probe(input).map_err(|e| EncoderError::ProbeFailed(e.to_string()))
+}
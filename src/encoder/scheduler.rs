@@ -0,0 +1,426 @@
+//! Runs several queued jobs at once within the configured concurrency limits.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use tokio::sync::{watch, RwLock};
+use tokio::task::{Id, JoinSet};
+use tracing::{error, info};
+
+use super::throttle::{Throttle, ThrottleStatus};
+use super::worker::EncodeWorker;
+use crate::config::model::{AppConfig, ConcurrencyConfig, Profile};
+use crate::error::QueueError;
+use crate::notify::prometheus::Metrics;
+use crate::queue::{EncodeJob, QueueManager};
+use crate::validation::SystemCapabilities;
+
+/// How long to wait before looking at the queue again when nothing can start.
+const IDLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Number of queued job IDs read at a time.
+const QUEUE_SCAN_PAGE: usize = 50;
+
+/// Number of jobs at the front of the queue considered on each pass.
+const QUEUE_SCAN_LIMIT: usize = 500;
+
+/// How long to wait after the queue could not be read.
+const ERROR_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Resources held by a running job.
+#[derive(Debug, Clone)]
+struct Reservation {
+    job_id: String,
+    profile: String,
+    workers: usize,
+}
+
+/// Starts queued jobs while the concurrency limits allow.
+///
+/// Jobs are considered in queue order. A job whose profile is at its limit is
+/// skipped, but a job that does not fit in the worker budget holds back the
+/// jobs behind it, so large encodes are not starved by small ones. A job that
+/// needs more workers than the whole budget runs once nothing else is.
+///
+/// With capabilities set, jobs whose profile needs an encoder this node lacks
+/// are left for other nodes. The profile is checked as the job's next attempt
+/// runs it, so a retry step that switches encoder goes to a node that has it.
+///
+/// Only the first jobs of the queue are considered on each pass, so a long
+/// queue does not cost a read of every job.
+///
+/// No job starts during a global pause window or while the throttle pauses
+/// dequeueing; jobs of a profile inside its own pause window are skipped, as
+/// are retries still waiting out their delay.
+///
+/// A dry-run worker plans queued jobs without claiming them, once each, so
+/// real workers sharing the queue still encode them.
+pub struct Scheduler {
+    /// Worker cloned for each job.
+    worker: EncodeWorker,
+    /// Queue the jobs are taken from.
+    queue: QueueManager,
+    /// Current configuration, re-read before every start.
+    config: Arc<RwLock<AppConfig>>,
+    /// Metrics for running jobs, if enabled.
+    metrics: Option<Arc<Metrics>>,
+    /// ID recorded on the jobs this node runs.
+    node_id: Option<String>,
+    /// Encoders of this node, used to pick only jobs it can run.
+    capabilities: Option<SystemCapabilities>,
+    /// Running jobs by task.
+    running: HashMap<Id, Reservation>,
+    /// Queued jobs already planned by a dry-run worker.
+    planned: HashSet<String>,
+    /// Pause windows and load checks.
+    throttle: Throttle,
+    /// Latest throttle decision.
+    throttle_tx: watch::Sender<ThrottleStatus>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler that runs jobs with clones of the given worker.
+    pub fn new(worker: EncodeWorker, queue: QueueManager, config: Arc<RwLock<AppConfig>>) -> Self {
+        Self {
+            worker,
+            queue,
+            config,
+            metrics: None,
+            node_id: None,
+            capabilities: None,
+            running: HashMap::new(),
+            planned: HashSet::new(),
+            throttle: Throttle::new(),
+            throttle_tx: watch::channel(ThrottleStatus::default()).0,
+        }
+    }
+
+    /// Reports running jobs to the given metrics.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.worker = self.worker.with_metrics(metrics.clone());
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Runs jobs as the given node, taking only jobs its encoders can handle.
+    pub fn with_node(mut self, node_id: String, capabilities: SystemCapabilities) -> Self {
+        self.node_id = Some(node_id);
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Returns a receiver of the throttle state, updated as jobs are scheduled.
+    pub fn throttle_status(&self) -> watch::Receiver<ThrottleStatus> {
+        self.throttle_tx.subscribe()
+    }
+
+    /// Runs the scheduling loop.
+    pub async fn run(&mut self) -> Result<()> {
+        let mut tasks = JoinSet::new();
+
+        loop {
+            let wait = match self.next_job().await {
+                Ok(Some((job, workers))) => {
+                    self.start(&mut tasks, job, workers);
+                    continue;
+                }
+                Ok(None) => IDLE_INTERVAL,
+                Err(e) => {
+                    error!(error = %e, "Failed to dequeue job");
+                    ERROR_INTERVAL
+                }
+            };
+
+            tokio::select! {
+                Some(finished) = tasks.join_next_with_id(), if !tasks.is_empty() => {
+                    let (id, result) = match finished {
+                        Ok((id, result)) => (id, result),
+                        Err(e) => (e.id(), Err(anyhow::anyhow!("Job task panicked: {}", e))),
+                    };
+                    self.finish(id, result);
+                }
+                _ = tokio::time::sleep(wait) => {}
+            }
+        }
+    }
+
+    /// Takes the next queued job that fits the limits, with its worker count.
+    async fn next_job(&mut self) -> Result<Option<(EncodeJob, usize)>, QueueError> {
+        loop {
+            let status = self.check_throttle().await;
+            if status.paused {
+                return Ok(None);
+            }
+
+            let (limits, profiles) = {
+                let config = self.config.read().await;
+                (config.global.concurrency.clone(), config.profiles.clone())
+            };
+
+            if self.running.len() >= limits.max_jobs {
+                return Ok(None);
+            }
+
+            let Some((job, workers)) = self.find_candidate(&status, &limits, &profiles).await?
+            else {
+                return Ok(None);
+            };
+
+            // Dry runs leave the job queued for the workers that encode it
+            if self.worker.is_dry_run() {
+                self.planned.insert(job.id.clone());
+                return Ok(Some((job, workers)));
+            }
+
+            // Another worker may have taken it since the queue was listed
+            if let Some(job) = self.queue.claim(&job.id).await? {
+                return Ok(Some((job, workers)));
+            }
+        }
+    }
+
+    /// Finds the first queued job that can start, with its worker count.
+    ///
+    /// The queue is read a page of IDs at a time, up to [`QUEUE_SCAN_LIMIT`]
+    /// jobs, and each job is fetched only when it is considered.
+    async fn find_candidate(
+        &mut self,
+        status: &ThrottleStatus,
+        limits: &ConcurrencyConfig,
+        profiles: &[Profile],
+    ) -> Result<Option<(EncodeJob, usize)>, QueueError> {
+        let now = Utc::now();
+        let mut queued = HashSet::new();
+
+        for start in (0..QUEUE_SCAN_LIMIT).step_by(QUEUE_SCAN_PAGE) {
+            let ids = self.queue.queued_ids(start, QUEUE_SCAN_PAGE).await?;
+
+            for id in &ids {
+                if self.planned.contains(id) {
+                    queued.insert(id.clone());
+                    continue;
+                }
+                let Some(job) = self.queue.get_job(id).await? else {
+                    continue;
+                };
+
+                // Unknown profiles fail in the worker; they only need a slot
+                if status.paused_profiles.contains(&job.profile_name) || job.is_retry_delayed(now) {
+                    continue;
+                }
+                let workers = match profiles.iter().find(|p| p.name == job.profile_name) {
+                    Some(profile) => {
+                        let profile = job.profile_for_attempt(profile, job.attempt_count + 1);
+                        if !self
+                            .capabilities
+                            .as_ref()
+                            .is_none_or(|c| c.can_encode(&profile))
+                        {
+                            continue;
+                        }
+                        profile.worker_cost()
+                    }
+                    None => 1,
+                };
+                let workers = status
+                    .reduced_workers
+                    .map_or(workers, |limit| workers.min(limit));
+                match fits(self.running.values(), limits, &job.profile_name, workers) {
+                    Fit::Yes => return Ok(Some((job, workers))),
+                    Fit::ProfileFull => {}
+                    Fit::OverBudget => return Ok(None),
+                }
+            }
+
+            if ids.len() < QUEUE_SCAN_PAGE {
+                break;
+            }
+        }
+
+        // Forget planned jobs that left the queue, so the set stays bounded
+        // and a job requeued under the same ID is planned again
+        self.planned.retain(|id| queued.contains(id));
+
+        Ok(None)
+    }
+
+    /// Evaluates the throttle and publishes its state when it changes.
+    async fn check_throttle(&mut self) -> ThrottleStatus {
+        let (throttle, windows) = {
+            let config = self.config.read().await;
+            let windows: Vec<_> = config
+                .profiles
+                .iter()
+                .filter(|p| !p.pause_windows.is_empty())
+                .map(|p| (p.name.clone(), p.pause_windows.clone()))
+                .collect();
+            (config.global.throttle.clone(), windows)
+        };
+
+        let status = self
+            .throttle
+            .status(
+                &throttle,
+                windows
+                    .iter()
+                    .map(|(name, w)| (name.as_str(), w.as_slice())),
+            )
+            .await;
+
+        if status != *self.throttle_tx.borrow() {
+            if status.is_active() {
+                info!(
+                    paused = status.paused,
+                    reduced_workers = ?status.reduced_workers,
+                    reasons = ?status.reasons,
+                    paused_profiles = ?status.paused_profiles,
+                    "Throttling new jobs"
+                );
+            } else {
+                info!("Throttle lifted, starting jobs normally");
+            }
+            if let Some(metrics) = &self.metrics {
+                metrics.set_throttle(&status);
+            }
+            self.throttle_tx.send_replace(status.clone());
+        }
+
+        status
+    }
+
+    /// Runs a job on its own clone of the worker.
+    fn start(&mut self, tasks: &mut JoinSet<Result<()>>, mut job: EncodeJob, workers: usize) {
+        job.node = self.node_id.clone();
+        let reservation = Reservation {
+            job_id: job.id.clone(),
+            profile: job.profile_name.clone(),
+            workers,
+        };
+
+        let limit = self.throttle_tx.borrow().reduced_workers;
+        let mut worker = self.worker.clone().with_workers_limit(limit);
+        let handle = tasks.spawn(async move { worker.run_job(job).await });
+
+        info!(
+            job_id = %reservation.job_id,
+            profile = %reservation.profile,
+            workers,
+            running = self.running.len() + 1,
+            "Started job"
+        );
+        if let Some(metrics) = &self.metrics {
+            metrics.start_job(&reservation.job_id, &reservation.profile, workers);
+        }
+        self.running.insert(handle.id(), reservation);
+        self.report_running();
+    }
+
+    /// Releases the resources of a job whose task ended.
+    fn finish(&mut self, id: Id, result: Result<()>) {
+        let Some(reservation) = self.running.remove(&id) else {
+            return;
+        };
+
+        if let Err(e) = result {
+            error!(job_id = %reservation.job_id, error = %e, "Encoder worker failed");
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics.finish_job(&reservation.job_id, &reservation.profile);
+        }
+        self.report_running();
+    }
+
+    fn report_running(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.set_jobs_in_progress(self.running.len());
+        }
+    }
+}
+
+/// Checks whether a job of the profile needing the given workers can start
+/// next to the running jobs.
+fn fits<'a>(
+    running: impl IntoIterator<Item = &'a Reservation>,
+    limits: &ConcurrencyConfig,
+    profile: &str,
+    workers: usize,
+) -> Fit {
+    let running: Vec<&Reservation> = running.into_iter().collect();
+
+    if let Some(limit) = limits.profile_limits.get(profile) {
+        if running.iter().filter(|r| r.profile == profile).count() >= *limit {
+            return Fit::ProfileFull;
+        }
+    }
+
+    if limits.worker_budget > 0 && !running.is_empty() {
+        let used: usize = running.iter().map(|r| r.workers).sum();
+        if used + workers > limits.worker_budget {
+            return Fit::OverBudget;
+        }
+    }
+
+    Fit::Yes
+}
+
+/// Whether a job can start under the current limits.
+#[derive(Debug, PartialEq, Eq)]
+enum Fit {
+    /// The job can start.
+    Yes,
+    /// The job's profile is at its limit; later jobs may still start.
+    ProfileFull,
+    /// The job does not fit in the worker budget; later jobs must wait.
+    OverBudget,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn running(profile: &str, workers: usize) -> Reservation {
+        Reservation {
+            job_id: format!("{}-{}", profile, workers),
+            profile: profile.to_string(),
+            workers,
+        }
+    }
+
+    fn limits(worker_budget: usize, profile_limits: &[(&str, usize)]) -> ConcurrencyConfig {
+        ConcurrencyConfig {
+            worker_budget,
+            profile_limits: profile_limits
+                .iter()
+                .map(|(p, l)| (p.to_string(), *l))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fits_within_the_worker_budget() {
+        let jobs = [running("movies", 4)];
+
+        assert_eq!(fits(&jobs, &limits(8, &[]), "movies", 4), Fit::Yes);
+        assert_eq!(fits(&jobs, &limits(8, &[]), "movies", 5), Fit::OverBudget);
+        assert_eq!(fits(&jobs, &limits(0, &[]), "movies", 64), Fit::Yes);
+    }
+
+    #[test]
+    fn fits_a_first_job_larger_than_the_budget() {
+        assert_eq!(fits(&[], &limits(8, &[]), "movies", 16), Fit::Yes);
+    }
+
+    #[test]
+    fn fits_stops_a_profile_at_its_limit() {
+        let jobs = [running("movies", 2), running("tv", 2)];
+        let limits = limits(0, &[("movies", 1), ("tv", 2)]);
+
+        assert_eq!(fits(&jobs, &limits, "movies", 2), Fit::ProfileFull);
+        assert_eq!(fits(&jobs, &limits, "tv", 2), Fit::Yes);
+        assert_eq!(fits(&jobs, &limits, "anime", 2), Fit::Yes);
+    }
+}
@@ -0,0 +1,275 @@
+//! Time windows and system load checks that hold back new jobs.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+use crate::config::model::{ThrottleAction, ThrottleConfig, TimeWindow};
+
+/// Longest time the playback command may run.
+const PLAYBACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether new jobs may start, as decided by the throttle.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThrottleStatus {
+    /// No new jobs start.
+    pub paused: bool,
+    /// New jobs start with at most this many av1an workers.
+    pub reduced_workers: Option<usize>,
+    /// Why the throttle is active (`window`, `load`, `memory`, `playback`).
+    pub reasons: Vec<String>,
+    /// Profiles inside one of their own pause windows.
+    pub paused_profiles: Vec<String>,
+}
+
+impl ThrottleStatus {
+    /// Returns true if any restriction applies.
+    pub fn is_active(&self) -> bool {
+        self.paused || self.reduced_workers.is_some() || !self.paused_profiles.is_empty()
+    }
+}
+
+/// Evaluates the throttle conditions, caching system checks between intervals.
+#[derive(Debug, Default)]
+pub struct Throttle {
+    /// Reasons the system was busy at the last check.
+    busy: Vec<String>,
+    /// When the system was last checked.
+    checked_at: Option<Instant>,
+}
+
+impl Throttle {
+    /// Creates a throttle that checks the system on first use.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decides whether new jobs may start now.
+    ///
+    /// `profiles` lists each profile's own pause windows.
+    pub async fn status<'a>(
+        &mut self,
+        config: &ThrottleConfig,
+        profiles: impl IntoIterator<Item = (&'a str, &'a [TimeWindow])>,
+    ) -> ThrottleStatus {
+        let now = Local::now();
+        let mut status = ThrottleStatus::default();
+
+        if config.pause_windows.iter().any(|w| window_contains(w, now)) {
+            status.paused = true;
+            status.reasons.push("window".to_string());
+        }
+
+        status.paused_profiles = profiles
+            .into_iter()
+            .filter(|(_, windows)| windows.iter().any(|w| window_contains(w, now)))
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        let interval = Duration::from_secs(config.check_interval_seconds);
+        if self.checked_at.is_none_or(|at| at.elapsed() >= interval) {
+            self.busy = check_system(config).await;
+            self.checked_at = Some(Instant::now());
+        }
+
+        if !self.busy.is_empty() {
+            match config.action {
+                ThrottleAction::Pause => status.paused = true,
+                ThrottleAction::Reduce => status.reduced_workers = Some(config.reduced_workers),
+            }
+            status.reasons.extend(self.busy.iter().cloned());
+        }
+
+        status
+    }
+}
+
+/// Returns true if the local time falls inside the window.
+///
+/// Windows ending before they start run past midnight into the next day.
+pub fn window_contains<Tz: TimeZone>(window: &TimeWindow, now: DateTime<Tz>) -> bool {
+    let (Some(start), Some(end)) = (parse_time(&window.start), parse_time(&window.end)) else {
+        return false;
+    };
+
+    let time = now.time();
+    let today = now.weekday();
+    let yesterday = today.pred();
+    let starts_on = |day| window.days.is_empty() || window.days.contains(&day);
+
+    if start <= end {
+        starts_on(today) && time >= start && time < end
+    } else {
+        (starts_on(today) && time >= start) || (starts_on(yesterday) && time < end)
+    }
+}
+
+/// Parses an `HH:MM` time.
+pub fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+/// Returns the reasons the system is too busy for full-size jobs.
+async fn check_system(config: &ThrottleConfig) -> Vec<String> {
+    let mut busy = Vec::new();
+
+    if let Some(max) = config.max_load_average {
+        match load_average() {
+            Some(load) if load > max => {
+                debug!(load, max, "Load average above throttle limit");
+                busy.push("load".to_string());
+            }
+            Some(_) => {}
+            None => warn!("Could not read the load average"),
+        }
+    }
+
+    if let Some(min) = config.min_free_memory_mb {
+        match available_memory_mb() {
+            Some(free) if free < min => {
+                debug!(free, min, "Available memory below throttle limit");
+                busy.push("memory".to_string());
+            }
+            Some(_) => {}
+            None => warn!("Could not read available memory"),
+        }
+    }
+
+    if let Some(command) = &config.playback_command {
+        if playback_active(command).await {
+            busy.push("playback".to_string());
+        }
+    }
+
+    busy
+}
+
+/// Reads the 1-minute load average.
+fn load_average() -> Option<f64> {
+    let loadavg = std::fs::read_to_string("/proc/loadavg").ok()?;
+    loadavg.split_whitespace().next()?.parse().ok()
+}
+
+/// Reads the memory available to new processes, in MiB.
+fn available_memory_mb() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib / 1024)
+}
+
+/// Runs the playback command; exit code 0 means playback is active.
+async fn playback_active(command: &[String]) -> bool {
+    let Some((program, args)) = command.split_first() else {
+        return false;
+    };
+
+    let status = Command::new(program).args(args).kill_on_drop(true).status();
+    match tokio::time::timeout(PLAYBACK_TIMEOUT, status).await {
+        Ok(Ok(status)) => status.success(),
+        Ok(Err(e)) => {
+            warn!(program, error = %e, "Failed to run playback command");
+            false
+        }
+        Err(_) => {
+            warn!(program, "Playback command timed out");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Utc, Weekday};
+
+    use super::*;
+
+    fn window(days: &[Weekday], start: &str, end: &str) -> TimeWindow {
+        TimeWindow {
+            days: days.to_vec(),
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    /// 2024-01-05 was a Friday.
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn overnight_window_runs_into_the_next_day() {
+        let w = window(&[Weekday::Fri], "22:00", "06:00");
+
+        assert!(window_contains(&w, at(5, 22, 0)));
+        assert!(window_contains(&w, at(5, 23, 59)));
+        assert!(window_contains(&w, at(6, 0, 0)));
+        assert!(window_contains(&w, at(6, 5, 59)));
+        assert!(!window_contains(&w, at(6, 6, 0)));
+        assert!(!window_contains(&w, at(5, 21, 59)));
+    }
+
+    #[test]
+    fn overnight_window_only_starts_on_its_days() {
+        let w = window(&[Weekday::Fri], "22:00", "06:00");
+
+        // Friday morning belongs to a window starting on Thursday
+        assert!(!window_contains(&w, at(5, 3, 0)));
+        // Saturday night starts a window on Saturday
+        assert!(!window_contains(&w, at(6, 23, 0)));
+        // Sunday morning belongs to a window starting on Saturday
+        assert!(!window_contains(&w, at(7, 3, 0)));
+    }
+
+    #[test]
+    fn overnight_window_wraps_from_sunday_to_monday() {
+        let w = window(&[Weekday::Sun], "23:00", "01:00");
+
+        assert!(window_contains(&w, at(7, 23, 30)));
+        assert!(window_contains(&w, at(8, 0, 30)));
+        assert!(!window_contains(&w, at(8, 23, 30)));
+    }
+
+    #[test]
+    fn same_day_window_respects_days_and_end() {
+        let w = window(&[Weekday::Sat, Weekday::Sun], "09:00", "17:00");
+
+        assert!(window_contains(&w, at(6, 9, 0)));
+        assert!(window_contains(&w, at(7, 16, 59)));
+        assert!(!window_contains(&w, at(7, 17, 0)));
+        assert!(!window_contains(&w, at(5, 12, 0)));
+    }
+
+    #[test]
+    fn window_without_days_applies_every_day() {
+        let w = window(&[], "22:00", "06:00");
+
+        for day in 1..=7 {
+            assert!(window_contains(&w, at(day, 23, 0)));
+            assert!(window_contains(&w, at(day, 2, 0)));
+            assert!(!window_contains(&w, at(day, 12, 0)));
+        }
+    }
+
+    #[test]
+    fn window_with_invalid_times_never_matches() {
+        assert!(!window_contains(
+            &window(&[], "25:00", "06:00"),
+            at(5, 1, 0)
+        ));
+        assert!(!window_contains(&window(&[], "22:00", "6pm"), at(5, 23, 0)));
+    }
+
+    #[test]
+    fn parse_time_accepts_only_hours_and_minutes() {
+        assert_eq!(parse_time("00:00"), NaiveTime::from_hms_opt(0, 0, 0));
+        assert_eq!(parse_time("23:59"), NaiveTime::from_hms_opt(23, 59, 0));
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("12:60"), None);
+        assert_eq!(parse_time("noon"), None);
+        assert_eq!(parse_time(""), None);
+    }
+}
@@ -0,0 +1,116 @@
+//! Detection of encoder subprocesses that stop making progress.
+//!
+//! Every byte a subprocess writes to stdout or stderr, including av1an's
+//! progress lines, counts as activity. A subprocess that stays silent for
+//! the stall period is considered hung.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::error::EncoderError;
+
+/// When a subprocess last produced output.
+#[derive(Debug, Clone)]
+pub struct Activity {
+    last: Arc<Mutex<Instant>>,
+}
+
+impl Activity {
+    /// Starts tracking, counting now as the last activity.
+    pub fn new() -> Self {
+        Self {
+            last: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Records activity.
+    pub fn touch(&self) {
+        *self.last.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    /// Returns how long there has been no activity.
+    pub fn idle(&self) -> Duration {
+        self.last
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .elapsed()
+    }
+
+    /// Wraps a subprocess stream so that reading from it records activity.
+    pub fn track<R: AsyncRead + Unpin>(&self, inner: R) -> ActivityReader<R> {
+        ActivityReader {
+            inner,
+            activity: self.clone(),
+        }
+    }
+
+    /// Resolves once there has been no activity for `limit`; never with `None`.
+    pub async fn stalled(&self, limit: Option<Duration>) {
+        let Some(limit) = limit else {
+            return std::future::pending().await;
+        };
+
+        loop {
+            let idle = self.idle();
+            if idle >= limit {
+                return;
+            }
+            tokio::time::sleep(limit - idle).await;
+        }
+    }
+}
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stream reader that records activity whenever data arrives.
+#[derive(Debug)]
+pub struct ActivityReader<R> {
+    inner: R,
+    activity: Activity,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for ActivityReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let before = buf.filled().len();
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if matches!(result, Poll::Ready(Ok(()))) && buf.filled().len() > before {
+            self.activity.touch();
+        }
+        result
+    }
+}
+
+/// Runs a phase, failing it as stalled once it exceeds its time limit.
+///
+/// Dropping the phase kills its subprocesses.
+pub async fn phase_timeout<T>(
+    phase: &str,
+    seconds: Option<u64>,
+    future: impl Future<Output = Result<T, EncoderError>>,
+) -> Result<T, EncoderError> {
+    let Some(seconds) = seconds else {
+        return future.await;
+    };
+
+    tokio::time::timeout(Duration::from_secs(seconds), future)
+        .await
+        .unwrap_or_else(|_| {
+            Err(EncoderError::Stalled {
+                phase: phase.to_string(),
+                reason: format!("exceeded its time limit of {} seconds", seconds),
+            })
+        })
+}
@@ -1,30 +1,60 @@
 //! Encoding worker that processes jobs from the queue.
 
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::Result;
+use serde::Serialize;
 use tokio::sync::{mpsc, RwLock};
 use tracing::{error, info, warn};
 
+use super::ffmpeg::ExtractedSubtitle;
+use super::limits::ProcessControl;
+use super::phases::{tracked, PhaseEvent, PhaseProgress};
+use super::plan::{self, EncodePlan, WorkFiles};
+use super::watchdog::phase_timeout;
 use super::{av1an, ffmpeg, mkvmerge};
-use crate::config::model::{AppConfig, Profile};
-use crate::error::EncoderError;
-use crate::media::{audio, probe, subtitle};
+use crate::config::model::{
+    AppConfig, HookEvent, PhaseTimeouts, Profile, ProfileMode, RetryConfig, SizeGuard,
+    SizeGuardAction, SkipAction,
+};
+use crate::error::{EncoderError, ErrorCategory};
+use crate::media::audio::{self, AudioDecision};
+use crate::media::subtitle::{self, SubtitleDecision, SubtitleTrackAction};
+use crate::media::{probe, provenance, skip};
+use crate::notify::prometheus::Metrics;
+use crate::notify::{DiscordNotifier, HookRunner};
 use crate::queue::dead_letter::{DeadLetterHandler, FailureAction};
-use crate::queue::job::{EncodeJob, EncodeResultMetadata};
+use crate::queue::job::{
+    EncodeJob, EncodeResultMetadata, EncodeSettings, JobStatus, ProgressDetail, SizeDecision,
+};
 use crate::queue::QueueManager;
 
 /// Worker that processes encoding jobs from the queue.
+///
+/// Clones share the queue connection and configuration, one per running job.
+#[derive(Clone)]
 pub struct EncodeWorker {
     /// Queue manager for fetching and updating jobs.
     queue: QueueManager,
     /// Current configuration.
     config: Arc<RwLock<AppConfig>>,
-    /// Maximum retry attempts.
-    max_attempts: u32,
+    /// Retry policies for failed jobs.
+    retry: RetryConfig,
     /// Channel for progress updates.
     progress_tx: Option<mpsc::Sender<WorkerProgress>>,
+    /// Runner for user-defined lifecycle hooks.
+    hooks: Arc<HookRunner>,
+    /// Discord notifier, if configured.
+    discord: Option<Arc<DiscordNotifier>>,
+    /// Plan jobs without running any encoder or touching outputs.
+    dry_run: bool,
+    /// Metrics updated with per-job progress, if enabled.
+    metrics: Option<Arc<Metrics>>,
+    /// Cap on the profile's av1an workers, set while the system is throttled.
+    workers_limit: Option<usize>,
 }
 
 /// Progress update from the worker.
@@ -36,15 +66,22 @@ pub struct WorkerProgress {
     pub percent: f32,
     /// Current phase.
     pub phase: EncodePhase,
+    /// Encoding speed reported by av1an, if known.
+    pub speed: Option<String>,
+    /// Estimated time remaining reported by av1an, if known.
+    pub eta: Option<String>,
 }
 
 /// Current phase of encoding.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EncodePhase {
     /// Analyzing source file.
     Analyzing,
     /// Encoding video.
     EncodingVideo,
+    /// Burning subtitles into the video.
+    BurningSubtitles,
     /// Processing audio.
     ProcessingAudio,
     /// Extracting subtitles.
@@ -55,53 +92,200 @@ pub enum EncodePhase {
     Verifying,
 }
 
+impl std::fmt::Display for EncodePhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Analyzing => write!(f, "analyzing"),
+            Self::EncodingVideo => write!(f, "encoding_video"),
+            Self::BurningSubtitles => write!(f, "burning_subtitles"),
+            Self::ProcessingAudio => write!(f, "processing_audio"),
+            Self::ExtractingSubtitles => write!(f, "extracting_subtitles"),
+            Self::Muxing => write!(f, "muxing"),
+            Self::Verifying => write!(f, "verifying"),
+        }
+    }
+}
+
 impl EncodeWorker {
     /// Creates a new encode worker.
     pub fn new(
         queue: QueueManager,
         config: Arc<RwLock<AppConfig>>,
-        max_attempts: u32,
+        retry: RetryConfig,
         progress_tx: Option<mpsc::Sender<WorkerProgress>>,
+        hooks: Arc<HookRunner>,
+        discord: Option<Arc<DiscordNotifier>>,
+        dry_run: bool,
     ) -> Self {
         Self {
             queue,
             config,
-            max_attempts,
+            retry,
             progress_tx,
+            hooks,
+            discord,
+            dry_run,
+            metrics: None,
+            workers_limit: None,
         }
     }
 
-    /// Runs the worker loop, processing jobs from the queue.
-    pub async fn run(&mut self) -> Result<()> {
-        info!("Starting encode worker");
-
-        loop {
-            // Try to get a job from the queue
-            match self.queue.dequeue().await {
-                Ok(Some(mut job)) => {
-                    info!(job_id = %job.id, input = ?job.input_path, "Processing job");
-
-                    match self.process_job(&mut job).await {
-                        Ok(()) => {
-                            info!(job_id = %job.id, "Job completed successfully");
-                            self.queue.complete_job(&job).await?;
-                        }
-                        Err(e) => {
-                            error!(job_id = %job.id, error = %e, "Job failed");
-                            self.handle_failure(job, e.to_string()).await?;
-                        }
-                    }
+    /// Reports per-job progress to the given metrics.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Returns true if jobs are only planned, not encoded.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Caps the av1an workers of the profile, or removes the cap with `None`.
+    pub fn with_workers_limit(mut self, limit: Option<usize>) -> Self {
+        self.workers_limit = limit;
+        self
+    }
+
+    /// Returns the job's profile with its overrides, the retry step for the
+    /// current attempt and the workers cap applied.
+    fn job_profile(&self, job: &EncodeJob, profile: &Profile) -> Profile {
+        if let Some((index, _)) = profile.retry_step(job.attempt_count) {
+            info!(job_id = %job.id, attempt = job.attempt_count, step = index, "Applying retry step");
+        }
+        let mut profile = job.profile_for_attempt(profile, job.attempt_count);
+        if let Some(limit) = self.workers_limit {
+            profile.workers = profile.workers.min(limit);
+        }
+        profile
+    }
+
+    /// Runs a job taken from the queue through to completion, failure or cancellation.
+    pub async fn run_job(&mut self, mut job: EncodeJob) -> Result<()> {
+        if self.dry_run {
+            info!(job_id = %job.id, input = ?job.input_path, "Planning job (dry run)");
+
+            // The job stays queued for real workers, so a failed plan is only logged
+            if let Err(e) = self.plan_job(&job).await {
+                error!(job_id = %job.id, error = %e, "Job planning failed");
+            }
+            return Ok(());
+        }
+
+        info!(job_id = %job.id, input = ?job.input_path, "Processing job");
+
+        let result = match self.process_job(&mut job).await {
+            Ok(()) if job.status == JobStatus::Skipped => Ok(()),
+            Ok(()) => self
+                .hooks
+                .dispatch(HookEvent::Succeeded, &job)
+                .await
+                .map_err(|e| EncoderError::HookFailed(e.to_string())),
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(()) if job.status == JobStatus::Skipped => {
+                info!(job_id = %job.id, "Job skipped");
+                self.queue.complete_job(&job).await?;
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_skip(SkipAction::Skip);
                 }
-                Ok(None) => {
-                    // No jobs in queue, wait before checking again
-                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            }
+            Ok(()) => {
+                info!(job_id = %job.id, "Job completed successfully");
+                self.queue.complete_job(&job).await?;
+                if let Some(metrics) = &self.metrics {
+                    // Remuxes and clean-ups spared by a skip rule are not encodes
+                    match (&job.skip, &job.result_metadata) {
+                        (Some(decision), _) => metrics.record_skip(decision.action),
+                        (None, Some(metadata)) => metrics.record_success(metadata),
+                        (None, None) => {}
+                    }
                 }
-                Err(e) => {
-                    error!(error = %e, "Failed to dequeue job");
-                    tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+                if let Some(discord) = &self.discord {
+                    if let Err(e) = discord.notify_encode_success(&job).await {
+                        warn!(error = %e, "Failed to send success notification");
+                    }
                 }
             }
+            Err(EncoderError::Cancelled) => {
+                info!(job_id = %job.id, "Job cancelled");
+                job.cancel();
+                self.queue.finish_cancelled(&job).await?;
+            }
+            Err(e) => {
+                error!(job_id = %job.id, error = %e, category = %e.category(), "Job failed");
+                self.handle_failure(job, e.to_string(), e.category())
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Plans a job and reports what would run, without encoding.
+    ///
+    /// Only ffprobe is run. The job is left untouched in the queue, and no
+    /// hooks run because they usually act on the output, which a dry run
+    /// never writes.
+    async fn plan_job(&mut self, job: &EncodeJob) -> Result<(), EncoderError> {
+        let profile = {
+            let config = self.config.read().await;
+            config
+                .profiles
+                .iter()
+                .find(|p| p.name == job.profile_name)
+                .map(|p| self.job_profile(job, p))
+                .ok_or_else(|| EncoderError::ProfileNotFound {
+                    name: job.profile_name.clone(),
+                })?
+        };
+
+        let temp_dir = std::env::temp_dir().join(format!("encode_{}", job.id));
+        let plan = EncodePlan::build(&job.input_path, &job.output_path, &profile, &temp_dir)?;
+
+        for decision in &plan.audio {
+            info!(
+                job_id = %job.id,
+                stream = decision.stream.index,
+                language = decision.stream.language.as_deref().unwrap_or("und"),
+                rule = ?decision.matched_rule,
+                action = %decision.action,
+                "Dry run: audio decision"
+            );
+        }
+        for decision in &plan.subtitles {
+            info!(
+                job_id = %job.id,
+                stream = decision.stream.index,
+                language = decision.stream.language.as_deref().unwrap_or("und"),
+                action = %decision.action,
+                "Dry run: subtitle decision"
+            );
+        }
+        if let Some(skip) = &plan.skip {
+            info!(
+                job_id = %job.id,
+                rule = %skip.rule,
+                action = %skip.action,
+                reason = %skip.reason,
+                "Dry run: source matches a skip rule"
+            );
+        }
+        for planned in &plan.commands {
+            info!(job_id = %job.id, phase = %planned.phase, command = %planned.command, "Dry run: would run");
+        }
+
+        info!(job_id = %job.id, output = ?job.output_path, "Dry run complete");
+
+        if let Some(discord) = &self.discord {
+            if let Err(e) = discord.notify_dry_run(job, &plan).await {
+                warn!(error = %e, "Failed to send dry run notification");
+            }
         }
+
+        Ok(())
     }
 
     /// Processes a single encoding job.
@@ -109,24 +293,41 @@ impl EncodeWorker {
         job.start();
         self.queue.update_job(job).await.ok();
 
+        self.hooks
+            .dispatch(HookEvent::Started, job)
+            .await
+            .map_err(|e| EncoderError::HookFailed(e.to_string()))?;
+
         let config = self.config.read().await;
         let profile = config
             .profiles
             .iter()
             .find(|p| p.name == job.profile_name)
-            .ok_or_else(|| EncoderError::SpawnFailed(format!(
-                "Profile '{}' not found",
-                job.profile_name
-            )))?
-            .clone();
+            .ok_or_else(|| EncoderError::ProfileNotFound {
+                name: job.profile_name.clone(),
+            })
+            .map(|p| self.job_profile(job, p))?;
+        let mut limits = ProcessControl::new(&config.global.process, &profile.process)?
+            .with_stall_timeout(config.global.watchdog.stall_timeout_seconds);
+        let timeouts = config.global.watchdog.phase_timeouts.clone();
         drop(config);
+        limits.create_cgroup(&job.id)?;
 
         // Create temp directory for this job
         let temp_dir = std::env::temp_dir().join(format!("encode_{}", job.id));
-        std::fs::create_dir_all(&temp_dir)
-            .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+        if let Err(e) = std::fs::create_dir_all(&temp_dir) {
+            limits.remove_cgroup().await;
+            return Err(EncoderError::SpawnFailed(e.to_string()));
+        }
 
-        let result = self.run_encode_pipeline(job, &profile, &temp_dir).await;
+        // Dropping the pipeline future kills any running subprocess
+        let mut cancel_queue = self.queue.clone();
+        let job_id = job.id.clone();
+        let result = tokio::select! {
+            result = self.run_encode_pipeline(job, &profile, &temp_dir, &limits, &timeouts) => result,
+            _ = wait_for_cancel(&mut cancel_queue, &job_id) => Err(EncoderError::Cancelled),
+        };
+        limits.remove_cgroup().await;
 
         // Clean up temp directory
         if let Err(e) = std::fs::remove_dir_all(&temp_dir) {
@@ -137,80 +338,63 @@ impl EncodeWorker {
     }
 
     /// Runs the full encoding pipeline.
+    ///
+    /// Subtitle extraction and audio processing run alongside the video
+    /// encode; only muxing waits for all of them.
     async fn run_encode_pipeline(
         &mut self,
         job: &mut EncodeJob,
         profile: &Profile,
-        temp_dir: &PathBuf,
+        temp_dir: &Path,
+        limits: &ProcessControl,
+        timeouts: &PhaseTimeouts,
     ) -> Result<(), EncoderError> {
         let start_time = std::time::Instant::now();
 
         // Phase 1: Analyze source
-        self.send_progress(job, 0.0, EncodePhase::Analyzing).await;
-        let probe_result = probe::probe(&job.input_path)
-            .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
+        self.report_progress(job, 0.0, EncodePhase::Analyzing, None, None)
+            .await;
+        let probe_result = plan::probe_input(&job.input_path)?;
 
         // Phase 2: Determine audio and subtitle handling
-        let audio_decisions = audio::process_audio_streams(&probe_result.audio_streams, &profile.audio);
-        let subtitle_decisions = subtitle::process_subtitle_streams(&probe_result.subtitle_streams, &profile.subtitles);
-
-        // Phase 3: Extract subtitles
-        self.send_progress(job, 5.0, EncodePhase::ExtractingSubtitles).await;
-        let extracted_subs = ffmpeg::extract_subtitles(&job.input_path, temp_dir, &subtitle_decisions).await?;
-
-        // Check if we need to burn in subtitles
-        let burn_in_sub = extracted_subs.iter().find(|s| s.should_burn_in);
-
-        // Phase 4: Encode video
-        self.send_progress(job, 10.0, EncodePhase::EncodingVideo).await;
-        let video_output = temp_dir.join("video.mkv");
-
-        // Set up progress channel for av1an
-        let (progress_tx, mut progress_rx) = mpsc::channel(100);
-
-        // Spawn av1an with progress tracking
-        let input = job.input_path.clone();
-        let output = video_output.clone();
-        let profile_clone = profile.clone();
-
-        let encode_handle = tokio::spawn(async move {
-            av1an::encode(&input, &output, &profile_clone, Some(progress_tx)).await
-        });
-
-        // Forward progress updates
-        let job_id = job.id.clone();
-        let self_progress_tx = self.progress_tx.clone();
-        tokio::spawn(async move {
-            while let Some(progress) = progress_rx.recv().await {
-                if let Some(tx) = &self_progress_tx {
-                    let _ = tx.send(WorkerProgress {
-                        job_id: job_id.clone(),
-                        percent: 10.0 + (progress.percent * 0.7), // 10% to 80%
-                        phase: EncodePhase::EncodingVideo,
-                    }).await;
+        let audio_decisions =
+            audio::process_audio_streams(&probe_result.audio_streams, &profile.audio);
+        let subtitle_decisions =
+            subtitle::process_subtitle_streams(&probe_result.subtitle_streams, &profile.subtitles);
+
+        // Sources matched by a skip rule are not re-encoded
+        let skip_action = match skip::evaluate(&profile.skip_rules, &probe_result) {
+            Some(decision) => {
+                info!(
+                    job_id = %job.id,
+                    rule = %decision.rule,
+                    action = %decision.action,
+                    reason = %decision.reason,
+                    "Source matched a skip rule"
+                );
+                if decision.action == SkipAction::Skip {
+                    job.skip(decision);
+                    return Ok(());
                 }
+                let action = decision.action;
+                job.skip = Some(decision);
+                Some(action)
             }
-        });
-
-        // Wait for encode to complete
-        encode_handle.await.map_err(|e| EncoderError::SpawnFailed(e.to_string()))??;
-
-        // Phase 5: Handle subtitle burn-in if needed
-        let final_video = if let Some(sub) = burn_in_sub {
-            let burned_output = temp_dir.join("video_burned.mkv");
-            ffmpeg::burn_subtitles(&video_output, &sub.path, &burned_output, true).await?;
-            burned_output
-        } else {
-            video_output
+            None => None,
         };
-
-        // Phase 6: Process audio
-        self.send_progress(job, 85.0, EncodePhase::ProcessingAudio).await;
-        let audio_output = temp_dir.join("audio.mka");
-        ffmpeg::process_audio(&job.input_path, &audio_output, &audio_decisions).await?;
-
-        // Phase 7: Mux final output
-        self.send_progress(job, 95.0, EncodePhase::Muxing).await;
+        let keep_video =
+            profile.mode == ProfileMode::Remux || skip_action == Some(SkipAction::CleanupOnly);
+
+        let mut progress = PhaseProgress::new(&pipeline_phases(
+            skip_action,
+            keep_video,
+            &subtitle_decisions,
+        ));
+        progress.finish(EncodePhase::Analyzing);
+
+        let files = WorkFiles::new(temp_dir);
+        std::fs::write(&files.tags, provenance::tags_xml(&profile.name))
+            .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
 
         // Ensure output directory exists
         if let Some(parent) = job.output_path.parent() {
@@ -218,16 +402,96 @@ impl EncodeWorker {
                 .map_err(|e| EncoderError::SpawnFailed(e.to_string()))?;
         }
 
-        mkvmerge::mux(&final_video, &audio_output, &extracted_subs, &job.output_path).await?;
+        let mut profile = Cow::Borrowed(profile);
+        let size_decision = if skip_action == Some(SkipAction::RemuxOnly) {
+            // Remux the source unchanged
+            self.start_phase(job, &mut progress, EncodePhase::Muxing)
+                .await;
+            phase_timeout(
+                "muxing",
+                timeouts.muxing,
+                mkvmerge::remux(&job.input_path, &job.output_path, Some(&files.tags), limits),
+            )
+            .await?;
+            None
+        } else {
+            // Phases 3-6: Extract subtitles, encode video, burn in subtitles and process audio
+            let (final_video, extracted_subs) = self
+                .run_media_phases(
+                    job,
+                    &profile,
+                    &files,
+                    temp_dir,
+                    &audio_decisions,
+                    &subtitle_decisions,
+                    keep_video,
+                    &mut progress,
+                    limits,
+                    timeouts,
+                )
+                .await?;
+
+            // Phase 7: Mux final output
+            self.start_phase(job, &mut progress, EncodePhase::Muxing)
+                .await;
+            phase_timeout(
+                "muxing",
+                timeouts.muxing,
+                mkvmerge::mux(
+                    &final_video,
+                    &files.audio,
+                    &extracted_subs,
+                    &job.output_path,
+                    Some(&files.tags),
+                    limits,
+                ),
+            )
+            .await?;
+
+            // Phase 8: Check the output against the size rules
+            let size_decision = match profile.size_guard.clone() {
+                Some(guard) if !keep_video && skip_action.is_none() => Some(
+                    self.enforce_size_guard(
+                        job,
+                        &mut profile,
+                        &guard,
+                        &files,
+                        &extracted_subs,
+                        &mut progress,
+                        limits,
+                        timeouts,
+                    )
+                    .await?,
+                ),
+                _ => None,
+            };
+
+            // Remuxes exist to tidy up tracks, so they also get names and flags
+            if profile.mode == ProfileMode::Remux {
+                let labels = mkvmerge::output_track_labels(&audio_decisions, &extracted_subs);
+                phase_timeout(
+                    "muxing",
+                    timeouts.muxing,
+                    mkvmerge::set_track_properties(&job.output_path, &labels, limits),
+                )
+                .await?;
+            }
 
-        // Phase 8: Verify output
-        self.send_progress(job, 99.0, EncodePhase::Verifying).await;
+            size_decision
+        };
+
+        // Phase 9: Verify output
+        progress.finish(EncodePhase::Muxing);
+        self.start_phase(job, &mut progress, EncodePhase::Verifying)
+            .await;
         let output_probe = probe::probe(&job.output_path)
             .map_err(|e| EncoderError::VerificationFailed(e.to_string()))?;
 
         // Verify output has video
         if output_probe.video_streams.is_empty() {
-            return Err(EncoderError::VerificationFailed("No video stream in output".to_string()));
+            return Err(EncoderError::VerificationFailed(
+                "No video stream in output".to_string(),
+            ));
         }
 
         // Build result metadata
@@ -239,25 +503,320 @@ impl EncodeWorker {
             vmaf_score: None, // TODO: Could be parsed from av1an output
             video_duration_secs: probe_result.info.duration,
             encoding_speed: probe_result.info.duration / encode_duration,
+            // The profile still carries its ladder, so the applied step can be looked up
+            settings: (skip_action.is_none() && profile.mode == ProfileMode::Encode).then(|| {
+                EncodeSettings::from_profile(
+                    &profile,
+                    profile
+                        .retry_step(job.attempt_count)
+                        .map(|(index, _)| index),
+                )
+            }),
+            size_decision,
         };
 
         job.complete(metadata);
-        self.send_progress(job, 100.0, EncodePhase::Verifying).await;
+        progress.complete();
+        self.send_progress(job, &progress).await;
 
         Ok(())
     }
 
-    /// Handles a job failure.
-    async fn handle_failure(&mut self, job: EncodeJob, error: String) -> Result<()> {
-        let mut handler = DeadLetterHandler::new(&mut self.queue, self.max_attempts);
+    /// Extracts subtitles, encodes the video, burns in subtitles and processes audio.
+    ///
+    /// Subtitle extraction and audio processing run alongside the video
+    /// encode; burn-in waits for both the encode and the extracted subtitles.
+    /// Returns the video to mux and the extracted subtitles.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_media_phases(
+        &mut self,
+        job: &mut EncodeJob,
+        profile: &Profile,
+        files: &WorkFiles,
+        temp_dir: &Path,
+        audio_decisions: &[AudioDecision],
+        subtitle_decisions: &[SubtitleDecision],
+        keep_video: bool,
+        progress: &mut PhaseProgress,
+        limits: &ProcessControl,
+        timeouts: &PhaseTimeouts,
+    ) -> Result<(PathBuf, Vec<ExtractedSubtitle>), EncoderError> {
+        let input = job.input_path.clone();
+        let (events_tx, events_rx) = mpsc::channel::<PhaseEvent>(16);
+        let (progress_tx, progress_rx) = mpsc::channel::<av1an::EncodeProgress>(100);
+
+        let subtitles = tracked(
+            EncodePhase::ExtractingSubtitles,
+            &events_tx,
+            phase_timeout(
+                "extracting_subtitles",
+                timeouts.extracting_subtitles,
+                ffmpeg::extract_subtitles(&input, temp_dir, subtitle_decisions, limits),
+            ),
+        );
+        let video = async {
+            if keep_video {
+                return Ok(None);
+            }
+            encode_video_phase(
+                &input,
+                profile,
+                files,
+                &events_tx,
+                progress_tx,
+                limits,
+                timeouts,
+            )
+            .await
+            .map(Some)
+        };
+
+        // Burn-in needs both the encoded video and the extracted subtitles
+        let video_and_subtitles = async {
+            let (mut subtitles, video) = tokio::try_join!(subtitles, video)?;
+            let final_video = match video {
+                // Burning in needs a re-encode, so such tracks are kept as regular tracks
+                None => {
+                    for sub in &mut subtitles {
+                        sub.should_burn_in = false;
+                    }
+                    input.clone()
+                }
+                Some(video) => {
+                    let burn_in_sub = subtitles.iter().find(|s| s.should_burn_in);
+                    burn_in_phase(video, burn_in_sub, files, &events_tx, limits, timeouts).await?
+                }
+            };
+            Ok::<_, EncoderError>((final_video, subtitles))
+        };
+
+        let audio = tracked(
+            EncodePhase::ProcessingAudio,
+            &events_tx,
+            phase_timeout(
+                "processing_audio",
+                timeouts.processing_audio,
+                ffmpeg::process_audio(&input, &files.audio, audio_decisions, limits),
+            ),
+        );
+
+        let phases = async { tokio::try_join!(video_and_subtitles, audio) };
+        let (output, ()) = self
+            .drive_phases(job, progress, phases, events_rx, progress_rx)
+            .await?;
+        Ok(output)
+    }
 
-        match handler.handle_failure(job, error).await {
-            Ok(FailureAction::Retrying { attempt, max_attempts }) => {
-                info!(attempt, max_attempts, "Job will be retried");
+    /// Waits for a set of phases, reporting their combined progress as they run.
+    async fn drive_phases<T>(
+        &mut self,
+        job: &mut EncodeJob,
+        progress: &mut PhaseProgress,
+        phases: impl Future<Output = Result<T, EncoderError>>,
+        mut events_rx: mpsc::Receiver<PhaseEvent>,
+        mut progress_rx: mpsc::Receiver<av1an::EncodeProgress>,
+    ) -> Result<T, EncoderError> {
+        tokio::pin!(phases);
+
+        let result = loop {
+            tokio::select! {
+                result = &mut phases => break result,
+                Some(event) = events_rx.recv() => {
+                    progress.apply(event);
+                    self.send_progress(job, progress).await;
+                }
+                Some(encode) = progress_rx.recv() => {
+                    progress.update(EncodePhase::EncodingVideo, encode.percent / 100.0);
+                    self.report_progress(job, progress.percent(), progress.current(), encode.speed, encode.eta)
+                        .await;
+                }
             }
-            Ok(FailureAction::DeadLettered { reason }) => {
-                warn!(reason, "Job moved to dead letter queue");
-                // TODO: Send notification
+        };
+
+        // Phases that finished last may not have been reported yet
+        while let Ok(event) = events_rx.try_recv() {
+            progress.apply(event);
+        }
+
+        result
+    }
+
+    /// Checks the muxed output against the profile's size rules and acts on a violation.
+    ///
+    /// Retargeting lowers the profile's VMAF target in place, so the result
+    /// metadata records the target of the kept encode. Once the floor is
+    /// reached without meeting the rules, the source is kept.
+    #[allow(clippy::too_many_arguments)]
+    async fn enforce_size_guard(
+        &mut self,
+        job: &mut EncodeJob,
+        profile: &mut Cow<'_, Profile>,
+        guard: &SizeGuard,
+        files: &WorkFiles,
+        subtitles: &[ExtractedSubtitle],
+        progress: &mut PhaseProgress,
+        limits: &ProcessControl,
+        timeouts: &PhaseTimeouts,
+    ) -> Result<SizeDecision, EncoderError> {
+        let input_size = file_size(&job.input_path)?;
+        let encoded_size = file_size(&job.output_path)?;
+        let mut output_size = encoded_size;
+        let mut retargeted = false;
+
+        while guard.violated(input_size, output_size) {
+            let vmaf_target = profile.vmaf_target - guard.vmaf_step;
+            let action = match guard.action {
+                SizeGuardAction::Retarget if vmaf_target < guard.vmaf_floor => {
+                    SizeGuardAction::KeepSource
+                }
+                action => action,
+            };
+            warn!(job_id = %job.id, input_size, output_size, ?action, "Output breaks the size rules");
+
+            match action {
+                SizeGuardAction::KeepSource => {
+                    // Remuxed rather than copied, so any source ends up as MKV with provenance tags
+                    self.start_phase(job, progress, EncodePhase::Muxing).await;
+                    phase_timeout(
+                        "muxing",
+                        timeouts.muxing,
+                        mkvmerge::remux(
+                            &job.input_path,
+                            &job.output_path,
+                            Some(&files.tags),
+                            limits,
+                        ),
+                    )
+                    .await?;
+                    return Ok(SizeDecision::KeptSource { encoded_size });
+                }
+                SizeGuardAction::RemuxOnly => {
+                    self.start_phase(job, progress, EncodePhase::Muxing).await;
+                    phase_timeout(
+                        "muxing",
+                        timeouts.muxing,
+                        mkvmerge::mux(
+                            &job.input_path,
+                            &files.audio,
+                            subtitles,
+                            &job.output_path,
+                            Some(&files.tags),
+                            limits,
+                        ),
+                    )
+                    .await?;
+                    return Ok(SizeDecision::RemuxedSource { encoded_size });
+                }
+                SizeGuardAction::DeadLetter => {
+                    if let Err(e) = std::fs::remove_file(&job.output_path) {
+                        warn!(error = %e, "Failed to remove rejected output");
+                    }
+                    return Err(EncoderError::OutputTooLarge {
+                        input_size,
+                        output_size,
+                    });
+                }
+                SizeGuardAction::Retarget => {
+                    info!(job_id = %job.id, vmaf_target, "Re-encoding with a lower VMAF target");
+                    profile.to_mut().vmaf_target = vmaf_target;
+
+                    // av1an refuses to overwrite an existing output
+                    let _ = std::fs::remove_file(&files.video);
+                    let input = job.input_path.clone();
+                    let (events_tx, events_rx) = mpsc::channel::<PhaseEvent>(16);
+                    let (progress_tx, progress_rx) = mpsc::channel::<av1an::EncodeProgress>(100);
+                    let video = async {
+                        let video = encode_video_phase(
+                            &input,
+                            profile,
+                            files,
+                            &events_tx,
+                            progress_tx,
+                            limits,
+                            timeouts,
+                        )
+                        .await?;
+                        let subtitle = subtitles.iter().find(|s| s.should_burn_in);
+                        burn_in_phase(video, subtitle, files, &events_tx, limits, timeouts).await
+                    };
+                    let video = self
+                        .drive_phases(job, progress, video, events_rx, progress_rx)
+                        .await?;
+
+                    self.start_phase(job, progress, EncodePhase::Muxing).await;
+                    phase_timeout(
+                        "muxing",
+                        timeouts.muxing,
+                        mkvmerge::mux(
+                            &video,
+                            &files.audio,
+                            subtitles,
+                            &job.output_path,
+                            Some(&files.tags),
+                            limits,
+                        ),
+                    )
+                    .await?;
+                    output_size = file_size(&job.output_path)?;
+                    retargeted = true;
+                }
+            }
+        }
+
+        if !retargeted {
+            Ok(SizeDecision::Accepted)
+        } else {
+            Ok(SizeDecision::Retargeted {
+                encoded_size,
+                vmaf_target: profile.vmaf_target,
+            })
+        }
+    }
+
+    /// Handles a job failure according to the retry policy of its category.
+    async fn handle_failure(
+        &mut self,
+        mut job: EncodeJob,
+        error: String,
+        category: ErrorCategory,
+    ) -> Result<()> {
+        // Hooks and notifications see the failure, not the job reset for retry
+        job.fail(error.clone(), category);
+        let failed = job.clone();
+
+        let mut handler = DeadLetterHandler::new(&mut self.queue, &self.retry);
+        let action = handler.handle_failure(&mut job, error, category).await;
+
+        // Failure hooks only ever log, so their result is ignored.
+        let _ = self.hooks.dispatch(HookEvent::Failed, &failed).await;
+
+        match action {
+            Ok(FailureAction::Retrying {
+                attempt,
+                max_attempts,
+                category,
+            }) => {
+                info!(attempt, max_attempts, %category, retry_at = ?job.retry_at, "Job will be retried");
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_failure(category);
+                }
+                if let Some(discord) = &self.discord {
+                    if let Err(e) = discord.notify_encode_failure(&failed).await {
+                        warn!(error = %e, "Failed to send failure notification");
+                    }
+                }
+            }
+            Ok(FailureAction::DeadLettered { reason, category }) => {
+                warn!(reason, %category, "Job moved to dead letter queue");
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_dead_letter(category);
+                }
+                let _ = self.hooks.dispatch(HookEvent::DeadLettered, &job).await;
+                if let Some(discord) = &self.discord {
+                    if let Err(e) = discord.notify_dead_letter(&job).await {
+                        warn!(error = %e, "Failed to send dead letter notification");
+                    }
+                }
             }
             Err(e) => {
                 error!(error = %e, "Failed to handle job failure");
@@ -267,14 +826,173 @@ impl EncodeWorker {
         Ok(())
     }
 
-    /// Sends a progress update.
-    async fn send_progress(&self, job: &EncodeJob, percent: f32, phase: EncodePhase) {
+    /// Sends a progress update for the job's phases.
+    async fn send_progress(&mut self, job: &mut EncodeJob, progress: &PhaseProgress) {
+        self.report_progress(job, progress.percent(), progress.current(), None, None)
+            .await;
+    }
+
+    /// Marks a phase as started and sends a progress update.
+    async fn start_phase(
+        &mut self,
+        job: &mut EncodeJob,
+        progress: &mut PhaseProgress,
+        phase: EncodePhase,
+    ) {
+        progress.start(phase);
+        self.send_progress(job, progress).await;
+    }
+
+    /// Sends a progress update with encoder speed and ETA.
+    ///
+    /// The job record in Redis is updated whenever the phase or the whole
+    /// percentage changes, so other processes can follow the encode.
+    async fn report_progress(
+        &mut self,
+        job: &mut EncodeJob,
+        percent: f32,
+        phase: EncodePhase,
+        speed: Option<String>,
+        eta: Option<String>,
+    ) {
+        if job.status == JobStatus::InProgress {
+            let phase_name = phase.to_string();
+            let phase_changed = job
+                .progress_detail
+                .as_ref()
+                .is_none_or(|d| d.phase != phase_name);
+            let percent_changed = job.progress.map(f32::floor) != Some(percent.floor());
+
+            if phase_changed || percent_changed {
+                job.update_progress(percent);
+                job.progress_detail = Some(ProgressDetail {
+                    phase: phase_name,
+                    speed: speed.clone(),
+                    eta: eta.clone(),
+                });
+                if let Err(e) = self.queue.update_job(job).await {
+                    warn!(job_id = %job.id, error = %e, "Failed to persist job progress");
+                }
+            }
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.set_job_progress(&job.id, &job.profile_name, percent);
+        }
+
         if let Some(tx) = &self.progress_tx {
-            let _ = tx.send(WorkerProgress {
-                job_id: job.id.clone(),
-                percent,
-                phase,
-            }).await;
+            let _ = tx
+                .send(WorkerProgress {
+                    job_id: job.id.clone(),
+                    percent,
+                    phase,
+                    speed,
+                    eta,
+                })
+                .await;
+        }
+    }
+}
+
+/// Lists the phases a job runs, in the order they start.
+fn pipeline_phases(
+    skip_action: Option<SkipAction>,
+    keep_video: bool,
+    subtitle_decisions: &[SubtitleDecision],
+) -> Vec<EncodePhase> {
+    let mut phases = vec![EncodePhase::Analyzing];
+    if skip_action != Some(SkipAction::RemuxOnly) {
+        phases.extend([
+            EncodePhase::ExtractingSubtitles,
+            EncodePhase::ProcessingAudio,
+        ]);
+        if !keep_video {
+            phases.push(EncodePhase::EncodingVideo);
+            if subtitle_decisions
+                .iter()
+                .any(|d| matches!(d.action, SubtitleTrackAction::BurnIn))
+            {
+                phases.push(EncodePhase::BurningSubtitles);
+            }
+        }
+    }
+    phases.extend([EncodePhase::Muxing, EncodePhase::Verifying]);
+    phases
+}
+
+/// Encodes the video with av1an, returning the encoded file.
+async fn encode_video_phase(
+    input: &Path,
+    profile: &Profile,
+    files: &WorkFiles,
+    events: &mpsc::Sender<PhaseEvent>,
+    progress_tx: mpsc::Sender<av1an::EncodeProgress>,
+    limits: &ProcessControl,
+    timeouts: &PhaseTimeouts,
+) -> Result<PathBuf, EncoderError> {
+    tracked(
+        EncodePhase::EncodingVideo,
+        events,
+        phase_timeout(
+            "encoding_video",
+            timeouts.encoding_video,
+            av1an::encode(
+                input,
+                &files.video,
+                &files.av1an_temp,
+                profile,
+                limits,
+                Some(progress_tx),
+            ),
+        ),
+    )
+    .await?;
+    Ok(files.video.clone())
+}
+
+/// Burns a subtitle into the encoded video, returning the video to mux.
+async fn burn_in_phase(
+    video: PathBuf,
+    subtitle: Option<&ExtractedSubtitle>,
+    files: &WorkFiles,
+    events: &mpsc::Sender<PhaseEvent>,
+    limits: &ProcessControl,
+    timeouts: &PhaseTimeouts,
+) -> Result<PathBuf, EncoderError> {
+    let Some(subtitle) = subtitle else {
+        return Ok(video);
+    };
+
+    tracked(
+        EncodePhase::BurningSubtitles,
+        events,
+        phase_timeout(
+            "burning_subtitles",
+            timeouts.burning_subtitles,
+            ffmpeg::burn_subtitles(&video, &subtitle.path, &files.burned_video, true, limits),
+        ),
+    )
+    .await?;
+    Ok(files.burned_video.clone())
+}
+
+/// Returns the size of a file in bytes.
+fn file_size(path: &Path) -> Result<u64, EncoderError> {
+    std::fs::metadata(path).map(|m| m.len()).map_err(|e| {
+        EncoderError::VerificationFailed(format!(
+            "Failed to read size of '{}': {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Resolves once cancellation has been requested for the job.
+async fn wait_for_cancel(queue: &mut QueueManager, job_id: &str) {
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        if let Ok(true) = queue.is_cancel_requested(job_id).await {
+            return;
         }
     }
 }
@@ -1,6 +1,8 @@
 //! Error types for the encoding pipeline.
 
 use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Top-level application errors.
@@ -24,6 +26,9 @@ pub enum AppError {
     #[error("Notification error: {0}")]
     Notification(#[from] NotificationError),
 
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -83,6 +88,51 @@ pub enum QueueError {
 
     #[error("Failed to serialize job: {0}")]
     SerializationFailed(String),
+
+    #[error("Job {job_id} cannot be {action} while {status}")]
+    InvalidState {
+        job_id: String,
+        action: String,
+        status: String,
+    },
+
+    #[error("Profile not found: {name}")]
+    ProfileNotFound { name: String },
+
+    #[error("No profile matches path '{path}'")]
+    NoMatchingProfile { path: PathBuf },
+
+    #[error("'{path}' is already queued as job {job_id}")]
+    DuplicateJob { path: PathBuf, job_id: String },
+
+    #[error("Key migration failed: {0}")]
+    MigrationFailed(String),
+
+    #[error("Spool failed: {0}")]
+    SpoolFailed(String),
+
+    #[error("Invalid job overrides: {0}")]
+    InvalidOverrides(String),
+}
+
+impl QueueError {
+    /// Classifies the error for retrying.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::ConnectionFailed { .. }
+            | Self::EnqueueFailed(_)
+            | Self::DequeueFailed(_)
+            | Self::SpoolFailed(_) => ErrorCategory::Transient,
+            Self::JobNotFound { .. }
+            | Self::SerializationFailed(_)
+            | Self::InvalidState { .. }
+            | Self::ProfileNotFound { .. }
+            | Self::NoMatchingProfile { .. }
+            | Self::DuplicateJob { .. }
+            | Self::MigrationFailed(_)
+            | Self::InvalidOverrides(_) => ErrorCategory::Permanent,
+        }
+    }
 }
 
 /// Encoding operation errors.
@@ -100,11 +150,110 @@ pub enum EncoderError {
     #[error("Process spawn failed: {0}")]
     SpawnFailed(String),
 
+    #[error("Input file not found: {path}")]
+    InputNotFound { path: PathBuf },
+
+    #[error("Failed to probe input: {0}")]
+    ProbeFailed(String),
+
+    #[error("Profile '{name}' not found")]
+    ProfileNotFound { name: String },
+
     #[error("Encoding timed out after {seconds} seconds")]
     Timeout { seconds: u64 },
 
     #[error("Output verification failed: {0}")]
     VerificationFailed(String),
+
+    #[error(
+        "Output is {output_size} bytes for a {input_size} byte source, breaking the size rules"
+    )]
+    OutputTooLarge { input_size: u64, output_size: u64 },
+
+    #[error("Job exceeded its memory limit of {limit_mb} MiB")]
+    MemoryLimitExceeded { limit_mb: u64 },
+
+    #[error("{phase} stalled: {reason}")]
+    Stalled { phase: String, reason: String },
+
+    #[error("Hook failed: {0}")]
+    HookFailed(String),
+
+    #[error("Job was cancelled")]
+    Cancelled,
+}
+
+impl EncoderError {
+    /// Classifies the error for retrying.
+    ///
+    /// Tool failures are permanent when their output shows the input itself
+    /// cannot be read or decoded.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Av1anFailed { stderr, .. }
+            | Self::FfmpegFailed { stderr, .. }
+            | Self::MkvmergeFailed { stderr, .. } => {
+                if is_unusable_input(stderr) {
+                    ErrorCategory::Permanent
+                } else {
+                    ErrorCategory::Transient
+                }
+            }
+            Self::InputNotFound { .. }
+            | Self::ProbeFailed(_)
+            | Self::ProfileNotFound { .. }
+            | Self::OutputTooLarge { .. }
+            | Self::Cancelled => ErrorCategory::Permanent,
+            Self::SpawnFailed(_) | Self::VerificationFailed(_) | Self::HookFailed(_) => {
+                ErrorCategory::Transient
+            }
+            Self::Timeout { .. } | Self::MemoryLimitExceeded { .. } | Self::Stalled { .. } => {
+                ErrorCategory::Resource
+            }
+        }
+    }
+}
+
+/// Messages encoder tools print when the input cannot be read or decoded.
+///
+/// Only messages about the input file or its streams are listed, so a
+/// missing tool, temporary directory or option stays a transient failure.
+const UNUSABLE_INPUT_MESSAGES: &[&str] = &[
+    // FFmpeg
+    "Invalid data found when processing input",
+    "moov atom not found",
+    "not found for input stream",
+    "Unsupported codec with id",
+    // mkvmerge
+    "could not be opened for reading",
+    "could not be recognized",
+];
+
+/// Returns whether tool output shows the input cannot be read or decoded.
+fn is_unusable_input(stderr: &str) -> bool {
+    UNUSABLE_INPUT_MESSAGES.iter().any(|m| stderr.contains(m))
+}
+
+/// Kind of failure, which decides how often a job is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// Retrying cannot help (missing input, unsupported codec, unknown profile).
+    Permanent,
+    /// Another attempt may succeed (I/O errors, crashed tools).
+    Transient,
+    /// A memory or time limit was hit, or a subprocess stalled.
+    Resource,
+}
+
+impl std::fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Permanent => write!(f, "permanent"),
+            Self::Transient => write!(f, "transient"),
+            Self::Resource => write!(f, "resource"),
+        }
+    }
 }
 
 /// File watcher errors.
@@ -131,6 +280,19 @@ pub enum NotificationError {
 
     #[error("HTTP request failed: {0}")]
     HttpFailed(#[from] reqwest::Error),
+
+    #[error("Hook '{name}' failed: {message}")]
+    HookFailed { name: String, message: String },
+}
+
+/// HTTP control API errors.
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("Failed to bind API server on port {port}: {message}")]
+    BindFailed { port: u16, message: String },
+
+    #[error("Failed to accept connection: {0}")]
+    AcceptFailed(String),
 }
 
 /// Capability detection errors.
@@ -3,53 +3,148 @@
 //! This library provides a complete video encoding solution with folder watching,
 //! queue management, and configurable encoding profiles.
 
+pub mod api;
 pub mod cli;
 pub mod config;
+pub mod dashboard;
 pub mod encoder;
 pub mod error;
 pub mod media;
 pub mod notify;
+pub mod output;
 pub mod queue;
 pub mod validation;
 pub mod watcher;
 
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::ExitCode;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::Result;
-use tokio::sync::mpsc;
+use anyhow::{Context, Result};
+use tokio::sync::{mpsc, watch, RwLock};
 use tracing::{error, info, warn};
 
-use crate::cli::{Cli, Commands, RunArgs};
+use crate::api::{ApiServer, ApiState};
+use crate::cli::{Cli, Commands, EnqueueArgs, HistoryArgs, OutputFormat, RunArgs};
 use crate::config::ConfigManager;
-use crate::encoder::EncodeWorker;
-use crate::notify::{DiscordNotifier, MetricsServer};
-use crate::queue::QueueManager;
+use crate::encoder::throttle::ThrottleStatus;
+use crate::encoder::{EncodePlan, EncodeWorker, Scheduler};
+use crate::error::QueueError;
+use crate::notify::{DiscordNotifier, EventBus, HookRunner, MetricsServer, PipelineEvent};
+use crate::output::{
+    emit, ClearOutput, EnqueueOutput, EnqueueResult, ErrorOutput, ExitStatus, HistoryOutput,
+    JobOutput, MigrateOutput, NodeOutput, NodesOutput, QueueListOutput, RetryOutput,
+    ValidationOutput,
+};
+use crate::queue::history::{profile_stats, HistoryFilter};
+use crate::queue::job::JobOverrides;
+use crate::queue::leader::LeaderElection;
+use crate::queue::nodes::{default_node_id, NodeInfo, NodeMode};
+use crate::queue::submit::{calculate_output_path, resolve_profile, submit, SubmitRequest};
+use crate::queue::{KeySpace, QueueManager};
 use crate::validation::SystemCapabilities;
 use crate::watcher::WatcherManager;
 
+/// First delay between Redis connection attempts at startup.
+const REDIS_BACKOFF_INITIAL: Duration = Duration::from_secs(1);
+
+/// Longest delay between Redis connection attempts at startup.
+const REDIS_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// How often spooled queue writes are retried and Redis health is reported.
+const SPOOL_REPLAY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often a node publishes its heartbeat.
+const NODE_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a node stays listed without a heartbeat.
+const NODE_TTL: Duration = Duration::from_secs(30);
+
+/// How often the leader looks for jobs left in progress by dead nodes.
+const REAPER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long an orphaned job must go without updates before it is requeued.
+const ORPHAN_GRACE: chrono::Duration = chrono::Duration::minutes(5);
+
+/// How often a worker-only node checks for configuration changes.
+const CONFIG_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Runs the encoding pipeline with the provided CLI arguments.
-pub async fn run(cli: Cli) -> Result<()> {
-    setup_logging(&cli.log_level())?;
-
-    match cli.command {
-        Commands::Run(args) => run_pipeline(args, &cli.config).await,
-        Commands::ConfigValidate => validate_config(&cli.config).await,
-        Commands::ConfigShow => show_config(&cli.config).await,
-        Commands::QueueList => list_queue(&cli.config).await,
-        Commands::QueueClear => clear_queue(&cli.config).await,
-        Commands::RetryDeadLetter { job_id } => retry_dead_letter(&cli.config, &job_id).await,
+pub async fn run(cli: Cli) -> Result<ExitCode> {
+    // Only the long-running pipeline logs to stdout; other commands keep it for results
+    // The dashboard owns the terminal, so it does not log at all
+    let log_to_stderr = !matches!(cli.command, Commands::Run(_));
+    if !matches!(cli.command, Commands::Dashboard) {
+        setup_logging(cli.log_level(), log_to_stderr)?;
+    }
+
+    let format = cli.output;
+
+    let result = match cli.command {
+        Commands::Run(args) if args.worker_only => run_worker(args, &cli.config)
+            .await
+            .map(|()| ExitStatus::Success),
+        Commands::Run(args) => run_pipeline(args, &cli.config)
+            .await
+            .map(|()| ExitStatus::Success),
+        Commands::ConfigValidate => validate_config(&cli.config, format).await,
+        Commands::ConfigShow => show_config(&cli.config, format).await,
+        Commands::QueueList => list_queue(&cli.config, format).await,
+        Commands::QueueClear => clear_queue(&cli.config, format).await,
+        Commands::Enqueue(args) => enqueue(args, &cli.config, format).await,
+        Commands::Plan {
+            file,
+            profile,
+            json,
+        } => {
+            let format = if json { OutputFormat::Json } else { format };
+            plan(&cli.config, &file, profile.as_deref(), format).await
+        }
+        Commands::RedisMigrate { from, dry_run } => {
+            redis_migrate(&cli.config, from.as_deref(), dry_run, format).await
+        }
+        Commands::History(args) => history(args, &cli.config, format).await,
+        Commands::Dashboard => dashboard(&cli.config).await,
+        Commands::Nodes => list_nodes(&cli.config, format).await,
+        Commands::RetryDeadLetter { job_id } => {
+            retry_dead_letter(&cli.config, &job_id, format).await
+        }
+    };
+
+    match result {
+        Ok(status) => Ok(status.into()),
+        Err(e) if format != OutputFormat::Text => {
+            emit(
+                format,
+                &ErrorOutput {
+                    error: format!("{:#}", e),
+                },
+                |_| {},
+            )?;
+            Ok(ExitStatus::Failure.into())
+        }
+        Err(e) => Err(e),
     }
 }
 
 /// Initializes the tracing subscriber for structured logging.
-fn setup_logging(level: &str) -> Result<()> {
+fn setup_logging(level: &str, to_stderr: bool) -> Result<()> {
+    use tracing_subscriber::fmt::writer::BoxMakeWriter;
     use tracing_subscriber::{fmt, EnvFilter};
 
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
 
+    let writer = if to_stderr {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+
     fmt()
         .with_env_filter(filter)
+        .with_writer(writer)
         .json()
         .with_target(true)
         .with_file(true)
@@ -59,9 +154,37 @@ fn setup_logging(level: &str) -> Result<()> {
     Ok(())
 }
 
+/// Connects to Redis, retrying with exponential backoff until it succeeds.
+///
+/// Returns `None` if a shutdown signal arrives first.
+async fn connect_with_backoff(config: &config::model::RedisConfig) -> Option<QueueManager> {
+    let mut delay = REDIS_BACKOFF_INITIAL;
+    let mut attempt = 1u32;
+
+    loop {
+        match QueueManager::new(config).await {
+            Ok(queue) => return Some(queue),
+            Err(e) => {
+                warn!(attempt, retry_in = ?delay, error = %e, "Redis unavailable, retrying");
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return None,
+            _ = tokio::time::sleep(delay) => {}
+        }
+
+        delay = (delay * 2).min(REDIS_BACKOFF_MAX);
+        attempt += 1;
+    }
+}
+
 /// Runs the main encoding pipeline loop.
 async fn run_pipeline(args: RunArgs, config_path: &std::path::Path) -> Result<()> {
     info!("Starting encoding pipeline");
+    if args.dry_run {
+        warn!("Dry-run mode: queued jobs will be planned and reported but left in the queue, nothing will be encoded");
+    }
 
     // Detect system capabilities
     let capabilities = SystemCapabilities::detect()?;
@@ -75,25 +198,27 @@ async fn run_pipeline(args: RunArgs, config_path: &std::path::Path) -> Result<()
 
     let config_read = config.read().await;
 
-    // Build Redis URL
-    let redis_url = build_redis_url(&config_read.global.redis);
+    // Initialize Redis connection, waiting for it to come up
+    let Some(mut queue) = connect_with_backoff(&config_read.global.redis).await else {
+        info!("Shutdown signal received while waiting for Redis");
+        return Ok(());
+    };
+    queue = queue.with_history(config_read.global.history.clone());
+    info!(redis = %queue::connection::describe(&config_read.global.redis), "Connected to Redis");
 
-    // Initialize Redis connection
-    let queue = QueueManager::new(&redis_url).await?;
-    info!("Connected to Redis");
+    // Attach the spool and replay writes left over from a previous run
+    queue = attach_spool(queue, &config_read.global.spool).await?;
 
-    // Store config in Redis cache
-    {
-        let mut redis_conn = redis::Client::open(redis_url.as_str())?
-            .get_connection_manager()
-            .await?;
-        config::cache::store_config(&mut redis_conn, &config_read).await?;
-        info!("Configuration cached in Redis");
-    }
+    // Store config in Redis cache, without secrets
+    cache_config(&config_read).await?;
+    info!("Configuration cached in Redis");
 
     // Initialize metrics
     let metrics = Arc::new(notify::prometheus::Metrics::new()?);
 
+    // Replay spooled writes once Redis is back, and report its health
+    spawn_spool_replayer(queue.clone(), metrics.clone());
+
     // Initialize Discord notifier if configured
     let discord = config_read
         .global
@@ -104,22 +229,46 @@ async fn run_pipeline(args: RunArgs, config_path: &std::path::Path) -> Result<()
 
     let prometheus_port = config_read.global.prometheus.port;
     let prometheus_enabled = config_read.global.prometheus.enabled;
-    let stability_duration = Duration::from_secs(config_read.global.stability_check.duration_seconds);
-    let poll_interval = Duration::from_secs(config_read.global.stability_check.poll_interval_seconds);
-    let max_attempts = config_read.global.retry.max_attempts;
+    let stability_duration =
+        Duration::from_secs(config_read.global.stability_check.duration_seconds);
+    let poll_interval =
+        Duration::from_secs(config_read.global.stability_check.poll_interval_seconds);
+    let retry = config_read.global.retry.clone();
     let process_existing = args.process_existing;
+    let api_config = config_read.global.api.clone();
 
     drop(config_read);
 
     // Start Prometheus metrics server
     if prometheus_enabled {
-        let metrics_server = MetricsServer::new(metrics.clone(), prometheus_port);
+        start_metrics_server(metrics.clone(), prometheus_port);
+    }
+
+    // Progress and lifecycle events for streaming clients
+    let events = EventBus::new();
+
+    // Lifecycle hooks are shared by the watchers, the API and the worker
+    let hooks = Arc::new(HookRunner::new(config.clone(), events.clone()));
+
+    // Start control API server
+    if api_config.enabled {
+        let api_server = ApiServer::new(
+            ApiState {
+                queue: queue.clone(),
+                config: config.clone(),
+                capabilities: capabilities.clone(),
+                hooks: hooks.clone(),
+                events: events.clone(),
+                token: api_config.token.clone(),
+            },
+            api_config.port,
+        );
         tokio::spawn(async move {
-            if let Err(e) = metrics_server.start().await {
-                error!(error = %e, "Prometheus server failed");
+            if let Err(e) = api_server.start().await {
+                error!(error = %e, "Control API server failed");
             }
         });
-        info!(port = prometheus_port, "Prometheus metrics server started");
+        info!(port = api_config.port, "Control API server started");
     }
 
     // Start config hot-reload watcher
@@ -137,42 +286,61 @@ async fn run_pipeline(args: RunArgs, config_path: &std::path::Path) -> Result<()
     });
     info!("Config hot-reload enabled");
 
-    // Start file watchers
-    let mut watcher_manager = WatcherManager::new(
+    // This node's identity, for the leader lease and the node registry
+    let node = NodeInfo::new(
+        args.node_id.clone().unwrap_or_else(default_node_id),
+        NodeMode::Pipeline,
+        capabilities.clone(),
+    );
+    ensure_unique_node(&mut queue, &node.id).await?;
+
+    // Only the leader among pipelines sharing the queue watches folders and reaps jobs
+    let election = LeaderElection::new(queue.clone(), node.id.clone());
+    let lease_token = election.token().to_string();
+    let leadership = election.spawn();
+    spawn_leader_tasks(
+        leadership,
         config.clone(),
         queue.clone(),
+        hooks.clone(),
         stability_duration,
         poll_interval,
-    )
-    .await;
-
-    tokio::spawn(async move {
-        if let Err(e) = watcher_manager.start(process_existing).await {
-            error!(error = %e, "Watcher manager failed");
-        }
-    });
-    info!("File watchers started");
+        process_existing,
+    );
 
-    // Start encoder worker
+    // Start encoder workers, as many as the concurrency limits allow
     let (progress_tx, mut progress_rx) = mpsc::channel(100);
-    let mut worker = EncodeWorker::new(
+    let worker = EncodeWorker::new(
         queue.clone(),
         config.clone(),
-        max_attempts,
+        retry,
         Some(progress_tx),
+        hooks,
+        discord,
+        args.dry_run,
     );
+    let mut scheduler = Scheduler::new(worker, queue.clone(), config.clone())
+        .with_metrics(metrics.clone())
+        .with_node(node.id.clone(), capabilities.clone());
+    let throttle = scheduler.throttle_status();
 
-    let metrics_clone = metrics.clone();
     tokio::spawn(async move {
-        if let Err(e) = worker.run().await {
-            error!(error = %e, "Encoder worker failed");
+        if let Err(e) = scheduler.run().await {
+            error!(error = %e, "Job scheduler failed");
         }
     });
-    info!("Encoder worker started");
+    info!(node_id = %node.id, "Job scheduler started");
+
+    // Let other nodes and the `nodes` command see this one
+    spawn_heartbeat(queue.clone(), config.clone(), node.clone(), throttle);
 
     // Main loop: handle signals and events
     info!("Encoding pipeline is running. Press Ctrl+C to stop.");
 
+    // Last known phase per job, for phase transition events
+    let mut job_phases: HashMap<String, encoder::worker::EncodePhase> = HashMap::new();
+    let mut lifecycle = events.subscribe();
+
     loop {
         tokio::select! {
             // Handle graceful shutdown
@@ -186,7 +354,10 @@ async fn run_pipeline(args: RunArgs, config_path: &std::path::Path) -> Result<()
                 match event {
                     config::hot_reload::ConfigReloadEvent::Reloaded => {
                         info!("Configuration reloaded");
-                        // TODO: Signal watchers to update
+                        // Worker nodes pick up the new config through the cache hash
+                        if let Err(e) = cache_config(&*config.read().await).await {
+                            warn!(error = %e, "Failed to cache reloaded configuration");
+                        }
                     }
                     config::hot_reload::ConfigReloadEvent::ValidationFailed { error_count } => {
                         warn!(error_count, "Configuration reload failed validation");
@@ -194,117 +365,1158 @@ async fn run_pipeline(args: RunArgs, config_path: &std::path::Path) -> Result<()
                 }
             }
 
-            // Handle progress updates (for metrics)
+            // Handle progress updates (for streaming clients)
             Some(progress) = progress_rx.recv() => {
-                // Update metrics
-                metrics_clone.set_jobs_in_progress(1);
+                if job_phases.get(&progress.job_id) != Some(&progress.phase) {
+                    events.publish(PipelineEvent::PhaseChanged {
+                        job_id: progress.job_id.clone(),
+                        phase: progress.phase.to_string(),
+                    });
+                }
+
+                events.publish(PipelineEvent::Progress {
+                    job_id: progress.job_id.clone(),
+                    percent: progress.percent,
+                    phase: progress.phase.to_string(),
+                    speed: progress.speed,
+                    eta: progress.eta,
+                });
+
+                if progress.percent >= 100.0 {
+                    job_phases.remove(&progress.job_id);
+                } else {
+                    job_phases.insert(progress.job_id, progress.phase);
+                }
+            }
+
+            // Forget the phase of jobs whose attempt ended before reaching 100%
+            Ok(PipelineEvent::Lifecycle { job_id, event, .. }) = lifecycle.recv() => {
+                if matches!(
+                    event,
+                    config::model::HookEvent::Succeeded
+                        | config::model::HookEvent::Failed
+                        | config::model::HookEvent::DeadLettered
+                ) {
+                    job_phases.remove(&job_id);
+                }
             }
         }
     }
 
     info!("Shutting down encoding pipeline");
     // TODO: Graceful shutdown - wait for current encode to complete
+    if let Err(e) = queue.release_leadership(&lease_token).await {
+        warn!(error = %e, "Failed to release leader lease");
+    }
+    unregister_node(queue, &node.id).await;
     Ok(())
 }
 
-/// Builds the Redis URL from configuration.
-fn build_redis_url(config: &config::model::RedisConfig) -> String {
-    match &config.password {
-        Some(pass) => format!("redis://:{}@{}:{}/{}", pass, config.host, config.port, config.db),
-        None => format!("redis://{}:{}/{}", config.host, config.port, config.db),
-    }
+/// Runs the folder watchers and the orphaned-job reaper while this node is the leader.
+///
+/// The tasks are stopped when the lease is lost and started afresh when it is
+/// regained.
+fn spawn_leader_tasks(
+    mut leadership: watch::Receiver<bool>,
+    config: Arc<RwLock<config::AppConfig>>,
+    queue: QueueManager,
+    hooks: Arc<HookRunner>,
+    stability_duration: Duration,
+    poll_interval: Duration,
+    process_existing: bool,
+) {
+    tokio::spawn(async move {
+        let mut tasks: Vec<tokio::task::JoinHandle<()>> = Vec::new();
+
+        while leadership.changed().await.is_ok() {
+            let leader = *leadership.borrow_and_update();
+            for task in tasks.drain(..) {
+                task.abort();
+            }
+            if !leader {
+                continue;
+            }
+
+            let mut watcher_manager = WatcherManager::new(
+                config.clone(),
+                queue.clone(),
+                stability_duration,
+                poll_interval,
+                hooks.clone(),
+            )
+            .await;
+            tasks.push(tokio::spawn(async move {
+                if let Err(e) = watcher_manager.start(process_existing).await {
+                    error!(error = %e, "Watcher manager failed");
+                }
+            }));
+            info!("File watchers started");
+
+            let mut reaper_queue = queue.clone();
+            tasks.push(tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(REAPER_INTERVAL).await;
+                    match reaper_queue.reap_orphaned_jobs(ORPHAN_GRACE).await {
+                        Ok(jobs) => {
+                            for job in jobs {
+                                warn!(
+                                    job_id = %job.id,
+                                    node = job.node.as_deref().unwrap_or("unknown"),
+                                    "Requeued job left in progress by a node that stopped"
+                                );
+                            }
+                        }
+                        Err(e) => warn!(error = %e, "Failed to reap orphaned jobs"),
+                    }
+                }
+            }));
+        }
+    });
 }
 
-/// Validates the configuration file and reports any issues.
-async fn validate_config(config_path: &std::path::Path) -> Result<()> {
+/// Runs a worker-only node that encodes jobs from a shared queue.
+///
+/// Profiles and settings come from the configuration cached in Redis by a
+/// full pipeline, and are reloaded when it changes. Only jobs whose encoder
+/// and audio codecs are available on this machine are taken.
+async fn run_worker(args: RunArgs, config_path: &std::path::Path) -> Result<()> {
+    info!("Starting worker node");
+    if args.dry_run {
+        warn!("Dry-run mode: queued jobs will be planned and reported but left in the queue, nothing will be encoded");
+    }
+
     let capabilities = SystemCapabilities::detect()?;
-    let result = config::loader::load_and_validate(config_path, &capabilities)?;
+    info!(?capabilities, "Detected system capabilities");
 
-    println!("Configuration is valid.");
-    println!("Found {} profile(s):", result.profiles.len());
-    for profile in &result.profiles {
-        println!(
-            "  - {} (encoder: {:?}, vmaf: {})",
-            profile.name, profile.encoder, profile.vmaf_target
+    // The config file is optional with --redis-url
+    let (redis_config, local_config) = match &args.redis_url {
+        Some(url) => (
+            config::model::RedisConfig {
+                url: Some(url.clone()),
+                namespace: args.redis_namespace.clone(),
+                ..Default::default()
+            },
+            config::loader::load_from_path(config_path).ok(),
+        ),
+        None => {
+            let local = config::loader::load_from_path(config_path).context(
+                "Worker-only mode needs --redis-url or a config file with Redis settings",
+            )?;
+            (local.global.redis.clone(), Some(local))
+        }
+    };
+
+    let Some(mut queue) = connect_with_backoff(&redis_config).await else {
+        info!("Shutdown signal received while waiting for Redis");
+        return Ok(());
+    };
+    info!(redis = %queue::connection::describe(&redis_config), "Connected to Redis");
+
+    // Wait for a pipeline to publish its configuration
+    let keys = KeySpace::from_config(&redis_config);
+    let mut redis_conn = queue::connection::connect(&redis_config).await?;
+    let cached = loop {
+        if let Some(hash) = config::cache::cached_hash(&mut redis_conn, &keys).await? {
+            if let Some(cached) = config::cache::load_config(&mut redis_conn, &keys).await? {
+                break (cached, hash);
+            }
+        }
+        warn!("No configuration cached in Redis yet; start a pipeline with a config file first");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tokio::time::sleep(CONFIG_REFRESH_INTERVAL) => {}
+        }
+    };
+    let (mut cached, mut cached_hash) = cached;
+    log_unsupported_profiles(&cached, &capabilities);
+    restore_local_secrets(&mut cached, local_config.as_ref());
+
+    queue = queue.with_history(cached.global.history.clone());
+    queue = attach_spool(queue, &cached.global.spool).await?;
+
+    let metrics = Arc::new(notify::prometheus::Metrics::new()?);
+    spawn_spool_replayer(queue.clone(), metrics.clone());
+    if cached.global.prometheus.enabled {
+        start_metrics_server(metrics.clone(), cached.global.prometheus.port);
+    }
+
+    let retry = cached.global.retry.clone();
+    let discord = worker_discord(
+        &cached,
+        local_config
+            .as_ref()
+            .and_then(|c| c.global.notifications.discord.clone()),
+        args.discord_webhook_url.clone(),
+    )
+    .map(|dc| Arc::new(DiscordNotifier::new(&dc)));
+    let config = Arc::new(RwLock::new(cached));
+
+    // Follow configuration changes made on the pipeline
+    {
+        let config = config.clone();
+        let capabilities = capabilities.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(CONFIG_REFRESH_INTERVAL).await;
+                match config::cache::cached_hash(&mut redis_conn, &keys).await {
+                    Ok(Some(hash)) if hash != cached_hash => {
+                        match config::cache::load_config(&mut redis_conn, &keys).await {
+                            Ok(Some(mut cached)) => {
+                                log_unsupported_profiles(&cached, &capabilities);
+                                restore_local_secrets(&mut cached, local_config.as_ref());
+                                *config.write().await = cached;
+                                cached_hash = hash;
+                                info!("Cached configuration changed, reloaded");
+                            }
+                            Ok(None) => {}
+                            Err(e) => warn!(error = %e, "Failed to reload cached configuration"),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!(error = %e, "Failed to check cached configuration"),
+                }
+            }
+        });
+    }
+
+    let hooks = Arc::new(HookRunner::new(config.clone(), EventBus::new()));
+    let worker = EncodeWorker::new(
+        queue.clone(),
+        config.clone(),
+        retry,
+        None,
+        hooks,
+        discord,
+        args.dry_run,
+    );
+
+    let node = NodeInfo::new(
+        args.node_id.unwrap_or_else(default_node_id),
+        NodeMode::Worker,
+        capabilities.clone(),
+    );
+    ensure_unique_node(&mut queue, &node.id).await?;
+    let mut scheduler = Scheduler::new(worker, queue.clone(), config.clone())
+        .with_metrics(metrics)
+        .with_node(node.id.clone(), capabilities);
+    let throttle = scheduler.throttle_status();
+
+    tokio::spawn(async move {
+        if let Err(e) = scheduler.run().await {
+            error!(error = %e, "Job scheduler failed");
+        }
+    });
+    spawn_heartbeat(queue.clone(), config, node.clone(), throttle);
+    info!(node_id = %node.id, "Worker node is running. Press Ctrl+C to stop.");
+
+    tokio::signal::ctrl_c().await?;
+    info!("Shutting down worker node");
+    unregister_node(queue, &node.id).await;
+    Ok(())
+}
+
+/// Restores the HTTP hook secrets redacted in the cached configuration from
+/// the local config file, warning about hooks that will not be able to run.
+fn restore_local_secrets(cached: &mut config::AppConfig, local: Option<&config::AppConfig>) {
+    let missing = config::redact::restore_hook_secrets(cached, local);
+    if !missing.is_empty() {
+        warn!(
+            hooks = ?missing,
+            "HTTP hooks have redacted credentials in the cached configuration and will fail on this worker; \
+             define them in this node's config file"
         );
     }
+}
+
+/// Returns the Discord settings of a worker node.
+///
+/// The webhook in the cached configuration is redacted, so it comes from
+/// `--discord-webhook-url` or the local config file instead. A worker without
+/// one warns that its jobs will not be reported, if the pipeline reports them.
+fn worker_discord(
+    cached: &config::AppConfig,
+    local: Option<config::model::DiscordConfig>,
+    webhook_url: Option<String>,
+) -> Option<config::model::DiscordConfig> {
+    let pipeline = cached.global.notifications.discord.clone();
+    let pipeline_notifies = pipeline.is_some();
+
+    let discord = match (webhook_url, local) {
+        (Some(webhook_url), local) => Some(config::model::DiscordConfig {
+            webhook_url,
+            ..local
+                .or(pipeline)
+                .unwrap_or_else(|| config::model::DiscordConfig {
+                    webhook_url: String::new(),
+                    events: Default::default(),
+                    mention_on_failure: None,
+                })
+        }),
+        (None, Some(local)) if local.webhook_url != config::redact::REDACTED => Some(local),
+        (None, _) => None,
+    };
 
+    if discord.is_none() && pipeline_notifies {
+        warn!(
+            "Discord notifications are configured on the pipeline, but this worker has no webhook; \
+             set --discord-webhook-url or DISCORD_WEBHOOK_URL to report its jobs"
+        );
+    }
+
+    discord
+}
+
+/// Warns about profiles whose jobs this node cannot encode.
+fn log_unsupported_profiles(config: &config::AppConfig, capabilities: &SystemCapabilities) {
+    for profile in &config.profiles {
+        let missing = validation::codec::missing_codecs(profile, capabilities);
+        if !missing.is_empty() {
+            warn!(
+                profile = %profile.name,
+                missing = %missing.join(", "),
+                "Profile cannot be encoded on this node, its jobs are left to other nodes"
+            );
+        }
+    }
+}
+
+/// Stores the configuration in Redis, without secrets, for worker nodes to load.
+async fn cache_config(config: &config::AppConfig) -> Result<()> {
+    let mut redis_conn = queue::connection::connect(&config.global.redis).await?;
+    let keys = KeySpace::from_config(&config.global.redis);
+    let cached = config::redact::redact_config(config);
+    config::cache::store_config(&mut redis_conn, &keys, &cached).await?;
     Ok(())
 }
 
+/// Attaches the on-disk spool if enabled and replays writes left by a previous run.
+async fn attach_spool(
+    queue: QueueManager,
+    config: &config::model::SpoolConfig,
+) -> Result<QueueManager> {
+    if !config.enabled {
+        return Ok(queue);
+    }
+
+    let spool = queue::Spool::open(&config.dir)?;
+    if spool.pending() > 0 {
+        warn!(pending = spool.pending(), dir = ?spool.dir(), "Replaying queue writes spooled by a previous run");
+    }
+
+    let mut queue = queue.with_spool(spool);
+    if let Err(e) = queue.replay_spool().await {
+        warn!(error = %e, "Failed to replay spooled queue writes, will retry");
+    }
+    Ok(queue)
+}
+
+/// Replays spooled writes once Redis is back, and reports its health.
+fn spawn_spool_replayer(mut queue: QueueManager, metrics: Arc<notify::prometheus::Metrics>) {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = queue.replay_spool().await {
+                warn!(error = %e, "Failed to replay spooled queue writes, will retry");
+            }
+            let spooled = queue.spool().map_or(0, |spool| spool.pending());
+            let up = spooled == 0 && queue.is_reachable().await;
+            metrics.set_redis_health(up, spooled);
+            tokio::time::sleep(SPOOL_REPLAY_INTERVAL).await;
+        }
+    });
+}
+
+/// Serves Prometheus metrics in the background.
+fn start_metrics_server(metrics: Arc<notify::prometheus::Metrics>, port: u16) {
+    let metrics_server = MetricsServer::new(metrics, port);
+    tokio::spawn(async move {
+        if let Err(e) = metrics_server.start().await {
+            error!(error = %e, "Prometheus server failed");
+        }
+    });
+    info!(port, "Prometheus metrics server started");
+}
+
+/// Publishes the node's heartbeat until the process exits.
+fn spawn_heartbeat(
+    mut queue: QueueManager,
+    config: Arc<RwLock<config::AppConfig>>,
+    mut node: NodeInfo,
+    throttle: watch::Receiver<ThrottleStatus>,
+) {
+    tokio::spawn(async move {
+        loop {
+            node.max_jobs = config.read().await.global.concurrency.max_jobs;
+            node.throttle = throttle.borrow().clone();
+            node.heartbeat_at = chrono::Utc::now();
+            if let Err(e) = queue.register_node(&node, NODE_TTL.as_secs()).await {
+                warn!(error = %e, "Failed to publish node heartbeat");
+            }
+            tokio::time::sleep(NODE_HEARTBEAT_INTERVAL).await;
+        }
+    });
+}
+
+/// Refuses to start a node whose ID a running node already uses.
+///
+/// A registration left by an earlier run of this node stays until it expires,
+/// so a node only counts as running if its heartbeat moves on within one
+/// heartbeat interval.
+async fn ensure_unique_node(queue: &mut QueueManager, node_id: &str) -> Result<()> {
+    let Some(existing) = queue.node(node_id).await? else {
+        return Ok(());
+    };
+
+    info!(
+        node_id,
+        "A node with this ID is registered, checking whether it is still running"
+    );
+    tokio::time::sleep(NODE_HEARTBEAT_INTERVAL + Duration::from_secs(2)).await;
+
+    match queue.node(node_id).await? {
+        Some(current) if current.heartbeat_at != existing.heartbeat_at => anyhow::bail!(
+            "Node ID '{}' is already used by a running node; set a unique --node-id or NODE_ID",
+            node_id
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Removes the node from the registry on shutdown.
+async fn unregister_node(mut queue: QueueManager, node_id: &str) {
+    if let Err(e) = queue.unregister_node(node_id).await {
+        warn!(error = %e, "Failed to unregister node");
+    }
+}
+
+/// Validates the configuration file and reports any issues.
+async fn validate_config(
+    config_path: &std::path::Path,
+    format: OutputFormat,
+) -> Result<ExitStatus> {
+    let capabilities = SystemCapabilities::detect()?;
+    let config = config::loader::load_from_path(config_path)?;
+    let result = validation::validate_config(&config, &capabilities);
+
+    let output = ValidationOutput {
+        valid: result.is_valid(),
+        error_count: result.error_count(),
+        warning_count: result.warnings().count(),
+        issues: result.errors().chain(result.warnings()).cloned().collect(),
+        profiles: config.profiles.iter().map(|p| p.name.clone()).collect(),
+    };
+
+    emit(format, &output, |output| {
+        println!("{}", validation::report::format_report(&result));
+        if output.valid {
+            println!("Found {} profile(s):", config.profiles.len());
+            for profile in &config.profiles {
+                println!(
+                    "  - {} (encoder: {:?}, vmaf: {})",
+                    profile.name, profile.encoder, profile.vmaf_target
+                );
+            }
+        }
+    })?;
+
+    Ok(if !output.valid {
+        ExitStatus::Failure
+    } else if output.warning_count > 0 {
+        ExitStatus::Warnings
+    } else {
+        ExitStatus::Success
+    })
+}
+
+/// Lists live nodes and the jobs each one is running.
+async fn list_nodes(config_path: &std::path::Path, format: OutputFormat) -> Result<ExitStatus> {
+    // Only the Redis settings are needed, so the local tools are not checked
+    let config = config::loader::load_from_path(config_path)?;
+    let mut queue = QueueManager::connect(&config).await?;
+
+    let leader = queue.leader().await?;
+    let mut nodes = queue.list_nodes().await?;
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+    let mut processing = queue.list_processing().await?;
+
+    let nodes: Vec<NodeOutput> = nodes
+        .into_iter()
+        .map(|node| {
+            let (running, rest) = processing
+                .drain(..)
+                .partition(|job| job.node.as_deref() == Some(node.id.as_str()));
+            processing = rest;
+
+            let mut av1an_encoders: Vec<String> =
+                node.capabilities.av1an_encoders.into_iter().collect();
+            av1an_encoders.sort();
+
+            NodeOutput {
+                leader: leader.as_deref() == Some(node.id.as_str()),
+                id: node.id,
+                mode: node.mode,
+                cores: node.cores,
+                max_jobs: node.max_jobs,
+                av1an_encoders,
+                ffmpeg_encoders: node.capabilities.available_encoders.len(),
+                version: node.version,
+                started_at: node.started_at,
+                heartbeat_at: node.heartbeat_at,
+                throttle: node.throttle,
+                running: running.iter().map(JobOutput::from).collect(),
+            }
+        })
+        .collect();
+
+    let output = NodesOutput {
+        nodes,
+        orphaned: processing.iter().map(JobOutput::from).collect(),
+    };
+
+    emit(format, &output, |output| {
+        if output.nodes.is_empty() {
+            println!("No live nodes.");
+        }
+        for node in &output.nodes {
+            let seen = (chrono::Utc::now() - node.heartbeat_at).num_seconds();
+            println!(
+                "{}{} ({}, v{}): {} cores, {}/{} jobs, encoders: {}, seen {}s ago",
+                node.id,
+                if node.leader { " [leader]" } else { "" },
+                node.mode,
+                node.version,
+                node.cores,
+                node.running.len(),
+                node.max_jobs,
+                node.av1an_encoders.join(", "),
+                seen
+            );
+            let throttle = &node.throttle;
+            if throttle.paused {
+                println!("  paused: {}", throttle.reasons.join(", "));
+            } else if let Some(workers) = throttle.reduced_workers {
+                println!(
+                    "  reduced to {} workers: {}",
+                    workers,
+                    throttle.reasons.join(", ")
+                );
+            }
+            if !throttle.paused_profiles.is_empty() {
+                println!("  paused profiles: {}", throttle.paused_profiles.join(", "));
+            }
+            for job in &node.running {
+                println!(
+                    "  {} {} [{}] {:.1}% {}",
+                    job.id,
+                    job.profile,
+                    job.phase.as_deref().unwrap_or("-"),
+                    job.progress.unwrap_or(0.0),
+                    job.input_path.display()
+                );
+            }
+        }
+        if !output.orphaned.is_empty() {
+            println!("\nIn progress on nodes that are no longer live:");
+            for job in &output.orphaned {
+                println!(
+                    "  {} {} (node: {}) {}",
+                    job.id,
+                    job.profile,
+                    job.node.as_deref().unwrap_or("unknown"),
+                    job.input_path.display()
+                );
+            }
+        }
+    })?;
+
+    Ok(ExitStatus::Success)
+}
+
 /// Displays the parsed configuration.
-async fn show_config(config_path: &std::path::Path) -> Result<()> {
+async fn show_config(config_path: &std::path::Path, format: OutputFormat) -> Result<ExitStatus> {
     let capabilities = SystemCapabilities::detect()?;
     let config = config::loader::load_and_validate(config_path, &capabilities)?;
-    let yaml = serde_yaml::to_string(&config)?;
-    println!("{}", yaml);
-    Ok(())
+
+    // The text form of the configuration is its YAML
+    let format = if format == OutputFormat::Text {
+        OutputFormat::Yaml
+    } else {
+        format
+    };
+    emit(format, &config::redact::redact_config(&config), |_| {})?;
+
+    Ok(ExitStatus::Success)
 }
 
 /// Lists all jobs in the queue.
-async fn list_queue(config_path: &std::path::Path) -> Result<()> {
+async fn list_queue(config_path: &std::path::Path, format: OutputFormat) -> Result<ExitStatus> {
     let capabilities = SystemCapabilities::detect()?;
     let config = config::loader::load_and_validate(config_path, &capabilities)?;
 
-    let redis_url = build_redis_url(&config.global.redis);
-    let mut queue = QueueManager::new(&redis_url).await?;
+    let mut queue = QueueManager::connect(&config).await?;
 
-    let jobs = queue.list_queue().await?;
+    let output = QueueListOutput {
+        processing: queue
+            .list_processing()
+            .await?
+            .iter()
+            .map(JobOutput::from)
+            .collect(),
+        pending: queue
+            .list_queue()
+            .await?
+            .iter()
+            .map(JobOutput::from)
+            .collect(),
+        dead_letter: queue
+            .list_dead_letter()
+            .await?
+            .iter()
+            .map(JobOutput::from)
+            .collect(),
+    };
 
-    if jobs.is_empty() {
-        println!("Queue is empty.");
-    } else {
-        println!("Queue ({} jobs):", jobs.len());
-        for job in jobs {
+    emit(format, &output, |output| {
+        if !output.processing.is_empty() {
+            println!("Processing ({} jobs):", output.processing.len());
+            for job in &output.processing {
+                println!(
+                    "  {} - {} ({:.0}%{})",
+                    job.id,
+                    job.input_path.display(),
+                    job.progress.unwrap_or(0.0),
+                    job.phase
+                        .as_deref()
+                        .map(|p| format!(", {}", p))
+                        .unwrap_or_default()
+                );
+            }
+            println!();
+        }
+
+        if output.pending.is_empty() {
+            println!("Queue is empty.");
+        } else {
+            println!("Queue ({} jobs):", output.pending.len());
+            for job in &output.pending {
+                println!(
+                    "  {} - {} ({:?})",
+                    job.id,
+                    job.input_path.display(),
+                    job.status
+                );
+            }
+        }
+
+        if !output.dead_letter.is_empty() {
+            println!("\nDead letter queue ({} jobs):", output.dead_letter.len());
+            for job in &output.dead_letter {
+                println!(
+                    "  {} - {} ({})",
+                    job.id,
+                    job.input_path.display(),
+                    job.error.as_deref().unwrap_or("Unknown error")
+                );
+            }
+        }
+    })?;
+
+    Ok(ExitStatus::Success)
+}
+
+/// Clears all jobs from the queue.
+async fn clear_queue(config_path: &std::path::Path, format: OutputFormat) -> Result<ExitStatus> {
+    let capabilities = SystemCapabilities::detect()?;
+    let config = config::loader::load_and_validate(config_path, &capabilities)?;
+
+    let mut queue = QueueManager::connect(&config).await?;
+
+    let cleared = queue.clear_queue().await?;
+    emit(format, &ClearOutput { cleared }, |output| {
+        println!("Cleared {} job(s) from queue.", output.cleared);
+    })?;
+
+    Ok(ExitStatus::Success)
+}
+
+/// Moves keys from another namespace into the configured one.
+async fn redis_migrate(
+    config_path: &std::path::Path,
+    from: Option<&str>,
+    dry_run: bool,
+    format: OutputFormat,
+) -> Result<ExitStatus> {
+    let config = config::loader::load_from_path(config_path)?;
+
+    // Namespaces end up in SCAN patterns, so they must not contain glob characters
+    for namespace in [from, config.global.redis.namespace.as_deref()]
+        .into_iter()
+        .flatten()
+    {
+        if !queue::keys::is_valid_namespace(namespace) {
+            anyhow::bail!("Invalid Redis namespace: '{}'", namespace);
+        }
+    }
+
+    let keys = KeySpace::from_config(&config.global.redis);
+    let source = KeySpace::new(from);
+    let mut queue = QueueManager::connect(&config).await?;
+
+    let migration = queue.migrate_keys(&source, dry_run).await?;
+    let output = MigrateOutput {
+        from: source.namespace().map(str::to_string),
+        to: keys.namespace().map(str::to_string),
+        dry_run,
+        moved: migration.moved,
+        conflicts: migration.conflicts,
+    };
+
+    emit(format, &output, |output| {
+        let verb = if output.dry_run {
+            "Would move"
+        } else {
+            "Moved"
+        };
+        println!(
+            "{} {} key(s) from {} to {}.",
+            verb,
+            output.moved.len(),
+            output.from.as_deref().unwrap_or("(no namespace)"),
+            output.to.as_deref().unwrap_or("(no namespace)")
+        );
+        for key in &output.moved {
+            println!("  {} -> {}", key.from, key.to);
+        }
+        if !output.conflicts.is_empty() {
             println!(
-                "  {} - {} ({:?})",
-                job.id,
-                job.input_path.display(),
-                job.status
+                "\n{} key(s) already exist in the destination and were left in place:",
+                output.conflicts.len()
             );
+            for key in &output.conflicts {
+                println!("  {} -> {}", key.from, key.to);
+            }
         }
+    })?;
+
+    if output.conflicts.is_empty() {
+        Ok(ExitStatus::Success)
+    } else {
+        Ok(ExitStatus::Failure)
     }
+}
+
+/// Searches finished jobs and summarizes their results.
+async fn history(
+    args: HistoryArgs,
+    config_path: &std::path::Path,
+    format: OutputFormat,
+) -> Result<ExitStatus> {
+    let capabilities = SystemCapabilities::detect()?;
+    let config = config::loader::load_and_validate(config_path, &capabilities)?;
+
+    let mut queue = QueueManager::connect(&config).await?;
+
+    let filter = HistoryFilter {
+        profile: args.profile,
+        status: args.status.map(Into::into),
+        since: args.since,
+        until: args.until,
+        path: args.path,
+    };
+
+    let jobs: Vec<_> = queue
+        .list_history(filter.since, filter.until)
+        .await?
+        .into_iter()
+        .filter(|job| filter.matches(job))
+        .collect();
+
+    let output = HistoryOutput {
+        matched: jobs.len(),
+        jobs: jobs.iter().take(args.limit).map(JobOutput::from).collect(),
+        stats: profile_stats(&jobs),
+    };
+
+    emit(format, &output, |output| {
+        if output.jobs.is_empty() {
+            println!("No finished jobs match.");
+            return;
+        }
 
-    let dead_letter = queue.list_dead_letter().await?;
-    if !dead_letter.is_empty() {
-        println!("\nDead letter queue ({} jobs):", dead_letter.len());
-        for job in dead_letter {
+        println!("Jobs ({} of {} shown):", output.jobs.len(), output.matched);
+        for job in &output.jobs {
+            let finished = job
+                .updated_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M");
+            let outcome = match (&job.result, job.status, &job.skip) {
+                (Some(_), _, Some(s)) => format!("{} by '{}': {}", s.action, s.rule, s.reason),
+                (Some(r), _, None) => format!(
+                    "saved {:.1}%, VMAF {}",
+                    r.size_reduction_percent(),
+                    r.vmaf_score
+                        .map_or("-".to_string(), |v| format!("{:.2}", v))
+                ),
+                (None, queue::JobStatus::Skipped, Some(s)) => {
+                    format!("skipped by '{}': {}", s.rule, s.reason)
+                }
+                (None, queue::JobStatus::Skipped, None) => "skipped".to_string(),
+                (None, _, _) => job
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| format!("{:?}", job.status)),
+            };
             println!(
-                "  {} - {} ({})",
-                job.id,
+                "  {}  {:<12} {:<10} {} ({})",
+                finished,
+                format!("{:?}", job.status),
+                job.profile,
                 job.input_path.display(),
-                job.error_message.as_deref().unwrap_or("Unknown error")
+                outcome
             );
         }
-    }
 
-    Ok(())
+        if !output.stats.is_empty() {
+            println!("\nStatistics by profile:");
+            for stats in &output.stats {
+                println!(
+                    "  {}: {} encodes, {:.2} GiB saved, {:.2}x compression, VMAF {}, {:.2}x speed",
+                    stats.profile,
+                    stats.encodes,
+                    stats.bytes_saved as f64 / (1024.0 * 1024.0 * 1024.0),
+                    stats.average_compression,
+                    stats
+                        .average_vmaf
+                        .map_or("-".to_string(), |v| format!("{:.2}", v)),
+                    stats.average_speed
+                );
+            }
+        }
+    })?;
+
+    Ok(ExitStatus::Success)
 }
 
-/// Clears all jobs from the queue.
-async fn clear_queue(config_path: &std::path::Path) -> Result<()> {
+/// Runs the interactive terminal dashboard.
+async fn dashboard(config_path: &std::path::Path) -> Result<ExitStatus> {
+    // Only Redis is needed, so the dashboard also works away from the encoding host
+    let config = config::loader::load_from_path(config_path)?;
+
+    let queue = QueueManager::connect(&config).await?;
+
+    dashboard::run(queue).await?;
+    Ok(ExitStatus::Success)
+}
+
+/// Retries a job from the dead letter queue.
+async fn retry_dead_letter(
+    config_path: &std::path::Path,
+    job_id: &str,
+    format: OutputFormat,
+) -> Result<ExitStatus> {
     let capabilities = SystemCapabilities::detect()?;
     let config = config::loader::load_and_validate(config_path, &capabilities)?;
 
-    let redis_url = build_redis_url(&config.global.redis);
-    let mut queue = QueueManager::new(&redis_url).await?;
+    let mut queue = QueueManager::connect(&config).await?;
 
-    let count = queue.clear_queue().await?;
-    println!("Cleared {} job(s) from queue.", count);
+    queue.retry_dead_letter(job_id).await?;
+    let output = RetryOutput {
+        job_id: job_id.to_string(),
+    };
+    emit(format, &output, |output| {
+        println!(
+            "Job {} moved from dead letter queue to main queue.",
+            output.job_id
+        );
+    })?;
 
-    Ok(())
+    Ok(ExitStatus::Success)
 }
 
-/// Retries a job from the dead letter queue.
-async fn retry_dead_letter(config_path: &std::path::Path, job_id: &str) -> Result<()> {
+/// Enqueues files, directories and glob patterns from the command line.
+async fn enqueue(
+    args: EnqueueArgs,
+    config_path: &std::path::Path,
+    format: OutputFormat,
+) -> Result<ExitStatus> {
     let capabilities = SystemCapabilities::detect()?;
     let config = config::loader::load_and_validate(config_path, &capabilities)?;
 
-    let redis_url = build_redis_url(&config.global.redis);
-    let mut queue = QueueManager::new(&redis_url).await?;
+    let mut queue = QueueManager::connect(&config).await?;
 
-    queue.retry_dead_letter(job_id).await?;
-    println!("Job {} moved from dead letter queue to main queue.", job_id);
+    // Directory arguments are filtered by the chosen profile's patterns,
+    // or by every profile's patterns when the profile is picked per file.
+    let patterns: Vec<glob::Pattern> = config
+        .profiles
+        .iter()
+        .filter(|p| args.profile.as_ref().is_none_or(|name| &p.name == name))
+        .flat_map(|p| p.file_patterns.iter())
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .collect();
 
-    Ok(())
+    let files = expand_enqueue_paths(&args.paths, args.recursive, &patterns)?;
+    if files.is_empty() {
+        anyhow::bail!("No matching files found");
+    }
+    if args.output_path.is_some() && files.len() > 1 {
+        anyhow::bail!(
+            "--output-path can only be used with a single file ({} matched)",
+            files.len()
+        );
+    }
+
+    let overrides = JobOverrides {
+        vmaf_target: args.vmaf_target,
+        encoder_params: args.encoder_params,
+        // Relative to the working directory, as the path is used by the daemon
+        output_path: args.output_path.map(std::path::absolute).transpose()?,
+        audio_languages: (!args.audio_languages.is_empty()).then_some(args.audio_languages),
+        subtitle_languages: (!args.subtitle_languages.is_empty())
+            .then_some(args.subtitle_languages),
+    };
+
+    let config = Arc::new(RwLock::new(config));
+    let hooks = HookRunner::new(config.clone(), EventBus::new());
+
+    let mut results = Vec::new();
+
+    for path in files {
+        let request = SubmitRequest {
+            profile: args.profile.clone(),
+            priority: args.priority,
+            overrides: overrides.clone(),
+            ..SubmitRequest::new(path.clone())
+        };
+
+        let result = match submit(&mut queue, &config, &hooks, request).await {
+            Ok(job) => EnqueueResult {
+                path,
+                status: "queued",
+                job_id: Some(job.id),
+                profile: Some(job.profile_name),
+                error: None,
+            },
+            Err(QueueError::DuplicateJob { job_id, .. }) => EnqueueResult {
+                path,
+                status: "duplicate",
+                job_id: Some(job_id),
+                profile: None,
+                error: None,
+            },
+            Err(e) => EnqueueResult {
+                path,
+                status: "failed",
+                job_id: None,
+                profile: None,
+                error: Some(e.to_string()),
+            },
+        };
+        results.push(result);
+    }
+
+    // Let the enqueue hooks finish before the process exits
+    hooks.flush().await;
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let output = EnqueueOutput {
+        queued: count("queued"),
+        skipped: count("duplicate"),
+        failed: count("failed"),
+        results,
+    };
+
+    emit(format, &output, |output| {
+        for result in &output.results {
+            let job_id = result.job_id.as_deref().unwrap_or_default();
+            match result.status {
+                "queued" => println!(
+                    "Queued {} - {} ({})",
+                    job_id,
+                    result.path.display(),
+                    result.profile.as_deref().unwrap_or_default()
+                ),
+                "duplicate" => println!(
+                    "Skipped {} (already queued as {})",
+                    result.path.display(),
+                    job_id
+                ),
+                _ => eprintln!(
+                    "Failed {}: {}",
+                    result.path.display(),
+                    result.error.as_deref().unwrap_or_default()
+                ),
+            }
+        }
+        println!(
+            "\n{} queued, {} skipped, {} failed",
+            output.queued, output.skipped, output.failed
+        );
+    })?;
+
+    Ok(if output.failed > 0 {
+        ExitStatus::Failure
+    } else {
+        ExitStatus::Success
+    })
+}
+
+/// Expands enqueue arguments into absolute file paths.
+///
+/// Files are taken as-is, directories are walked for files matching the
+/// patterns, and anything else is treated as a glob pattern.
+fn expand_enqueue_paths(
+    inputs: &[String],
+    recursive: bool,
+    patterns: &[glob::Pattern],
+) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for input in inputs {
+        let path = PathBuf::from(input);
+
+        if path.is_file() {
+            files.push(path);
+        } else if path.is_dir() {
+            let walker =
+                walkdir::WalkDir::new(&path).max_depth(if recursive { usize::MAX } else { 1 });
+            for entry in walker.into_iter().filter_map(|e| e.ok()) {
+                let matches = entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| patterns.iter().any(|p| p.matches(name)));
+                if entry.file_type().is_file() && matches {
+                    files.push(entry.into_path());
+                }
+            }
+        } else {
+            let matched: Vec<PathBuf> = glob::glob(input)
+                .map_err(|e| anyhow::anyhow!("Invalid path or pattern '{}': {}", input, e))?
+                .filter_map(|p| p.ok())
+                .filter(|p| p.is_file())
+                .collect();
+            if matched.is_empty() {
+                anyhow::bail!("No such file, directory or matching pattern: '{}'", input);
+            }
+            files.extend(matched);
+        }
+    }
+
+    // Profiles are matched by absolute input folder
+    let mut files = files
+        .into_iter()
+        .map(std::path::absolute)
+        .collect::<std::io::Result<Vec<_>>>()?;
+    files.sort();
+    files.dedup();
+
+    Ok(files)
+}
+
+/// Prints the decisions and commands an encode of a file would use.
+async fn plan(
+    config_path: &std::path::Path,
+    file: &std::path::Path,
+    profile: Option<&str>,
+    format: OutputFormat,
+) -> Result<ExitStatus> {
+    let capabilities = SystemCapabilities::detect()?;
+    let config = config::loader::load_and_validate(config_path, &capabilities)?;
+
+    let file = std::path::absolute(file)?;
+    let profile = resolve_profile(&config, &file, profile)?;
+    let output = calculate_output_path(&file, profile);
+    let temp_dir = std::env::temp_dir().join("encode_plan");
+
+    let plan = EncodePlan::build(&file, &output, profile, &temp_dir)?;
+    emit(format, &plan, print_plan)?;
+
+    Ok(ExitStatus::Success)
+}
+
+/// Prints an encode plan as text.
+fn print_plan(plan: &EncodePlan) {
+    println!("Input:   {}", plan.input.display());
+    println!("Profile: {}", plan.profile);
+    println!("Output:  {}", plan.output.display());
+    println!(
+        "Source:  {}, {:.0}s, {} bytes",
+        plan.media.format, plan.media.duration, plan.media.size
+    );
+    for video in &plan.video_streams {
+        println!(
+            "Video:   #{} {} {}x{} {}-bit{}",
+            video.index,
+            video.codec,
+            video.width,
+            video.height,
+            video.bit_depth,
+            video
+                .hdr_format
+                .as_deref()
+                .map(|h| format!(" {}", h))
+                .unwrap_or_default()
+        );
+    }
+
+    println!("\nAudio:");
+    println!(
+        "  {:<7} {:<5} {:<10} {:<3} {:<5} ACTION",
+        "STREAM", "LANG", "CODEC", "CH", "RULE"
+    );
+    for decision in &plan.audio {
+        let stream = &decision.stream;
+        println!(
+            "  {:<7} {:<5} {:<10} {:<3} {:<5} {}",
+            stream.index,
+            stream.language.as_deref().unwrap_or("und"),
+            stream.codec,
+            stream.channels,
+            decision
+                .matched_rule
+                .map(|r| r.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            decision.action
+        );
+    }
+
+    println!("\nSubtitles:");
+    println!(
+        "  {:<7} {:<5} {:<18} {:<14} ACTION",
+        "STREAM", "LANG", "CODEC", "FLAGS"
+    );
+    for decision in &plan.subtitles {
+        let stream = &decision.stream;
+        let flags: Vec<&str> = [
+            (stream.is_forced, "forced"),
+            (stream.is_default, "default"),
+            (stream.is_hearing_impaired, "sdh"),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, name)| *name)
+        .collect();
+        println!(
+            "  {:<7} {:<5} {:<18} {:<14} {}",
+            stream.index,
+            stream.language.as_deref().unwrap_or("und"),
+            stream.codec,
+            if flags.is_empty() {
+                "-".to_string()
+            } else {
+                flags.join(",")
+            },
+            decision.action
+        );
+    }
+
+    if let Some(skip) = &plan.skip {
+        println!(
+            "\nSkip:    rule '{}' ({}): {}",
+            skip.rule, skip.action, skip.reason
+        );
+    }
+
+    println!("\nCommands:");
+    for planned in &plan.commands {
+        println!("  [{}] {}", planned.phase, planned.command);
+    }
 }
@@ -1,9 +1,11 @@
+use std::process::ExitCode;
+
 use anyhow::Result;
 use clap::Parser;
 use encoding_pipeline::{cli::Cli, run};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
     run(cli).await
 }
@@ -1,11 +1,13 @@
 //! Audio track selection and processing logic.
 
+use serde::Serialize;
+
 use crate::config::model::{AudioAction, AudioConfig, AudioMatchCriteria, AudioRule, TrackFlags};
 
 use super::probe::AudioStream;
 
 /// Represents a decision for an audio track.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AudioDecision {
     /// The original stream.
     pub stream: AudioStream,
@@ -16,15 +18,13 @@ pub struct AudioDecision {
 }
 
 /// Action to take for an audio track.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum AudioTrackAction {
     /// Copy the track as-is.
     Passthrough,
     /// Transcode to the specified codec and bitrate.
-    Transcode {
-        codec: String,
-        bitrate: String,
-    },
+    Transcode { codec: String, bitrate: String },
     /// Exclude the track.
     Exclude,
     /// Copy and add a stereo downmix.
@@ -41,13 +41,41 @@ pub enum AudioTrackAction {
     },
 }
 
+impl std::fmt::Display for AudioTrackAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passthrough => write!(f, "passthrough"),
+            Self::Transcode { codec, bitrate } => write!(f, "transcode {} {}", codec, bitrate),
+            Self::Exclude => write!(f, "exclude"),
+            Self::PassthroughWithDownmix {
+                downmix_codec,
+                downmix_bitrate,
+            } => {
+                write!(
+                    f,
+                    "passthrough + downmix {} {}",
+                    downmix_codec, downmix_bitrate
+                )
+            }
+            Self::TranscodeWithDownmix {
+                codec,
+                bitrate,
+                downmix_codec,
+                downmix_bitrate,
+            } => write!(
+                f,
+                "transcode {} {} + downmix {} {}",
+                codec, bitrate, downmix_codec, downmix_bitrate
+            ),
+        }
+    }
+}
+
 /// Processes audio streams and determines what to do with each.
-pub fn process_audio_streams(
-    streams: &[AudioStream],
-    config: &AudioConfig,
-) -> Vec<AudioDecision> {
+pub fn process_audio_streams(streams: &[AudioStream], config: &AudioConfig) -> Vec<AudioDecision> {
     let mut decisions = Vec::new();
-    let mut track_counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    let mut track_counts: std::collections::HashMap<String, usize> =
+        std::collections::HashMap::new();
 
     for stream in streams {
         let decision = match match_stream_to_rule(stream, config) {
@@ -77,8 +105,10 @@ pub fn process_audio_streams(
                 // Apply fallback
                 let action = match config.fallback {
                     crate::config::model::TrackFallback::Exclude => AudioTrackAction::Exclude,
-                    crate::config::model::TrackFallback::Include |
-                    crate::config::model::TrackFallback::Passthrough => AudioTrackAction::Passthrough,
+                    crate::config::model::TrackFallback::Include
+                    | crate::config::model::TrackFallback::Passthrough => {
+                        AudioTrackAction::Passthrough
+                    }
                 };
                 AudioDecision {
                     stream: stream.clone(),
@@ -136,7 +166,10 @@ fn matches_criteria(stream: &AudioStream, criteria: &AudioMatchCriteria) -> bool
 
     // Check multiple codecs
     if let Some(codecs) = &criteria.codecs {
-        if !codecs.iter().any(|c| c.to_lowercase() == stream.codec.to_lowercase()) {
+        if !codecs
+            .iter()
+            .any(|c| c.to_lowercase() == stream.codec.to_lowercase())
+        {
             return false;
         }
     }
@@ -212,7 +245,11 @@ fn create_decision(stream: &AudioStream, rule: &AudioRule, rule_idx: usize) -> A
     use crate::config::model::DownmixMode;
 
     let base_action = determine_base_action(stream, rule);
-    let has_downmix = rule.downmix.as_ref().map(|d| !matches!(d.mode, DownmixMode::None)).unwrap_or(false);
+    let has_downmix = rule
+        .downmix
+        .as_ref()
+        .map(|d| !matches!(d.mode, DownmixMode::None))
+        .unwrap_or(false);
 
     let action = if has_downmix && stream.channels > 2 {
         let downmix = rule.downmix.as_ref().unwrap();
@@ -221,12 +258,14 @@ fn create_decision(stream: &AudioStream, rule: &AudioRule, rule_idx: usize) -> A
                 downmix_codec: downmix.codec.clone(),
                 downmix_bitrate: downmix.bitrate.clone(),
             },
-            AudioTrackAction::Transcode { codec, bitrate } => AudioTrackAction::TranscodeWithDownmix {
-                codec,
-                bitrate,
-                downmix_codec: downmix.codec.clone(),
-                downmix_bitrate: downmix.bitrate.clone(),
-            },
+            AudioTrackAction::Transcode { codec, bitrate } => {
+                AudioTrackAction::TranscodeWithDownmix {
+                    codec,
+                    bitrate,
+                    downmix_codec: downmix.codec.clone(),
+                    downmix_bitrate: downmix.bitrate.clone(),
+                }
+            }
             other => other,
         }
     } else {
@@ -248,7 +287,10 @@ fn determine_base_action(stream: &AudioStream, rule: &AudioRule) -> AudioTrackAc
         AudioAction::Transcode => {
             if let Some(transcode) = &rule.transcode {
                 let bitrate = if super::probe::is_lossless_codec(&stream.codec) {
-                    transcode.lossless_bitrate.as_ref().unwrap_or(&transcode.bitrate)
+                    transcode
+                        .lossless_bitrate
+                        .as_ref()
+                        .unwrap_or(&transcode.bitrate)
                 } else {
                     &transcode.bitrate
                 };
@@ -270,7 +312,10 @@ fn determine_base_action(stream: &AudioStream, rule: &AudioRule) -> AudioTrackAc
                 AudioTrackAction::Passthrough
             } else if let Some(transcode) = &rule.transcode {
                 let bitrate = if super::probe::is_lossless_codec(&stream.codec) {
-                    transcode.lossless_bitrate.as_ref().unwrap_or(&transcode.bitrate)
+                    transcode
+                        .lossless_bitrate
+                        .as_ref()
+                        .unwrap_or(&transcode.bitrate)
                 } else {
                     &transcode.bitrate
                 };
@@ -2,6 +2,8 @@
 
 pub mod audio;
 pub mod probe;
+pub mod provenance;
+pub mod skip;
 pub mod subtitle;
 
 pub use probe::{MediaInfo, ProbeResult};
@@ -1,5 +1,6 @@
 //! FFprobe wrapper for media analysis.
 
+use std::collections::HashMap;
 use std::path::Path;
 use std::process::Command;
 
@@ -32,6 +33,9 @@ pub struct MediaInfo {
     pub size: u64,
     /// Overall bitrate in bits per second.
     pub bitrate: u64,
+    /// Container-level tags.
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
 }
 
 /// Video stream information.
@@ -47,6 +51,9 @@ pub struct VideoStream {
     pub height: u32,
     /// Frame rate as a string (e.g., "24000/1001").
     pub frame_rate: String,
+    /// Bitrate in bits per second, if known.
+    #[serde(default)]
+    pub bitrate: Option<u64>,
     /// Bit depth.
     pub bit_depth: u8,
     /// Color space.
@@ -59,6 +66,18 @@ pub struct VideoStream {
     pub hdr_format: Option<String>,
 }
 
+impl VideoStream {
+    /// Returns the frame rate in frames per second, if it can be parsed.
+    pub fn fps(&self) -> Option<f64> {
+        let (num, den) = self
+            .frame_rate
+            .split_once('/')
+            .unwrap_or((&self.frame_rate, "1"));
+        let (num, den): (f64, f64) = (num.parse().ok()?, den.parse().ok()?);
+        (num > 0.0 && den > 0.0).then(|| num / den)
+    }
+}
+
 /// Audio stream information.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioStream {
@@ -118,8 +137,10 @@ impl SubtitleStream {
 pub fn probe(path: &Path) -> Result<ProbeResult> {
     let output = Command::new("ffprobe")
         .args([
-            "-v", "quiet",
-            "-print_format", "json",
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
             "-show_format",
             "-show_streams",
         ])
@@ -134,37 +155,53 @@ pub fn probe(path: &Path) -> Result<ProbeResult> {
         );
     }
 
-    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
-        .context("Failed to parse ffprobe output")?;
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).context("Failed to parse ffprobe output")?;
 
     parse_probe_output(&json, path)
 }
 
 /// Parses ffprobe JSON output into structured data.
 fn parse_probe_output(json: &serde_json::Value, path: &Path) -> Result<ProbeResult> {
-    let format = json.get("format").context("Missing format in ffprobe output")?;
-    let streams = json.get("streams")
+    let format = json
+        .get("format")
+        .context("Missing format in ffprobe output")?;
+    let streams = json
+        .get("streams")
         .and_then(|s| s.as_array())
         .context("Missing streams in ffprobe output")?;
 
     let info = MediaInfo {
         path: path.to_string_lossy().to_string(),
-        format: format.get("format_name")
+        format: format
+            .get("format_name")
             .and_then(|v| v.as_str())
             .unwrap_or("unknown")
             .to_string(),
-        duration: format.get("duration")
+        duration: format
+            .get("duration")
             .and_then(|v| v.as_str())
             .and_then(|s| s.parse().ok())
             .unwrap_or(0.0),
-        size: format.get("size")
+        size: format
+            .get("size")
             .and_then(|v| v.as_str())
             .and_then(|s| s.parse().ok())
             .unwrap_or(0),
-        bitrate: format.get("bit_rate")
+        bitrate: format
+            .get("bit_rate")
             .and_then(|v| v.as_str())
             .and_then(|s| s.parse().ok())
             .unwrap_or(0),
+        tags: format
+            .get("tags")
+            .and_then(|v| v.as_object())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default(),
     };
 
     let mut video_streams = Vec::new();
@@ -209,17 +246,38 @@ fn parse_video_stream(stream: &serde_json::Value) -> Option<VideoStream> {
         codec: stream.get("codec_name")?.as_str()?.to_string(),
         width: stream.get("width")?.as_u64()? as u32,
         height: stream.get("height")?.as_u64()? as u32,
-        frame_rate: stream.get("r_frame_rate")
+        frame_rate: stream
+            .get("r_frame_rate")
             .and_then(|v| v.as_str())
             .unwrap_or("0/1")
             .to_string(),
-        bit_depth: stream.get("bits_per_raw_sample")
+        // Matroska stores the bitrate in the BPS statistics tag instead
+        bitrate: stream
+            .get("bit_rate")
+            .or_else(|| {
+                stream
+                    .get("tags")
+                    .and_then(|t| t.get("BPS").or_else(|| t.get("BPS-eng")))
+            })
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse().ok()),
+        bit_depth: stream
+            .get("bits_per_raw_sample")
             .and_then(|v| v.as_str())
             .and_then(|s| s.parse().ok())
             .unwrap_or(8),
-        color_space: stream.get("color_space").and_then(|v| v.as_str()).map(String::from),
-        color_primaries: stream.get("color_primaries").and_then(|v| v.as_str()).map(String::from),
-        color_transfer: stream.get("color_transfer").and_then(|v| v.as_str()).map(String::from),
+        color_space: stream
+            .get("color_space")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        color_primaries: stream
+            .get("color_primaries")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        color_transfer: stream
+            .get("color_transfer")
+            .and_then(|v| v.as_str())
+            .map(String::from),
         hdr_format: detect_hdr_format(stream),
     })
 }
@@ -252,27 +310,50 @@ fn parse_audio_stream(stream: &serde_json::Value) -> Option<AudioStream> {
     let tags = stream.get("tags");
     let disposition = stream.get("disposition");
 
-    let title = tags.and_then(|t| t.get("title")).and_then(|v| v.as_str()).map(String::from);
-    let is_commentary = title.as_ref().map(|t| t.to_lowercase().contains("commentary")).unwrap_or(false)
-        || disposition.and_then(|d| d.get("comment")).and_then(|v| v.as_i64()) == Some(1);
+    let title = tags
+        .and_then(|t| t.get("title"))
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    let is_commentary = title
+        .as_ref()
+        .map(|t| t.to_lowercase().contains("commentary"))
+        .unwrap_or(false)
+        || disposition
+            .and_then(|d| d.get("comment"))
+            .and_then(|v| v.as_i64())
+            == Some(1);
 
     Some(AudioStream {
         index: stream.get("index")?.as_u64()? as usize,
         codec: stream.get("codec_name")?.as_str()?.to_string(),
         channels: stream.get("channels")?.as_u64()? as u8,
-        channel_layout: stream.get("channel_layout").and_then(|v| v.as_str()).map(String::from),
-        sample_rate: stream.get("sample_rate")
+        channel_layout: stream
+            .get("channel_layout")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        sample_rate: stream
+            .get("sample_rate")
             .and_then(|v| v.as_str())
             .and_then(|s| s.parse().ok())
             .unwrap_or(48000),
-        bitrate: stream.get("bit_rate")
+        bitrate: stream
+            .get("bit_rate")
             .and_then(|v| v.as_str())
             .and_then(|s| s.parse().ok()),
-        language: tags.and_then(|t| t.get("language")).and_then(|v| v.as_str()).map(String::from),
+        language: tags
+            .and_then(|t| t.get("language"))
+            .and_then(|v| v.as_str())
+            .map(String::from),
         title,
-        is_default: disposition.and_then(|d| d.get("default")).and_then(|v| v.as_i64()) == Some(1),
+        is_default: disposition
+            .and_then(|d| d.get("default"))
+            .and_then(|v| v.as_i64())
+            == Some(1),
         is_commentary,
-        is_visual_impaired: disposition.and_then(|d| d.get("visual_impaired")).and_then(|v| v.as_i64()) == Some(1),
+        is_visual_impaired: disposition
+            .and_then(|d| d.get("visual_impaired"))
+            .and_then(|v| v.as_i64())
+            == Some(1),
     })
 }
 
@@ -287,11 +368,26 @@ fn parse_subtitle_stream(stream: &serde_json::Value) -> Option<SubtitleStream> {
     Some(SubtitleStream {
         index: stream.get("index")?.as_u64()? as usize,
         codec: codec.to_string(),
-        language: tags.and_then(|t| t.get("language")).and_then(|v| v.as_str()).map(String::from),
-        title: tags.and_then(|t| t.get("title")).and_then(|v| v.as_str()).map(String::from),
-        is_default: disposition.and_then(|d| d.get("default")).and_then(|v| v.as_i64()) == Some(1),
-        is_forced: disposition.and_then(|d| d.get("forced")).and_then(|v| v.as_i64()) == Some(1),
-        is_hearing_impaired: disposition.and_then(|d| d.get("hearing_impaired")).and_then(|v| v.as_i64()) == Some(1),
+        language: tags
+            .and_then(|t| t.get("language"))
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        title: tags
+            .and_then(|t| t.get("title"))
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        is_default: disposition
+            .and_then(|d| d.get("default"))
+            .and_then(|v| v.as_i64())
+            == Some(1),
+        is_forced: disposition
+            .and_then(|d| d.get("forced"))
+            .and_then(|v| v.as_i64())
+            == Some(1),
+        is_hearing_impaired: disposition
+            .and_then(|d| d.get("hearing_impaired"))
+            .and_then(|v| v.as_i64())
+            == Some(1),
         is_image_based,
     })
 }
@@ -300,6 +396,14 @@ fn parse_subtitle_stream(stream: &serde_json::Value) -> Option<SubtitleStream> {
 pub fn is_lossless_codec(codec: &str) -> bool {
     matches!(
         codec.to_lowercase().as_str(),
-        "truehd" | "mlp" | "dts-hd ma" | "dtshd" | "flac" | "alac" | "pcm_s16le" | "pcm_s24le" | "pcm_s32le"
+        "truehd"
+            | "mlp"
+            | "dts-hd ma"
+            | "dtshd"
+            | "flac"
+            | "alac"
+            | "pcm_s16le"
+            | "pcm_s24le"
+            | "pcm_s32le"
     )
 }
@@ -0,0 +1,34 @@
+//! Provenance tag marking files this pipeline produced.
+//!
+//! Outputs are muxed with a global Matroska tag naming the profile, so a
+//! file can be recognized as already processed when it shows up again.
+
+use super::probe::MediaInfo;
+
+/// Name of the global tag written into outputs.
+pub const TAG_NAME: &str = "ENCODING_PIPELINE";
+
+/// Builds the mkvmerge global tags file for an output of the profile.
+pub fn tags_xml(profile: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <Tags>\n  <Tag>\n    <Simple>\n      <Name>{}</Name>\n      <String>{}</String>\n    </Simple>\n  </Tag>\n</Tags>\n",
+        TAG_NAME,
+        escape_xml(profile)
+    )
+}
+
+/// Returns the profile that produced a file, if it carries the provenance tag.
+pub fn tagged_profile(info: &MediaInfo) -> Option<&str> {
+    info.tags
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(TAG_NAME))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Escapes text for an XML element.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,82 @@
+//! Skip rules that spare already-efficient sources a full re-encode.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::model::{SkipAction, SkipRule};
+
+use super::probe::ProbeResult;
+use super::provenance;
+
+/// A skip rule that matched a source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkipDecision {
+    /// Name of the matching rule.
+    pub rule: String,
+    /// What is done instead of encoding.
+    pub action: SkipAction,
+    /// Why the rule matched.
+    pub reason: String,
+}
+
+/// Returns the first rule of the profile that matches the source.
+pub fn evaluate(rules: &[SkipRule], probe: &ProbeResult) -> Option<SkipDecision> {
+    rules.iter().find_map(|rule| {
+        let reasons = match_rule(rule, probe)?;
+        Some(SkipDecision {
+            rule: rule.name.clone(),
+            action: rule.action,
+            reason: reasons.join(", "),
+        })
+    })
+}
+
+/// Returns why every condition of the rule holds, or `None` if one does not.
+///
+/// A rule without conditions never matches.
+fn match_rule(rule: &SkipRule, probe: &ProbeResult) -> Option<Vec<String>> {
+    let video = probe.video_streams.first();
+    let mut reasons = Vec::new();
+
+    if !rule.codecs.is_empty() {
+        let codec = &video?.codec;
+        if !rule.codecs.iter().any(|c| c.eq_ignore_ascii_case(codec)) {
+            return None;
+        }
+        reasons.push(format!("source codec is {}", codec));
+    }
+
+    if let Some(max) = rule.max_bits_per_pixel {
+        let bpp = bits_per_pixel(probe)?;
+        if bpp >= max {
+            return None;
+        }
+        reasons.push(format!("{:.4} bits per pixel", bpp));
+    }
+
+    if let Some(floor) = rule.below_height {
+        let height = video?.height;
+        if height >= floor {
+            return None;
+        }
+        reasons.push(format!("height {} is below {}", height, floor));
+    }
+
+    if rule.provenance {
+        let profile = provenance::tagged_profile(&probe.info)?;
+        reasons.push(format!("already produced by profile '{}'", profile));
+    }
+
+    (!reasons.is_empty()).then_some(reasons)
+}
+
+/// Returns the video bits per pixel per frame.
+///
+/// Falls back to the container's overall bitrate when the stream has none.
+pub fn bits_per_pixel(probe: &ProbeResult) -> Option<f64> {
+    let video = probe.video_streams.first()?;
+    let bitrate = video
+        .bitrate
+        .or((probe.info.bitrate > 0).then_some(probe.info.bitrate))?;
+    let pixels_per_second = f64::from(video.width) * f64::from(video.height) * video.fps()?;
+    (pixels_per_second > 0.0).then(|| bitrate as f64 / pixels_per_second)
+}
@@ -1,11 +1,13 @@
 //! Subtitle track selection and processing logic.
 
+use serde::Serialize;
+
 use crate::config::model::{ImageSubsMode, SubtitleConfig, SubtitleTrackConfig, TrackFallback};
 
 use super::probe::SubtitleStream;
 
 /// Represents a decision for a subtitle track.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SubtitleDecision {
     /// The original stream.
     pub stream: SubtitleStream,
@@ -14,7 +16,8 @@ pub struct SubtitleDecision {
 }
 
 /// Action to take for a subtitle track.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SubtitleTrackAction {
     /// Copy the track as-is.
     Copy,
@@ -24,6 +27,16 @@ pub enum SubtitleTrackAction {
     Exclude,
 }
 
+impl std::fmt::Display for SubtitleTrackAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Copy => write!(f, "copy"),
+            Self::BurnIn => write!(f, "burn_in"),
+            Self::Exclude => write!(f, "exclude"),
+        }
+    }
+}
+
 /// Processes subtitle streams and determines what to do with each.
 pub fn process_subtitle_streams(
     streams: &[SubtitleStream],
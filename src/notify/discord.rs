@@ -5,8 +5,11 @@ use serde::Serialize;
 use tracing::{error, info};
 
 use crate::config::model::{DiscordConfig, DiscordEvents};
+use crate::encoder::EncodePlan;
 use crate::error::NotificationError;
-use crate::queue::job::{EncodeJob, EncodeResultMetadata, JobStatus};
+use crate::media::audio::AudioTrackAction;
+use crate::media::subtitle::SubtitleTrackAction;
+use crate::queue::job::EncodeJob;
 
 /// Sends notifications to Discord via webhook.
 pub struct DiscordNotifier {
@@ -56,7 +59,9 @@ impl DiscordNotifier {
             fields: vec![
                 EmbedField {
                     name: "File".to_string(),
-                    value: job.input_path.file_name()
+                    value: job
+                        .input_path
+                        .file_name()
                         .map(|n| n.to_string_lossy().to_string())
                         .unwrap_or_else(|| "Unknown".to_string()),
                     inline: false,
@@ -87,6 +92,66 @@ impl DiscordNotifier {
         self.send_embed(embed).await
     }
 
+    /// Notifies about a job planned in dry-run mode.
+    pub async fn notify_dry_run(
+        &self,
+        job: &EncodeJob,
+        plan: &EncodePlan,
+    ) -> Result<(), NotificationError> {
+        if !self.events.on_encode_success {
+            return Ok(());
+        }
+
+        let kept_audio = plan
+            .audio
+            .iter()
+            .filter(|d| !matches!(d.action, AudioTrackAction::Exclude))
+            .count();
+        let kept_subtitles = plan
+            .subtitles
+            .iter()
+            .filter(|d| !matches!(d.action, SubtitleTrackAction::Exclude))
+            .count();
+
+        let embed = DiscordEmbed {
+            title: "Dry Run: Would Encode".to_string(),
+            color: 0x3498DB, // Blue
+            fields: vec![
+                EmbedField {
+                    name: "File".to_string(),
+                    value: job
+                        .input_path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "Unknown".to_string()),
+                    inline: false,
+                },
+                EmbedField {
+                    name: "Output".to_string(),
+                    value: job.output_path.display().to_string(),
+                    inline: false,
+                },
+                EmbedField {
+                    name: "Profile".to_string(),
+                    value: job.profile_name.clone(),
+                    inline: true,
+                },
+                EmbedField {
+                    name: "Audio Tracks".to_string(),
+                    value: format!("{} of {}", kept_audio, plan.audio.len()),
+                    inline: true,
+                },
+                EmbedField {
+                    name: "Subtitle Tracks".to_string(),
+                    value: format!("{} of {}", kept_subtitles, plan.subtitles.len()),
+                    inline: true,
+                },
+            ],
+        };
+
+        self.send_embed(embed).await
+    }
+
     /// Notifies about a failed encode.
     pub async fn notify_encode_failure(&self, job: &EncodeJob) -> Result<(), NotificationError> {
         if !self.events.on_encode_failure {
@@ -106,7 +171,9 @@ impl DiscordNotifier {
             fields: vec![
                 EmbedField {
                     name: "File".to_string(),
-                    value: job.input_path.file_name()
+                    value: job
+                        .input_path
+                        .file_name()
                         .map(|n| n.to_string_lossy().to_string())
                         .unwrap_or_else(|| "Unknown".to_string()),
                     inline: false,
@@ -151,7 +218,9 @@ impl DiscordNotifier {
             fields: vec![
                 EmbedField {
                     name: "File".to_string(),
-                    value: job.input_path.file_name()
+                    value: job
+                        .input_path
+                        .file_name()
                         .map(|n| n.to_string_lossy().to_string())
                         .unwrap_or_else(|| "Unknown".to_string()),
                     inline: false,
@@ -186,13 +255,11 @@ impl DiscordNotifier {
         let embed = DiscordEmbed {
             title: "Queue Empty".to_string(),
             color: 0x0088FF, // Blue
-            fields: vec![
-                EmbedField {
-                    name: "Status".to_string(),
-                    value: "All encoding jobs have been processed.".to_string(),
-                    inline: false,
-                },
-            ],
+            fields: vec![EmbedField {
+                name: "Status".to_string(),
+                value: "All encoding jobs have been processed.".to_string(),
+                inline: false,
+            }],
         };
 
         self.send_embed(embed).await
@@ -210,7 +277,11 @@ impl DiscordNotifier {
         content: &str,
     ) -> Result<(), NotificationError> {
         let payload = DiscordPayload {
-            content: if content.is_empty() { None } else { Some(content.to_string()) },
+            content: if content.is_empty() {
+                None
+            } else {
+                Some(content.to_string())
+            },
             embeds: vec![embed],
         };
 
@@ -0,0 +1,75 @@
+//! In-process event bus for live progress and job lifecycle events.
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::config::model::HookEvent;
+use crate::queue::job::JobStatus;
+
+/// Number of events buffered for slow subscribers before they start lagging.
+const EVENT_BUFFER: usize = 256;
+
+/// An event published while the pipeline runs.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PipelineEvent {
+    /// Encoding progress for a job.
+    Progress {
+        job_id: String,
+        percent: f32,
+        phase: String,
+        speed: Option<String>,
+        eta: Option<String>,
+    },
+    /// A job moved to a new encoding phase.
+    PhaseChanged { job_id: String, phase: String },
+    /// A job lifecycle transition (enqueued, started, succeeded, ...).
+    Lifecycle {
+        job_id: String,
+        event: HookEvent,
+        status: JobStatus,
+        profile: String,
+    },
+}
+
+impl PipelineEvent {
+    /// Returns the SSE event name for this event.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Progress { .. } => "progress",
+            Self::PhaseChanged { .. } => "phase",
+            Self::Lifecycle { .. } => "lifecycle",
+        }
+    }
+}
+
+/// Broadcast channel shared by event producers and streaming clients.
+#[derive(Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<PipelineEvent>,
+}
+
+impl EventBus {
+    /// Creates a new event bus.
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(EVENT_BUFFER);
+        Self { tx }
+    }
+
+    /// Publishes an event to all current subscribers.
+    pub fn publish(&self, event: PipelineEvent) {
+        // No subscribers is not an error
+        let _ = self.tx.send(event);
+    }
+
+    /// Subscribes to future events.
+    pub fn subscribe(&self) -> broadcast::Receiver<PipelineEvent> {
+        self.tx.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,262 @@
+//! User-defined lifecycle hooks (local commands and HTTP callbacks).
+
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::RwLock;
+use tokio::task::JoinSet;
+use tracing::{debug, info, warn};
+
+use super::events::{EventBus, PipelineEvent};
+use crate::config::model::{AppConfig, HookConfig, HookEvent, HttpHookConfig};
+use crate::config::redact;
+use crate::error::NotificationError;
+use crate::queue::job::EncodeJob;
+
+/// Runs configured hooks when job lifecycle events occur.
+///
+/// Every dispatched event is also published on the event bus, so streaming
+/// clients see exactly the lifecycle events that hooks see.
+#[derive(Clone)]
+pub struct HookRunner {
+    /// Current configuration (hooks are looked up on every dispatch).
+    config: Arc<RwLock<AppConfig>>,
+    /// Event bus for lifecycle events.
+    events: EventBus,
+    /// HTTP client for HTTP hooks.
+    client: reqwest::Client,
+    /// Dispatches running in the background.
+    background: Arc<Mutex<JoinSet<()>>>,
+}
+
+/// Payload POSTed to HTTP hooks.
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    event: HookEvent,
+    job: &'a EncodeJob,
+}
+
+impl HookRunner {
+    /// Creates a new hook runner.
+    pub fn new(config: Arc<RwLock<AppConfig>>, events: EventBus) -> Self {
+        Self {
+            config,
+            events,
+            client: reqwest::Client::new(),
+            background: Arc::new(Mutex::new(JoinSet::new())),
+        }
+    }
+
+    /// Runs the hooks for an event in the background, so slow hooks do not
+    /// hold up the caller.
+    ///
+    /// Only for events that cannot fail the job; failures are logged.
+    pub fn spawn_dispatch(&self, event: HookEvent, job: EncodeJob) {
+        let runner = self.clone();
+        let mut background = self.background.lock().unwrap_or_else(|e| e.into_inner());
+        while background.try_join_next().is_some() {}
+        background.spawn(async move {
+            let _ = runner.dispatch(event, &job).await;
+        });
+    }
+
+    /// Waits for background dispatches to finish, for callers about to exit.
+    pub async fn flush(&self) {
+        let mut pending =
+            std::mem::take(&mut *self.background.lock().unwrap_or_else(|e| e.into_inner()));
+        while pending.join_next().await.is_some() {}
+    }
+
+    /// Runs all global and profile hooks registered for the event.
+    ///
+    /// Hook failures are logged. An error is returned only when a failing
+    /// hook has `fail_job` set and the event can still fail the job.
+    pub async fn dispatch(
+        &self,
+        event: HookEvent,
+        job: &EncodeJob,
+    ) -> Result<(), NotificationError> {
+        self.events.publish(PipelineEvent::Lifecycle {
+            job_id: job.id.clone(),
+            event,
+            status: job.status,
+            profile: job.profile_name.clone(),
+        });
+
+        let hooks: Vec<HookConfig> = {
+            let config = self.config.read().await;
+            let profile_hooks = config
+                .profiles
+                .iter()
+                .find(|p| p.name == job.profile_name)
+                .map(|p| p.hooks.as_slice())
+                .unwrap_or_default();
+
+            config
+                .global
+                .hooks
+                .iter()
+                .chain(profile_hooks)
+                .filter(|h| h.events.contains(&event))
+                .cloned()
+                .collect()
+        };
+
+        let can_fail_job = matches!(event, HookEvent::Started | HookEvent::Succeeded);
+        let mut first_error = None;
+
+        for hook in &hooks {
+            if let Err(e) = self.run_with_retries(hook, event, job).await {
+                warn!(hook = %hook.name, %event, job_id = %job.id, error = %e, "Hook failed");
+                if hook.fail_job && can_fail_job && first_error.is_none() {
+                    first_error = Some(e);
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs a hook, retrying on failure as configured.
+    async fn run_with_retries(
+        &self,
+        hook: &HookConfig,
+        event: HookEvent,
+        job: &EncodeJob,
+    ) -> Result<(), NotificationError> {
+        let timeout = Duration::from_secs(hook.timeout_seconds);
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            let result = match tokio::time::timeout(timeout, self.run_once(hook, event, job)).await
+            {
+                Ok(result) => result,
+                Err(_) => Err(format!("timed out after {} seconds", hook.timeout_seconds)),
+            };
+
+            match result {
+                Ok(()) => {
+                    info!(hook = %hook.name, %event, job_id = %job.id, "Hook completed");
+                    return Ok(());
+                }
+                Err(message) if attempt > hook.retries => {
+                    return Err(NotificationError::HookFailed {
+                        name: hook.name.clone(),
+                        message,
+                    });
+                }
+                Err(message) => {
+                    debug!(hook = %hook.name, attempt, error = %message, "Hook attempt failed, retrying");
+                    tokio::time::sleep(Duration::from_secs(hook.retry_delay_seconds)).await;
+                }
+            }
+        }
+    }
+
+    /// Runs a single hook attempt.
+    async fn run_once(
+        &self,
+        hook: &HookConfig,
+        event: HookEvent,
+        job: &EncodeJob,
+    ) -> Result<(), String> {
+        if let Some(command) = &hook.command {
+            run_command(command, event, job).await
+        } else if redact::hook_is_redacted(hook) {
+            Err("credentials are redacted in the cached configuration; define the hook in this node's config file"
+                .to_string())
+        } else if let Some(http) = &hook.http {
+            self.post_http(http, event, job).await
+        } else {
+            Err("hook has neither 'command' nor 'http' configured".to_string())
+        }
+    }
+
+    /// POSTs the event and job to an HTTP endpoint.
+    async fn post_http(
+        &self,
+        http: &HttpHookConfig,
+        event: HookEvent,
+        job: &EncodeJob,
+    ) -> Result<(), String> {
+        let mut request = self
+            .client
+            .post(&http.url)
+            .json(&HookPayload { event, job });
+
+        for (name, value) in &http.headers {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().await.map_err(|e| e.to_string())?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("HTTP {}: {}", status, text));
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs a local command with the job JSON on stdin and job details in the environment.
+async fn run_command(command: &[String], event: HookEvent, job: &EncodeJob) -> Result<(), String> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| "empty command".to_string())?;
+
+    let job_json = serde_json::to_string(job).map_err(|e| e.to_string())?;
+
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    cmd.env("ENCODE_HOOK_EVENT", event.to_string());
+    cmd.env("ENCODE_JOB_ID", &job.id);
+    cmd.env("ENCODE_JOB_STATUS", format!("{:?}", job.status));
+    cmd.env("ENCODE_PROFILE", &job.profile_name);
+    cmd.env("ENCODE_INPUT_PATH", &job.input_path);
+    cmd.env("ENCODE_OUTPUT_PATH", &job.output_path);
+    cmd.env("ENCODE_ATTEMPT", job.attempt_count.to_string());
+    if let Some(error) = &job.error_message {
+        cmd.env("ENCODE_ERROR", error);
+    }
+    if let Some(category) = job.failure_category {
+        cmd.env("ENCODE_ERROR_CATEGORY", category.to_string());
+    }
+
+    cmd.stdin(Stdio::piped());
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    cmd.kill_on_drop(true);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("failed to spawn '{}': {}", program, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores stdin may close it early; that is not an error.
+        let _ = stdin.write_all(job_json.as_bytes()).await;
+    }
+
+    let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "exited with code {}: {}",
+            output.status.code().unwrap_or(-1),
+            stderr.trim()
+        ));
+    }
+
+    Ok(())
+}
@@ -1,7 +1,11 @@
-//! Notification system for Discord webhooks and Prometheus metrics.
+//! Notification system for Discord webhooks, Prometheus metrics and user hooks.
 
 pub mod discord;
+pub mod events;
+pub mod hooks;
 pub mod prometheus;
 
 pub use discord::DiscordNotifier;
+pub use events::{EventBus, PipelineEvent};
+pub use hooks::HookRunner;
 pub use prometheus::MetricsServer;
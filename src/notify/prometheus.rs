@@ -4,13 +4,12 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 
 use anyhow::Result;
-use prometheus::{
-    Counter, CounterVec, Gauge, Histogram, HistogramOpts, HistogramVec, Opts, Registry,
-};
-use tokio::sync::RwLock;
+use prometheus::{CounterVec, Gauge, GaugeVec, Histogram, HistogramOpts, Opts, Registry};
 use tracing::{error, info};
 
-use crate::error::NotificationError;
+use crate::config::model::SkipAction;
+use crate::encoder::throttle::ThrottleStatus;
+use crate::error::{ErrorCategory, NotificationError};
 use crate::queue::job::EncodeResultMetadata;
 
 /// Prometheus metrics for the encoding pipeline.
@@ -23,6 +22,8 @@ pub struct Metrics {
     pub dead_letter_count: Gauge,
     /// Total encodes by status.
     pub encodes_total: CounterVec,
+    /// Failed encode attempts, labelled by failure category and outcome.
+    pub encode_failures_total: CounterVec,
     /// Encode duration in seconds.
     pub encode_duration_seconds: Histogram,
     /// Size reduction ratio.
@@ -31,6 +32,22 @@ pub struct Metrics {
     pub vmaf_score: Histogram,
     /// Currently encoding jobs.
     pub jobs_in_progress: Gauge,
+    /// Progress of each running job, labelled by job ID and profile.
+    pub job_progress: GaugeVec,
+    /// av1an workers reserved by each running job.
+    pub job_workers: GaugeVec,
+    /// Whether Redis is reachable (1) or writes are being spooled (0).
+    pub redis_up: Gauge,
+    /// Queue writes waiting in the spool.
+    pub spool_entries: Gauge,
+    /// Whether starting new jobs is paused (1) or not (0).
+    pub throttle_paused: Gauge,
+    /// Workers cap for new jobs while throttled, 0 when not reduced.
+    pub throttle_workers_limit: Gauge,
+    /// Active throttle reasons, labelled by reason.
+    pub throttle_active: GaugeVec,
+    /// Profiles inside one of their pause windows, labelled by profile.
+    pub profile_paused: GaugeVec,
 }
 
 impl Metrics {
@@ -53,6 +70,15 @@ impl Metrics {
         )
         .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
 
+        let encode_failures_total = CounterVec::new(
+            Opts::new(
+                "encode_failures_total",
+                "Failed encode attempts by failure category",
+            ),
+            &["category", "outcome"],
+        )
+        .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+
         let encode_duration_seconds = Histogram::with_opts(
             HistogramOpts::new(
                 "encode_duration_seconds",
@@ -72,8 +98,9 @@ impl Metrics {
         .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
 
         let vmaf_score = Histogram::with_opts(
-            HistogramOpts::new("encode_vmaf_score", "VMAF scores of encoded videos")
-                .buckets(vec![80.0, 85.0, 90.0, 92.0, 94.0, 95.0, 96.0, 97.0, 98.0, 99.0]),
+            HistogramOpts::new("encode_vmaf_score", "VMAF scores of encoded videos").buckets(vec![
+                80.0, 85.0, 90.0, 92.0, 94.0, 95.0, 96.0, 97.0, 98.0, 99.0,
+            ]),
         )
         .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
 
@@ -83,6 +110,66 @@ impl Metrics {
         )
         .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
 
+        let job_progress = GaugeVec::new(
+            Opts::new(
+                "encode_job_progress_percent",
+                "Progress of each running job",
+            ),
+            &["job_id", "profile"],
+        )
+        .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+
+        let job_workers = GaugeVec::new(
+            Opts::new(
+                "encode_job_workers",
+                "av1an workers reserved by each running job",
+            ),
+            &["job_id", "profile"],
+        )
+        .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+
+        let redis_up = Gauge::new(
+            "encode_redis_up",
+            "Whether Redis is reachable (1) or writes are spooled (0)",
+        )
+        .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+
+        let spool_entries = Gauge::new(
+            "encode_spool_entries",
+            "Number of queue writes waiting in the on-disk spool",
+        )
+        .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+
+        let throttle_paused = Gauge::new(
+            "encode_throttle_paused",
+            "Whether starting new jobs is paused",
+        )
+        .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+
+        let throttle_workers_limit = Gauge::new(
+            "encode_throttle_workers_limit",
+            "av1an workers cap for new jobs while throttled (0 when not reduced)",
+        )
+        .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+
+        let throttle_active = GaugeVec::new(
+            Opts::new(
+                "encode_throttle_active",
+                "Active reasons for holding back new jobs",
+            ),
+            &["reason"],
+        )
+        .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+
+        let profile_paused = GaugeVec::new(
+            Opts::new(
+                "encode_profile_paused",
+                "Profiles inside one of their pause windows",
+            ),
+            &["profile"],
+        )
+        .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+
         // Register all metrics
         registry
             .register(Box::new(queue_depth.clone()))
@@ -93,6 +180,9 @@ impl Metrics {
         registry
             .register(Box::new(encodes_total.clone()))
             .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+        registry
+            .register(Box::new(encode_failures_total.clone()))
+            .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
         registry
             .register(Box::new(encode_duration_seconds.clone()))
             .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
@@ -105,38 +195,92 @@ impl Metrics {
         registry
             .register(Box::new(jobs_in_progress.clone()))
             .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+        registry
+            .register(Box::new(job_progress.clone()))
+            .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+        registry
+            .register(Box::new(job_workers.clone()))
+            .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+        registry
+            .register(Box::new(redis_up.clone()))
+            .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+        registry
+            .register(Box::new(spool_entries.clone()))
+            .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+        registry
+            .register(Box::new(throttle_paused.clone()))
+            .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+        registry
+            .register(Box::new(throttle_workers_limit.clone()))
+            .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+        registry
+            .register(Box::new(throttle_active.clone()))
+            .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
+        registry
+            .register(Box::new(profile_paused.clone()))
+            .map_err(|e| NotificationError::PrometheusFailed(e.to_string()))?;
 
         Ok(Self {
             registry,
             queue_depth,
             dead_letter_count,
             encodes_total,
+            encode_failures_total,
             encode_duration_seconds,
             size_reduction_ratio,
             vmaf_score,
             jobs_in_progress,
+            job_progress,
+            job_workers,
+            redis_up,
+            spool_entries,
+            throttle_paused,
+            throttle_workers_limit,
+            throttle_active,
+            profile_paused,
         })
     }
 
     /// Records a successful encode.
     pub fn record_success(&self, metadata: &EncodeResultMetadata) {
         self.encodes_total.with_label_values(&["success"]).inc();
-        self.encode_duration_seconds.observe(metadata.encode_duration_secs);
-        self.size_reduction_ratio.observe(metadata.compression_ratio());
+        self.encode_duration_seconds
+            .observe(metadata.encode_duration_secs);
+        self.size_reduction_ratio
+            .observe(metadata.compression_ratio());
 
         if let Some(vmaf) = metadata.vmaf_score {
             self.vmaf_score.observe(vmaf as f64);
         }
     }
 
-    /// Records a failed encode.
-    pub fn record_failure(&self) {
+    /// Records a job that a skip rule spared from encoding.
+    ///
+    /// Remuxed and cleaned-up sources get their own status, so they are not
+    /// counted as encodes.
+    pub fn record_skip(&self, action: SkipAction) {
+        let status = match action {
+            SkipAction::Skip => "skipped",
+            SkipAction::RemuxOnly => "remuxed",
+            SkipAction::CleanupOnly => "cleaned_up",
+        };
+        self.encodes_total.with_label_values(&[status]).inc();
+    }
+
+    /// Records a failed encode that will be retried.
+    pub fn record_failure(&self, category: ErrorCategory) {
         self.encodes_total.with_label_values(&["failure"]).inc();
+        self.encode_failures_total
+            .with_label_values(&[&category.to_string(), "retrying"])
+            .inc();
     }
 
     /// Records a dead letter event.
-    pub fn record_dead_letter(&self) {
+    pub fn record_dead_letter(&self, category: ErrorCategory) {
         self.encodes_total.with_label_values(&["dead_letter"]).inc();
+        self.encode_failures_total
+            .with_label_values(&[&category.to_string(), "dead_lettered"])
+            .inc();
     }
 
     /// Updates queue depth gauge.
@@ -154,6 +298,51 @@ impl Metrics {
         self.jobs_in_progress.set(count as f64);
     }
 
+    /// Adds the per-job series for a job that started running.
+    pub fn start_job(&self, job_id: &str, profile: &str, workers: usize) {
+        self.job_progress
+            .with_label_values(&[job_id, profile])
+            .set(0.0);
+        self.job_workers
+            .with_label_values(&[job_id, profile])
+            .set(workers as f64);
+    }
+
+    /// Updates a running job's progress.
+    pub fn set_job_progress(&self, job_id: &str, profile: &str, percent: f32) {
+        self.job_progress
+            .with_label_values(&[job_id, profile])
+            .set(percent as f64);
+    }
+
+    /// Removes the per-job series of a job that stopped running.
+    pub fn finish_job(&self, job_id: &str, profile: &str) {
+        let _ = self.job_progress.remove_label_values(&[job_id, profile]);
+        let _ = self.job_workers.remove_label_values(&[job_id, profile]);
+    }
+
+    /// Updates the Redis health and spool gauges.
+    pub fn set_redis_health(&self, up: bool, spooled: usize) {
+        self.redis_up.set(if up { 1.0 } else { 0.0 });
+        self.spool_entries.set(spooled as f64);
+    }
+
+    /// Updates the throttle gauges.
+    pub fn set_throttle(&self, status: &ThrottleStatus) {
+        self.throttle_paused
+            .set(if status.paused { 1.0 } else { 0.0 });
+        self.throttle_workers_limit
+            .set(status.reduced_workers.unwrap_or(0) as f64);
+        self.throttle_active.reset();
+        for reason in &status.reasons {
+            self.throttle_active.with_label_values(&[reason]).set(1.0);
+        }
+        self.profile_paused.reset();
+        for profile in &status.paused_profiles {
+            self.profile_paused.with_label_values(&[profile]).set(1.0);
+        }
+    }
+
     /// Returns the metrics in Prometheus text format.
     pub fn gather(&self) -> String {
         use prometheus::Encoder;
@@ -181,12 +370,12 @@ impl MetricsServer {
 
     /// Starts the metrics HTTP server.
     pub async fn start(self) -> Result<(), NotificationError> {
+        use http_body_util::Full;
+        use hyper::body::Bytes;
         use hyper::server::conn::http1;
         use hyper::service::service_fn;
         use hyper::{body::Incoming, Request, Response};
         use hyper_util::rt::TokioIo;
-        use http_body_util::Full;
-        use hyper::body::Bytes;
 
         let addr: SocketAddr = ([0, 0, 0, 0], self.port).into();
         let listener = tokio::net::TcpListener::bind(addr)
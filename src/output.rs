@@ -0,0 +1,256 @@
+//! Machine-readable output for CLI subcommands.
+//!
+//! With `--output json` or `--output yaml`, each subcommand prints exactly one
+//! document to stdout using the structures below; logs go to stderr. Exit
+//! codes are listed on [`ExitStatus`].
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::cli::OutputFormat;
+use crate::encoder::throttle::ThrottleStatus;
+use crate::error::ErrorCategory;
+use crate::media::skip::SkipDecision;
+use crate::queue::history::ProfileStats;
+use crate::queue::job::{EncodeJob, EncodeResultMetadata, JobStatus};
+use crate::queue::nodes::NodeMode;
+use crate::queue::redis::KeyMove;
+use crate::validation::ValidationIssue;
+
+/// Process exit status of a CLI subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// Completed without errors or warnings (0).
+    Success,
+    /// Failed, or validation found errors (1).
+    Failure,
+    /// Completed, but validation found warnings (3).
+    ///
+    /// Exit code 2 is reserved for command-line usage errors.
+    Warnings,
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        match status {
+            ExitStatus::Success => ExitCode::SUCCESS,
+            ExitStatus::Failure => ExitCode::FAILURE,
+            ExitStatus::Warnings => ExitCode::from(3),
+        }
+    }
+}
+
+/// Prints a value in the requested format.
+///
+/// `text` renders the human-readable form and is only called for text output.
+pub fn emit<T: Serialize>(format: OutputFormat, value: &T, text: impl FnOnce(&T)) -> Result<()> {
+    match format {
+        OutputFormat::Text => text(value),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(value)?),
+    }
+    Ok(())
+}
+
+/// Error document printed when a subcommand fails in json or yaml mode.
+#[derive(Debug, Serialize)]
+pub struct ErrorOutput {
+    /// Error message, including its causes.
+    pub error: String,
+}
+
+/// A job as reported by the CLI.
+#[derive(Debug, Serialize)]
+pub struct JobOutput {
+    /// Job ID.
+    pub id: String,
+    /// Current status.
+    pub status: JobStatus,
+    /// Source file.
+    pub input_path: PathBuf,
+    /// Output file.
+    pub output_path: PathBuf,
+    /// Profile name.
+    pub profile: String,
+    /// Queue priority.
+    pub priority: i32,
+    /// Progress percentage, while encoding or once complete.
+    pub progress: Option<f32>,
+    /// Current encoding phase, while encoding.
+    pub phase: Option<String>,
+    /// Node that last ran the job.
+    pub node: Option<String>,
+    /// Encoding attempts made so far.
+    pub attempts: u32,
+    /// Last error message.
+    pub error: Option<String>,
+    /// Category of the last failure.
+    pub failure_category: Option<ErrorCategory>,
+    /// Skip rule that spared the source a re-encode.
+    pub skip: Option<SkipDecision>,
+    /// Creation time.
+    pub created_at: DateTime<Utc>,
+    /// Last update time.
+    pub updated_at: DateTime<Utc>,
+    /// Encode results, once complete.
+    pub result: Option<EncodeResultMetadata>,
+}
+
+impl From<&EncodeJob> for JobOutput {
+    fn from(job: &EncodeJob) -> Self {
+        Self {
+            id: job.id.clone(),
+            status: job.status,
+            input_path: job.input_path.clone(),
+            output_path: job.output_path.clone(),
+            profile: job.profile_name.clone(),
+            priority: job.priority,
+            progress: job.progress,
+            phase: job.progress_detail.as_ref().map(|d| d.phase.clone()),
+            node: job.node.clone(),
+            attempts: job.attempt_count,
+            error: job.error_message.clone(),
+            failure_category: job.failure_category,
+            skip: job.skip.clone(),
+            created_at: job.created_at,
+            updated_at: job.updated_at,
+            result: job.result_metadata.clone(),
+        }
+    }
+}
+
+/// Output of `queue-list`.
+#[derive(Debug, Serialize)]
+pub struct QueueListOutput {
+    /// Jobs being encoded.
+    pub processing: Vec<JobOutput>,
+    /// Pending jobs in dequeue order.
+    pub pending: Vec<JobOutput>,
+    /// Jobs in the dead letter queue.
+    pub dead_letter: Vec<JobOutput>,
+}
+
+/// Output of `config-validate`.
+#[derive(Debug, Serialize)]
+pub struct ValidationOutput {
+    /// Whether the configuration has no errors.
+    pub valid: bool,
+    /// Number of errors.
+    pub error_count: usize,
+    /// Number of warnings.
+    pub warning_count: usize,
+    /// Errors first, then warnings.
+    pub issues: Vec<ValidationIssue>,
+    /// Configured profile names.
+    pub profiles: Vec<String>,
+}
+
+/// Output of `queue-clear`.
+#[derive(Debug, Serialize)]
+pub struct ClearOutput {
+    /// Number of pending jobs removed.
+    pub cleared: usize,
+}
+
+/// Output of `retry-dead-letter`.
+#[derive(Debug, Serialize)]
+pub struct RetryOutput {
+    /// The requeued job ID.
+    pub job_id: String,
+}
+
+/// Outcome for one file of `enqueue`.
+#[derive(Debug, Serialize)]
+pub struct EnqueueResult {
+    /// File path.
+    pub path: PathBuf,
+    /// `queued`, `duplicate` or `failed`.
+    pub status: &'static str,
+    /// New or existing job ID.
+    pub job_id: Option<String>,
+    /// Profile used, when queued.
+    pub profile: Option<String>,
+    /// Failure reason.
+    pub error: Option<String>,
+}
+
+/// Output of `enqueue`.
+#[derive(Debug, Serialize)]
+pub struct EnqueueOutput {
+    /// Number of files queued.
+    pub queued: usize,
+    /// Number of files already queued.
+    pub skipped: usize,
+    /// Number of files that could not be queued.
+    pub failed: usize,
+    /// Per-file results.
+    pub results: Vec<EnqueueResult>,
+}
+
+/// Output of `history`.
+#[derive(Debug, Serialize)]
+pub struct HistoryOutput {
+    /// Number of jobs matching the filters.
+    pub matched: usize,
+    /// Matching jobs, newest first, up to the limit.
+    pub jobs: Vec<JobOutput>,
+    /// Statistics per profile over all matching jobs.
+    pub stats: Vec<ProfileStats>,
+}
+
+/// Output of `redis-migrate`.
+#[derive(Debug, Serialize)]
+pub struct MigrateOutput {
+    /// Source namespace (none for unprefixed keys).
+    pub from: Option<String>,
+    /// Destination namespace (none for unprefixed keys).
+    pub to: Option<String>,
+    /// Whether nothing was changed.
+    pub dry_run: bool,
+    /// Keys moved, or that would move in a dry run.
+    pub moved: Vec<KeyMove>,
+    /// Keys not moved because the destination already exists.
+    pub conflicts: Vec<KeyMove>,
+}
+
+/// A live node as reported by `nodes`.
+#[derive(Debug, Serialize)]
+pub struct NodeOutput {
+    /// Node ID.
+    pub id: String,
+    /// How the node was started.
+    pub mode: NodeMode,
+    /// Whether the node holds the leader lease and runs the watchers.
+    pub leader: bool,
+    /// Number of CPU cores.
+    pub cores: usize,
+    /// Maximum number of jobs run at once.
+    pub max_jobs: usize,
+    /// Available av1an encoders, sorted.
+    pub av1an_encoders: Vec<String>,
+    /// Number of available FFmpeg encoders.
+    pub ffmpeg_encoders: usize,
+    /// Pipeline version.
+    pub version: String,
+    /// When the node started.
+    pub started_at: DateTime<Utc>,
+    /// When the node last reported in.
+    pub heartbeat_at: DateTime<Utc>,
+    /// Whether the node is holding back new jobs.
+    pub throttle: ThrottleStatus,
+    /// Jobs the node is encoding.
+    pub running: Vec<JobOutput>,
+}
+
+/// Output of `nodes`.
+#[derive(Debug, Serialize)]
+pub struct NodesOutput {
+    /// Live nodes, by ID.
+    pub nodes: Vec<NodeOutput>,
+    /// In-progress jobs whose node is no longer live.
+    pub orphaned: Vec<JobOutput>,
+}
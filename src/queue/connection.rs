@@ -0,0 +1,384 @@
+//! Redis connections built from configuration.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use futures_util::FutureExt;
+use redis::aio::{ConnectionLike, ConnectionManager};
+use redis::{
+    Client, ClientTlsConfig, Cmd, ConnectionAddr, ConnectionInfo, ErrorKind, IntoConnectionInfo,
+    Pipeline, RedisConnectionInfo, RedisError, RedisFuture, TlsCertificates, Value,
+};
+use tracing::{info, warn};
+
+use crate::config::model::{RedisConfig, SentinelConfig};
+use crate::config::redact::{redact_url, REDACTED};
+use crate::error::QueueError;
+
+/// Default Sentinel port.
+const SENTINEL_PORT: u16 = 26379;
+
+/// Connection to Redis that follows Sentinel failovers.
+///
+/// Commands go through a [`ConnectionManager`], which reconnects to the same
+/// address. With Sentinel, a command that fails because the server went away
+/// or became a replica makes the connection ask the sentinels for the master
+/// again, so later commands reach the new one. Commands rejected as READONLY
+/// are sent again after switching; other failed commands are not, as they may
+/// have been applied. Clones share the connection.
+#[derive(Clone)]
+pub struct RedisConnection {
+    current: Arc<Mutex<Current>>,
+    /// Settings used to resolve the master again, with Sentinel only.
+    sentinel: Option<Arc<RedisConfig>>,
+    db: i64,
+}
+
+/// Connection in use and how many times it was replaced.
+struct Current {
+    manager: ConnectionManager,
+    generation: u64,
+}
+
+impl RedisConnection {
+    /// Returns the connection in use and its generation.
+    fn current(&self) -> (ConnectionManager, u64) {
+        let current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        (current.manager.clone(), current.generation)
+    }
+
+    /// Returns true if the error means the master should be resolved again.
+    fn needs_failover(&self, error: &RedisError) -> bool {
+        self.sentinel.is_some()
+            && (error.kind() == ErrorKind::ReadOnly
+                || error.is_io_error()
+                || error.is_connection_dropped()
+                || error.is_connection_refusal())
+    }
+
+    /// Resolves the master again and replaces the connection, unless another
+    /// clone already replaced the given generation. Returns true if a current
+    /// connection is in place.
+    async fn fail_over(&self, generation: u64) -> bool {
+        let Some(config) = &self.sentinel else {
+            return false;
+        };
+
+        let manager = match open_manager(config).await {
+            Ok(manager) => manager,
+            Err(e) => {
+                warn!(redis = %describe(config), error = %e, "Failed to resolve the Redis master after an error");
+                return false;
+            }
+        };
+
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        if current.generation == generation {
+            current.manager = manager;
+            current.generation += 1;
+            info!(redis = %describe(config), "Reconnected to the Redis master resolved through Sentinel");
+        }
+        true
+    }
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        async move {
+            let (mut manager, generation) = self.current();
+            let result = manager.send_packed_command(cmd).await;
+
+            match result {
+                Err(e) if self.needs_failover(&e) => {
+                    let read_only = e.kind() == ErrorKind::ReadOnly;
+                    if self.fail_over(generation).await && read_only {
+                        return self.current().0.send_packed_command(cmd).await;
+                    }
+                    Err(e)
+                }
+                result => result,
+            }
+        }
+        .boxed()
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        async move {
+            let (mut manager, generation) = self.current();
+            let result = manager.send_packed_commands(cmd, offset, count).await;
+
+            match result {
+                Err(e) if self.needs_failover(&e) => {
+                    let read_only = e.kind() == ErrorKind::ReadOnly;
+                    if self.fail_over(generation).await && read_only {
+                        return self
+                            .current()
+                            .0
+                            .send_packed_commands(cmd, offset, count)
+                            .await;
+                    }
+                    Err(e)
+                }
+                result => result,
+            }
+        }
+        .boxed()
+    }
+
+    fn get_db(&self) -> i64 {
+        self.db
+    }
+}
+
+/// Connects to the Redis server described by the configuration.
+///
+/// With Sentinel, the master is resolved at connect time and again whenever
+/// it stops answering as a master.
+pub async fn connect(config: &RedisConfig) -> Result<RedisConnection, QueueError> {
+    let manager = open_manager(config)
+        .await
+        .map_err(|message| QueueError::ConnectionFailed {
+            url: describe(config),
+            message,
+        })?;
+
+    Ok(RedisConnection {
+        current: Arc::new(Mutex::new(Current {
+            manager,
+            generation: 0,
+        })),
+        sentinel: config.sentinel.is_some().then(|| Arc::new(config.clone())),
+        db: i64::from(config.db),
+    })
+}
+
+/// Resolves the server and opens a connection manager to it.
+async fn open_manager(config: &RedisConfig) -> Result<ConnectionManager, String> {
+    let info = match &config.sentinel {
+        Some(sentinel) => sentinel_master(config, sentinel).await?,
+        None => direct_info(config).map_err(|e| e.to_string())?,
+    };
+
+    let client = build_client(config, info)?;
+
+    client
+        .get_connection_manager()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Describes the connection target for logs and errors, without passwords.
+pub fn describe(config: &RedisConfig) -> String {
+    if let Some(sentinel) = &config.sentinel {
+        return format!(
+            "sentinel://{}/{}/{}",
+            sentinel.nodes.join(","),
+            sentinel.master_name,
+            config.db
+        );
+    }
+    if let Some(url) = &config.url {
+        return redact_url(url);
+    }
+    if let Some(socket) = &config.socket {
+        return format!("redis+unix://{}?db={}", socket.display(), config.db);
+    }
+
+    let scheme = if config.tls.enabled {
+        "rediss"
+    } else {
+        "redis"
+    };
+    let credentials = match (&config.username, &config.password) {
+        (Some(user), Some(_)) => format!("{}:{}@", user, REDACTED),
+        (Some(user), None) => format!("{}@", user),
+        (None, Some(_)) => format!(":{}@", REDACTED),
+        (None, None) => String::new(),
+    };
+    format!(
+        "{}://{}{}:{}/{}",
+        scheme, credentials, config.host, config.port, config.db
+    )
+}
+
+/// Connection details for a directly addressed server.
+fn direct_info(config: &RedisConfig) -> redis::RedisResult<ConnectionInfo> {
+    let mut info = match (&config.url, &config.socket) {
+        (Some(url), _) => url.as_str().into_connection_info()?,
+        (None, Some(socket)) => ConnectionInfo {
+            addr: ConnectionAddr::Unix(socket.clone()),
+            redis: RedisConnectionInfo::default(),
+        },
+        (None, None) => ConnectionInfo {
+            addr: tcp_addr(config, config.host.clone(), config.port),
+            redis: RedisConnectionInfo::default(),
+        },
+    };
+
+    if config.url.is_none() {
+        info.redis.db = i64::from(config.db);
+    }
+    apply_credentials(config, &mut info.redis);
+
+    Ok(info)
+}
+
+/// Asks the sentinels for the current master.
+///
+/// Sentinels and the master are reached with the same TLS certificates as a
+/// direct connection, so a private CA or client certificate applies to all.
+async fn sentinel_master(
+    config: &RedisConfig,
+    sentinel: &SentinelConfig,
+) -> Result<ConnectionInfo, String> {
+    let mut master = RedisConnectionInfo {
+        db: i64::from(config.db),
+        ..RedisConnectionInfo::default()
+    };
+    apply_credentials(config, &mut master);
+
+    let mut last_error = "No sentinel nodes configured".to_string();
+
+    for node in &sentinel.nodes {
+        let (host, port) = match node.rsplit_once(':') {
+            Some((host, port)) => (host.to_string(), port.parse().unwrap_or(SENTINEL_PORT)),
+            None => (node.clone(), SENTINEL_PORT),
+        };
+        let node_info = ConnectionInfo {
+            addr: tcp_addr(config, host, port),
+            redis: RedisConnectionInfo {
+                password: sentinel.password.clone(),
+                ..RedisConnectionInfo::default()
+            },
+        };
+
+        let (host, port) = match master_address(config, node_info, &sentinel.master_name).await {
+            Ok(address) => address,
+            Err(e) => {
+                last_error = format!("Sentinel {}: {}", node, e);
+                continue;
+            }
+        };
+
+        let info = ConnectionInfo {
+            addr: tcp_addr(config, host.clone(), port),
+            redis: master.clone(),
+        };
+        match role(config, info.clone()).await {
+            Ok(role) if role == "master" => return Ok(info),
+            Ok(role) => {
+                last_error = format!(
+                    "Sentinel {} reported {}:{}, which is a {}",
+                    node, host, port, role
+                )
+            }
+            Err(e) => last_error = format!("Master {}:{}: {}", host, port, e),
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Asks one sentinel for the address of the named master.
+async fn master_address(
+    config: &RedisConfig,
+    sentinel: ConnectionInfo,
+    name: &str,
+) -> Result<(String, u16), String> {
+    let client = build_client(config, sentinel)?;
+    let mut connection = client
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let address: Option<(String, u16)> = redis::cmd("SENTINEL")
+        .arg("get-master-addr-by-name")
+        .arg(name)
+        .query_async(&mut connection)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    address.ok_or_else(|| format!("Unknown master '{}'", name))
+}
+
+/// Returns the replication role (`master` or `slave`) of a server.
+async fn role(config: &RedisConfig, info: ConnectionInfo) -> Result<String, String> {
+    let client = build_client(config, info)?;
+    let mut connection = client
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let reply: Vec<Value> = redis::cmd("ROLE")
+        .query_async(&mut connection)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match reply.first() {
+        Some(role) => redis::from_redis_value(role).map_err(|e| e.to_string()),
+        None => Err("Empty ROLE reply".to_string()),
+    }
+}
+
+/// Plain or TLS address of a TCP server.
+fn tcp_addr(config: &RedisConfig, host: String, port: u16) -> ConnectionAddr {
+    if config.tls.enabled {
+        ConnectionAddr::TcpTls {
+            host,
+            port,
+            insecure: config.tls.insecure,
+            tls_params: None,
+        }
+    } else {
+        ConnectionAddr::Tcp(host, port)
+    }
+}
+
+/// Fills in the configured username and password where not already set.
+fn apply_credentials(config: &RedisConfig, redis: &mut RedisConnectionInfo) {
+    if redis.username.is_none() {
+        redis.username = config.username.clone();
+    }
+    if redis.password.is_none() {
+        redis.password = config.password.clone();
+    }
+}
+
+/// Creates the client, loading custom TLS certificates if configured.
+fn build_client(config: &RedisConfig, info: ConnectionInfo) -> Result<Client, String> {
+    let tls = &config.tls;
+    let has_certs = tls.ca_cert.is_some() || tls.client_cert.is_some();
+    let is_tls = matches!(info.addr, ConnectionAddr::TcpTls { .. });
+
+    if !(has_certs && is_tls) {
+        return Client::open(info).map_err(|e| e.to_string());
+    }
+
+    let client_tls = match (&tls.client_cert, &tls.client_key) {
+        (Some(cert), Some(key)) => Some(ClientTlsConfig {
+            client_cert: read_pem(cert)?,
+            client_key: read_pem(key)?,
+        }),
+        (None, None) => None,
+        _ => return Err("TLS client_cert and client_key must be set together".to_string()),
+    };
+    let root_cert = tls.ca_cert.as_deref().map(read_pem).transpose()?;
+
+    Client::build_with_tls(
+        info,
+        TlsCertificates {
+            client_tls,
+            root_cert,
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Failed to read '{}': {}", path.display(), e))
+}
@@ -2,44 +2,54 @@
 
 use super::job::EncodeJob;
 use super::redis::QueueManager;
-use crate::error::QueueError;
+use crate::config::model::RetryConfig;
+use crate::error::{ErrorCategory, QueueError};
 
 /// Handles dead letter queue operations.
 pub struct DeadLetterHandler<'a> {
     queue: &'a mut QueueManager,
-    max_attempts: u32,
+    retry: &'a RetryConfig,
 }
 
 impl<'a> DeadLetterHandler<'a> {
     /// Creates a new dead letter handler.
-    pub fn new(queue: &'a mut QueueManager, max_attempts: u32) -> Self {
-        Self { queue, max_attempts }
+    pub fn new(queue: &'a mut QueueManager, retry: &'a RetryConfig) -> Self {
+        Self { queue, retry }
     }
 
-    /// Handles a failed job, either retrying or moving to dead letter.
+    /// Handles a job marked failed with [`EncodeJob::fail`], either retrying
+    /// or moving to dead letter.
+    ///
+    /// The retry policy of the failure's category decides how many attempts
+    /// the job gets and how long it waits before each retry.
     pub async fn handle_failure(
         &mut self,
-        mut job: EncodeJob,
+        job: &mut EncodeJob,
         error: String,
+        category: ErrorCategory,
     ) -> Result<FailureAction, QueueError> {
-        job.fail(error.clone());
+        let max_attempts = self.retry.max_attempts_for(category);
 
-        if job.attempt_count < self.max_attempts {
-            // Retry the job
-            job.retry();
-            self.queue.retry_job(&job).await?;
+        if job.attempt_count < max_attempts {
+            // Retry the job once its delay has passed
+            job.retry_after(self.retry.delay_for(category, job.attempt_count + 1));
+            self.queue.retry_job(job).await?;
             Ok(FailureAction::Retrying {
                 attempt: job.attempt_count,
-                max_attempts: self.max_attempts,
+                max_attempts,
+                category,
             })
         } else {
             // Move to dead letter queue
             job.dead_letter(format!(
-                "Exhausted {} attempts. Last error: {}",
-                self.max_attempts, error
+                "Exhausted {} attempts for {} errors. Last error: {}",
+                max_attempts, category, error
             ));
-            self.queue.dead_letter(&job).await?;
-            Ok(FailureAction::DeadLettered { reason: error })
+            self.queue.dead_letter(job).await?;
+            Ok(FailureAction::DeadLettered {
+                reason: error,
+                category,
+            })
         }
     }
 }
@@ -48,7 +58,14 @@ impl<'a> DeadLetterHandler<'a> {
 #[derive(Debug)]
 pub enum FailureAction {
     /// Job is being retried.
-    Retrying { attempt: u32, max_attempts: u32 },
+    Retrying {
+        attempt: u32,
+        max_attempts: u32,
+        category: ErrorCategory,
+    },
     /// Job was moved to dead letter queue.
-    DeadLettered { reason: String },
+    DeadLettered {
+        reason: String,
+        category: ErrorCategory,
+    },
 }
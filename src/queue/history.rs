@@ -0,0 +1,236 @@
+//! Search and statistics over finished jobs.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::job::{EncodeJob, JobStatus};
+
+/// Criteria for selecting archived jobs.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    /// Only jobs of this profile.
+    pub profile: Option<String>,
+    /// Only jobs with this status.
+    pub status: Option<JobStatus>,
+    /// Only jobs finished at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only jobs finished before this time.
+    pub until: Option<DateTime<Utc>>,
+    /// Only jobs whose input path contains this text (case-insensitive).
+    pub path: Option<String>,
+}
+
+impl HistoryFilter {
+    /// Returns true if the job satisfies every criterion.
+    pub fn matches(&self, job: &EncodeJob) -> bool {
+        let finished = job.finished_at();
+
+        self.profile.as_ref().is_none_or(|p| &job.profile_name == p)
+            && self.status.is_none_or(|s| job.status == s)
+            && self.since.is_none_or(|t| finished >= t)
+            && self.until.is_none_or(|t| finished < t)
+            && self.path.as_ref().is_none_or(|p| {
+                job.input_path
+                    .to_string_lossy()
+                    .to_lowercase()
+                    .contains(&p.to_lowercase())
+            })
+    }
+}
+
+/// Aggregate results of the completed encodes of one profile.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileStats {
+    /// Profile name.
+    pub profile: String,
+    /// Number of completed encodes with results.
+    pub encodes: usize,
+    /// Total size of the sources in bytes.
+    pub input_bytes: u64,
+    /// Total size of the outputs in bytes.
+    pub output_bytes: u64,
+    /// Bytes saved (negative if outputs grew).
+    pub bytes_saved: i64,
+    /// Mean compression ratio (input size / output size).
+    pub average_compression: f64,
+    /// Mean VMAF score over encodes that measured it.
+    pub average_vmaf: Option<f64>,
+    /// Mean encoding speed relative to realtime.
+    pub average_speed: f64,
+}
+
+/// Computes per-profile statistics over completed jobs.
+///
+/// Jobs without results (failed or cancelled) are ignored, as are sources a
+/// skip rule only remuxed or cleaned up, since they were not encoded.
+pub fn profile_stats(jobs: &[EncodeJob]) -> Vec<ProfileStats> {
+    let mut by_profile: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+    for job in jobs {
+        if let (JobStatus::Completed, Some(result), None) =
+            (job.status, &job.result_metadata, &job.skip)
+        {
+            by_profile
+                .entry(&job.profile_name)
+                .or_default()
+                .push(result);
+        }
+    }
+
+    by_profile
+        .into_iter()
+        .map(|(profile, results)| {
+            let count = results.len() as f64;
+            let input_bytes: u64 = results.iter().map(|r| r.input_size).sum();
+            let output_bytes: u64 = results.iter().map(|r| r.output_size).sum();
+            let vmaf: Vec<f64> = results
+                .iter()
+                .filter_map(|r| r.vmaf_score)
+                .map(f64::from)
+                .collect();
+
+            ProfileStats {
+                profile: profile.to_string(),
+                encodes: results.len(),
+                input_bytes,
+                output_bytes,
+                bytes_saved: input_bytes as i64 - output_bytes as i64,
+                average_compression: results.iter().map(|r| r.compression_ratio()).sum::<f64>()
+                    / count,
+                average_vmaf: (!vmaf.is_empty())
+                    .then(|| vmaf.iter().sum::<f64>() / vmaf.len() as f64),
+                average_speed: results.iter().map(|r| r.encoding_speed).sum::<f64>() / count,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::queue::job::EncodeResultMetadata;
+
+    fn job(profile: &str, input: &str) -> EncodeJob {
+        EncodeJob::new(
+            PathBuf::from(input),
+            PathBuf::from("/out/movie.mkv"),
+            profile.to_string(),
+        )
+    }
+
+    fn completed(
+        profile: &str,
+        input_size: u64,
+        output_size: u64,
+        vmaf: Option<f32>,
+        speed: f64,
+    ) -> EncodeJob {
+        let result: EncodeResultMetadata = serde_json::from_value(serde_json::json!({
+            "input_size": input_size,
+            "output_size": output_size,
+            "encode_duration_secs": 60.0,
+            "vmaf_score": vmaf,
+            "video_duration_secs": 60.0 * speed,
+            "encoding_speed": speed,
+        }))
+        .unwrap();
+        let mut job = job(profile, "/in/movie.mkv");
+        job.complete(result);
+        job
+    }
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn filter_matches_profile_status_and_path() {
+        let mut job = job("movies", "/in/Movies/Blade Runner (1982).mkv");
+        job.status = JobStatus::Completed;
+
+        let filter = |f: HistoryFilter| f.matches(&job);
+
+        assert!(filter(HistoryFilter::default()));
+        assert!(filter(HistoryFilter {
+            profile: Some("movies".to_string()),
+            status: Some(JobStatus::Completed),
+            path: Some("blade runner".to_string()),
+            ..Default::default()
+        }));
+        assert!(!filter(HistoryFilter {
+            profile: Some("tv".to_string()),
+            ..Default::default()
+        }));
+        assert!(!filter(HistoryFilter {
+            status: Some(JobStatus::Failed),
+            ..Default::default()
+        }));
+        assert!(!filter(HistoryFilter {
+            path: Some("alien".to_string()),
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn filter_date_range_includes_since_and_excludes_until() {
+        let mut job = job("movies", "/in/movie.mkv");
+        job.completed_at = Some(at(10));
+
+        let range = |since: u32, until: u32| {
+            HistoryFilter {
+                since: Some(at(since)),
+                until: Some(at(until)),
+                ..Default::default()
+            }
+            .matches(&job)
+        };
+
+        assert!(range(10, 11));
+        assert!(range(1, 20));
+        assert!(!range(1, 10));
+        assert!(!range(11, 20));
+    }
+
+    #[test]
+    fn profile_stats_aggregate_completed_encodes_per_profile() {
+        let mut failed = job("movies", "/in/failed.mkv");
+        failed.status = JobStatus::Failed;
+        let jobs = vec![
+            completed("movies", 4000, 1000, Some(94.0), 2.0),
+            completed("movies", 3000, 1000, None, 1.0),
+            completed("tv", 1000, 1500, Some(96.0), 3.0),
+            failed,
+        ];
+
+        let stats = profile_stats(&jobs);
+
+        assert_eq!(stats.len(), 2);
+        let movies = &stats[0];
+        assert_eq!(movies.profile, "movies");
+        assert_eq!(movies.encodes, 2);
+        assert_eq!(
+            (movies.input_bytes, movies.output_bytes, movies.bytes_saved),
+            (7000, 2000, 5000)
+        );
+        assert!((movies.average_compression - 3.5).abs() < 1e-9);
+        assert_eq!(movies.average_vmaf, Some(94.0));
+        assert!((movies.average_speed - 1.5).abs() < 1e-9);
+
+        let tv = &stats[1];
+        assert_eq!(tv.profile, "tv");
+        assert_eq!(tv.bytes_saved, -500);
+    }
+
+    #[test]
+    fn profile_stats_of_no_completed_jobs_is_empty() {
+        let mut cancelled = job("movies", "/in/movie.mkv");
+        cancelled.cancel();
+
+        assert!(profile_stats(&[cancelled]).is_empty());
+    }
+}
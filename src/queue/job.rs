@@ -5,6 +5,10 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
 
+use crate::config::model::{ChunkMethod, Encoder, Profile, SubtitleTrackConfig};
+use crate::error::ErrorCategory;
+use crate::media::skip::SkipDecision;
+
 /// Represents an encoding job in the queue.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncodeJob {
@@ -20,6 +24,10 @@ pub struct EncodeJob {
     /// Name of the profile to use for encoding.
     pub profile_name: String,
 
+    /// Queue priority; higher values are dequeued first.
+    #[serde(default)]
+    pub priority: i32,
+
     /// Current status of the job.
     pub status: JobStatus,
 
@@ -41,9 +49,33 @@ pub struct EncodeJob {
     /// Error message if the job failed.
     pub error_message: Option<String>,
 
+    /// Category of the last failure.
+    #[serde(default)]
+    pub failure_category: Option<ErrorCategory>,
+
     /// Encoding progress percentage (0-100).
     pub progress: Option<f32>,
 
+    /// Per-job settings applied on top of the profile.
+    #[serde(default)]
+    pub overrides: JobOverrides,
+
+    /// Live details of the current encode (phase, speed, ETA).
+    #[serde(default)]
+    pub progress_detail: Option<ProgressDetail>,
+
+    /// ID of the node that last ran the job.
+    #[serde(default)]
+    pub node: Option<String>,
+
+    /// Skip rule that spared the source a re-encode, if any.
+    #[serde(default)]
+    pub skip: Option<SkipDecision>,
+
+    /// Earliest time a retried job may start again.
+    #[serde(default)]
+    pub retry_at: Option<DateTime<Utc>>,
+
     /// Metadata about the encode result.
     pub result_metadata: Option<EncodeResultMetadata>,
 }
@@ -57,6 +89,7 @@ impl EncodeJob {
             input_path,
             output_path,
             profile_name,
+            priority: 0,
             status: JobStatus::Pending,
             attempt_count: 0,
             created_at: now,
@@ -64,7 +97,13 @@ impl EncodeJob {
             started_at: None,
             completed_at: None,
             error_message: None,
+            failure_category: None,
             progress: None,
+            overrides: JobOverrides::default(),
+            progress_detail: None,
+            node: None,
+            skip: None,
+            retry_at: None,
             result_metadata: None,
         }
     }
@@ -77,20 +116,42 @@ impl EncodeJob {
         self.attempt_count += 1;
     }
 
+    /// Returns the profile the job runs with on an attempt (1 = first attempt):
+    /// the job's overrides, then the attempt's retry step.
+    pub fn profile_for_attempt(&self, profile: &Profile, attempt: u32) -> Profile {
+        let profile = self.overrides.apply(profile);
+        match profile.retry_step(attempt) {
+            Some((_, step)) => step.apply(&profile),
+            None => profile,
+        }
+    }
+
     /// Marks the job as completed successfully.
     pub fn complete(&mut self, metadata: EncodeResultMetadata) {
         self.status = JobStatus::Completed;
         self.completed_at = Some(Utc::now());
         self.updated_at = Utc::now();
         self.progress = Some(100.0);
+        self.progress_detail = None;
         self.result_metadata = Some(metadata);
     }
 
+    /// Marks the job as skipped by a skip rule, without any output.
+    pub fn skip(&mut self, decision: SkipDecision) {
+        self.status = JobStatus::Skipped;
+        self.completed_at = Some(Utc::now());
+        self.updated_at = Utc::now();
+        self.progress = Some(100.0);
+        self.progress_detail = None;
+        self.skip = Some(decision);
+    }
+
     /// Marks the job as failed.
-    pub fn fail(&mut self, error: String) {
+    pub fn fail(&mut self, error: String, category: ErrorCategory) {
         self.status = JobStatus::Failed;
         self.updated_at = Utc::now();
         self.error_message = Some(error);
+        self.failure_category = Some(category);
     }
 
     /// Marks the job for retry.
@@ -99,6 +160,24 @@ impl EncodeJob {
         self.updated_at = Utc::now();
         self.error_message = None;
         self.progress = None;
+        self.progress_detail = None;
+        self.skip = None;
+        self.retry_at = None;
+    }
+
+    /// Marks the job for retry once a delay has passed.
+    pub fn retry_after(&mut self, delay: std::time::Duration) {
+        self.retry();
+        if !delay.is_zero() {
+            self.retry_at = chrono::Duration::from_std(delay)
+                .ok()
+                .map(|delay| self.updated_at + delay);
+        }
+    }
+
+    /// Returns whether the job is waiting out a retry delay.
+    pub fn is_retry_delayed(&self, now: DateTime<Utc>) -> bool {
+        self.retry_at.is_some_and(|at| at > now)
     }
 
     /// Marks the job as moved to dead letter queue.
@@ -108,6 +187,19 @@ impl EncodeJob {
         self.error_message = Some(reason);
     }
 
+    /// Marks the job as cancelled by the user.
+    pub fn cancel(&mut self) {
+        self.status = JobStatus::Cancelled;
+        self.updated_at = Utc::now();
+        self.progress = None;
+        self.progress_detail = None;
+    }
+
+    /// When the job finished, for archived jobs.
+    pub fn finished_at(&self) -> DateTime<Utc> {
+        self.completed_at.unwrap_or(self.updated_at)
+    }
+
     /// Updates the progress of the job.
     pub fn update_progress(&mut self, progress: f32) {
         self.progress = Some(progress.clamp(0.0, 100.0));
@@ -115,6 +207,82 @@ impl EncodeJob {
     }
 }
 
+/// Per-job settings that take precedence over the job's profile.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobOverrides {
+    /// VMAF target to use instead of the profile's.
+    #[serde(default)]
+    pub vmaf_target: Option<f32>,
+
+    /// av1an encoder parameters to use instead of the profile's.
+    #[serde(default)]
+    pub encoder_params: Option<String>,
+
+    /// Output file path to use instead of the one derived from the profile.
+    #[serde(default)]
+    pub output_path: Option<PathBuf>,
+
+    /// Audio language priority to use instead of the profile's.
+    #[serde(default)]
+    pub audio_languages: Option<Vec<String>>,
+
+    /// Subtitle languages to keep, replacing the profile's track list.
+    ///
+    /// Languages already configured on the profile keep their settings;
+    /// others include forced and full tracks.
+    #[serde(default)]
+    pub subtitle_languages: Option<Vec<String>>,
+}
+
+impl JobOverrides {
+    /// Returns a copy of the profile with the overrides applied.
+    pub fn apply(&self, profile: &Profile) -> Profile {
+        let mut profile = profile.clone();
+        if let Some(vmaf_target) = self.vmaf_target {
+            profile.vmaf_target = vmaf_target;
+        }
+        if let Some(encoder_params) = &self.encoder_params {
+            profile.encoder_params = encoder_params.clone();
+        }
+        if let Some(languages) = &self.audio_languages {
+            profile.audio.language_priority = languages.clone();
+        }
+        if let Some(languages) = &self.subtitle_languages {
+            let configured = std::mem::take(&mut profile.subtitles.tracks);
+            profile.subtitles.tracks = languages
+                .iter()
+                .map(|language| {
+                    configured
+                        .iter()
+                        .find(|t| &t.language == language)
+                        .cloned()
+                        .unwrap_or_else(|| SubtitleTrackConfig {
+                            language: language.clone(),
+                            include_forced: true,
+                            include_full: true,
+                            include_sdh: false,
+                            burn_in: false,
+                        })
+                })
+                .collect();
+        }
+        profile
+    }
+}
+
+/// Live progress details for an in-progress job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressDetail {
+    /// Current encoding phase (e.g., "encoding_video").
+    pub phase: String,
+
+    /// Encoding speed reported by the encoder, if known.
+    pub speed: Option<String>,
+
+    /// Estimated time remaining reported by the encoder, if known.
+    pub eta: Option<String>,
+}
+
 /// Status of an encoding job.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -125,10 +293,14 @@ pub enum JobStatus {
     InProgress,
     /// Job completed successfully.
     Completed,
+    /// Job matched a skip rule and was not encoded.
+    Skipped,
     /// Job failed (may be retried).
     Failed,
     /// Job moved to dead letter queue after exhausting retries.
     DeadLetter,
+    /// Job was cancelled by the user.
+    Cancelled,
 }
 
 /// Metadata about a completed encode.
@@ -151,6 +323,75 @@ pub struct EncodeResultMetadata {
 
     /// Encoding speed (e.g., 2.5x means 2.5 seconds of video per second of encoding).
     pub encoding_speed: f64,
+
+    /// Video settings that produced the output.
+    #[serde(default)]
+    pub settings: Option<EncodeSettings>,
+
+    /// What the profile's size rules decided, if it has any.
+    #[serde(default)]
+    pub size_decision: Option<SizeDecision>,
+}
+
+/// Outcome of checking an encode against the profile's size rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum SizeDecision {
+    /// The encode met the rules and was kept.
+    Accepted,
+    /// The source was remuxed unchanged to the output path.
+    KeptSource {
+        /// Size in bytes of the rejected encode.
+        encoded_size: u64,
+    },
+    /// The source video was muxed with the processed audio and subtitles.
+    RemuxedSource {
+        /// Size in bytes of the rejected encode.
+        encoded_size: u64,
+    },
+    /// The video was re-encoded with a lower VMAF target.
+    Retargeted {
+        /// Size in bytes of the first, rejected encode.
+        encoded_size: u64,
+        /// VMAF target of the kept encode.
+        vmaf_target: f32,
+    },
+}
+
+/// Video encode settings used for an attempt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncodeSettings {
+    /// Video encoder.
+    pub encoder: Encoder,
+
+    /// Encoder parameters.
+    pub encoder_params: String,
+
+    /// av1an chunking method.
+    pub chunk_method: ChunkMethod,
+
+    /// Number of av1an workers.
+    pub workers: usize,
+
+    /// VMAF target.
+    pub vmaf_target: f32,
+
+    /// Index of the profile's retry step applied, if any.
+    pub retry_step: Option<usize>,
+}
+
+impl EncodeSettings {
+    /// Captures the video settings of the profile an attempt ran with.
+    pub fn from_profile(profile: &Profile, retry_step: Option<usize>) -> Self {
+        Self {
+            encoder: profile.encoder.clone(),
+            encoder_params: profile.encoder_params.clone(),
+            chunk_method: profile.chunk_method,
+            workers: profile.workers,
+            vmaf_target: profile.vmaf_target,
+            retry_step,
+        }
+    }
 }
 
 impl EncodeResultMetadata {
@@ -172,3 +413,57 @@ impl EncodeResultMetadata {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> Profile {
+        serde_yaml::from_str(
+            "name: test\ninput_path: /in\noutput_path: /out\nencoder: svt-av1\nencoder_params: --preset 6\nvmaf_target: 93\naudio: {rules: [], language_priority: [eng]}\nsubtitles: {tracks: [{language: eng, include_full: false}]}\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn apply_without_overrides_keeps_the_profile() {
+        let applied = JobOverrides::default().apply(&profile());
+
+        assert_eq!(applied.vmaf_target, 93.0);
+        assert_eq!(applied.encoder_params, "--preset 6");
+        assert_eq!(applied.audio.language_priority, vec!["eng"]);
+        assert_eq!(applied.subtitles.tracks.len(), 1);
+    }
+
+    #[test]
+    fn apply_replaces_video_and_audio_settings() {
+        let overrides = JobOverrides {
+            vmaf_target: Some(95.0),
+            encoder_params: Some("--preset 4".to_string()),
+            audio_languages: Some(vec!["jpn".to_string(), "eng".to_string()]),
+            ..Default::default()
+        };
+
+        let applied = overrides.apply(&profile());
+
+        assert_eq!(applied.vmaf_target, 95.0);
+        assert_eq!(applied.encoder_params, "--preset 4");
+        assert_eq!(applied.audio.language_priority, vec!["jpn", "eng"]);
+    }
+
+    #[test]
+    fn apply_subtitle_languages_keeps_configured_track_settings() {
+        let overrides = JobOverrides {
+            subtitle_languages: Some(vec!["ger".to_string(), "eng".to_string()]),
+            ..Default::default()
+        };
+
+        let tracks = overrides.apply(&profile()).subtitles.tracks;
+
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].language, "ger");
+        assert!(tracks[0].include_forced && tracks[0].include_full);
+        assert_eq!(tracks[1].language, "eng");
+        assert!(!tracks[1].include_full);
+    }
+}
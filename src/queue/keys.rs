@@ -0,0 +1,149 @@
+//! Redis key names, optionally scoped to a namespace.
+
+use crate::config::model::RedisConfig;
+
+/// Prefix of per-job keys, followed by the job ID.
+const JOB_PREFIX: &str = "encode:job:";
+
+/// Prefix of per-node heartbeat keys, followed by the node ID.
+const NODE_PREFIX: &str = "encode:node:";
+
+/// Keys that exist once per deployment.
+const FIXED_KEYS: [&str; 11] = [
+    "encode:queue",
+    "encode:processing",
+    "encode:dead_letter",
+    "encode:cancel_requested",
+    "encode:history",
+    "encode:watcher:tracking",
+    "encode:nodes",
+    "encode:leader",
+    "config:current",
+    "config:hash",
+    "config:last_validated",
+];
+
+/// Returns true if a namespace is non-empty and only uses `A-Z a-z 0-9 _ . -`.
+///
+/// Namespaces are built into SCAN `MATCH` patterns, so glob characters such
+/// as `*` or `[` would let one namespace match the keys of another.
+pub fn is_valid_namespace(namespace: &str) -> bool {
+    !namespace.is_empty()
+        && namespace
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Names of every Redis key used by one pipeline deployment.
+///
+/// With a namespace, each key is prefixed with `<namespace>:` so several
+/// deployments can share a Redis database.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeySpace {
+    prefix: String,
+}
+
+impl KeySpace {
+    /// Creates a key space; `None` uses the unprefixed keys.
+    pub fn new(namespace: Option<&str>) -> Self {
+        Self {
+            prefix: namespace.map(|ns| format!("{}:", ns)).unwrap_or_default(),
+        }
+    }
+
+    /// Creates the key space configured for a Redis connection.
+    pub fn from_config(config: &RedisConfig) -> Self {
+        Self::new(config.namespace.as_deref())
+    }
+
+    /// Returns the namespace, if any.
+    pub fn namespace(&self) -> Option<&str> {
+        self.prefix.strip_suffix(':')
+    }
+
+    fn key(&self, name: &str) -> String {
+        format!("{}{}", self.prefix, name)
+    }
+
+    /// Pending job IDs in dequeue order.
+    pub fn queue(&self) -> String {
+        self.key("encode:queue")
+    }
+
+    /// IDs of jobs being encoded.
+    pub fn processing(&self) -> String {
+        self.key("encode:processing")
+    }
+
+    /// IDs of dead-lettered jobs.
+    pub fn dead_letter(&self) -> String {
+        self.key("encode:dead_letter")
+    }
+
+    /// IDs of in-progress jobs with a pending cancellation.
+    pub fn cancel_requested(&self) -> String {
+        self.key("encode:cancel_requested")
+    }
+
+    /// Finished job IDs scored by finish time.
+    pub fn history(&self) -> String {
+        self.key("encode:history")
+    }
+
+    /// Latest watcher tracking snapshot.
+    pub fn watcher_state(&self) -> String {
+        self.key("encode:watcher:tracking")
+    }
+
+    /// Registered node IDs scored by last heartbeat.
+    pub fn nodes(&self) -> String {
+        self.key("encode:nodes")
+    }
+
+    /// Heartbeat of a single node; expires when the node stops.
+    pub fn node(&self, node_id: &str) -> String {
+        format!("{}{}{}", self.prefix, NODE_PREFIX, node_id)
+    }
+
+    /// ID of the node holding the leader lease.
+    pub fn leader(&self) -> String {
+        self.key("encode:leader")
+    }
+
+    /// Data of a single job.
+    pub fn job(&self, job_id: &str) -> String {
+        format!("{}{}{}", self.prefix, JOB_PREFIX, job_id)
+    }
+
+    /// Pattern matching every job data key.
+    pub fn job_pattern(&self) -> String {
+        format!("{}{}*", self.prefix, JOB_PREFIX)
+    }
+
+    /// Cached configuration.
+    pub fn config(&self) -> String {
+        self.key("config:current")
+    }
+
+    /// Hash of the cached configuration.
+    pub fn config_hash(&self) -> String {
+        self.key("config:hash")
+    }
+
+    /// When the cached configuration was last validated.
+    pub fn config_timestamp(&self) -> String {
+        self.key("config:last_validated")
+    }
+
+    /// Every key that exists once per deployment (excludes job and node keys).
+    pub fn fixed_keys(&self) -> Vec<String> {
+        FIXED_KEYS.iter().map(|k| self.key(k)).collect()
+    }
+
+    /// Maps a key of this key space to the same key in another.
+    ///
+    /// Returns `None` if the key does not belong to this key space.
+    pub fn translate(&self, key: &str, to: &KeySpace) -> Option<String> {
+        key.strip_prefix(&self.prefix).map(|name| to.key(name))
+    }
+}
@@ -0,0 +1,101 @@
+//! Redis lease that elects one pipeline instance to run the shared tasks.
+//!
+//! Folder watching, stability checks and reaping of orphaned jobs must only
+//! run once per queue. Every pipeline instance competes for the lease; the
+//! holder renews it, and another instance takes over once it expires.
+//!
+//! The lease holds a token made of the node ID and a per-process UUID, so two
+//! processes started with the same node ID never both consider themselves
+//! the holder.
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use super::redis::QueueManager;
+
+/// How long the lease lasts without renewal.
+pub const LEASE_TTL: Duration = Duration::from_secs(15);
+
+/// How often the lease is renewed or, by followers, tried.
+const RENEW_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Separates the node ID from the per-process part of a lease token.
+const TOKEN_SEPARATOR: char = '/';
+
+/// Returns the node ID of the process holding a lease token.
+pub fn lease_holder(token: &str) -> &str {
+    token
+        .rsplit_once(TOKEN_SEPARATOR)
+        .map_or(token, |(node_id, _)| node_id)
+}
+
+/// Competes for the leader lease on behalf of one node.
+pub struct LeaderElection {
+    queue: QueueManager,
+    node_id: String,
+    token: String,
+}
+
+impl LeaderElection {
+    /// Creates an election for the given node, with a token unique to this process.
+    pub fn new(queue: QueueManager, node_id: String) -> Self {
+        let token = format!(
+            "{}{}{}",
+            node_id,
+            TOKEN_SEPARATOR,
+            uuid::Uuid::new_v4().simple()
+        );
+        Self {
+            queue,
+            node_id,
+            token,
+        }
+    }
+
+    /// Returns the lease token, used to release the lease on shutdown.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Runs the election in the background.
+    ///
+    /// The receiver holds `true` while this node is the leader.
+    pub fn spawn(mut self) -> watch::Receiver<bool> {
+        let (tx, rx) = watch::channel(false);
+
+        tokio::spawn(async move {
+            let mut renewed: Option<Instant> = None;
+
+            loop {
+                let leader = match self.queue.acquire_leadership(&self.token, LEASE_TTL).await {
+                    Ok(held) => {
+                        renewed = held.then(Instant::now);
+                        held
+                    }
+                    Err(e) => {
+                        // Without Redis, keep leading only until the lease would have expired
+                        warn!(error = %e, "Failed to renew leader lease");
+                        renewed.is_some_and(|at| at.elapsed() < LEASE_TTL)
+                    }
+                };
+
+                if leader != *tx.borrow() {
+                    if leader {
+                        info!(node_id = %self.node_id, "Acquired leader lease, running watchers and reapers");
+                    } else {
+                        warn!(node_id = %self.node_id, "Lost leader lease, stopping watchers and reapers");
+                    }
+                    if tx.send(leader).is_err() {
+                        break;
+                    }
+                }
+
+                tokio::time::sleep(RENEW_INTERVAL).await;
+            }
+        });
+
+        rx
+    }
+}
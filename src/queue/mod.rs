@@ -1,8 +1,17 @@
 //! Redis queue management for encoding jobs.
 
+pub mod connection;
 pub mod dead_letter;
+pub mod history;
 pub mod job;
+pub mod keys;
+pub mod leader;
+pub mod nodes;
 pub mod redis;
+pub mod spool;
+pub mod submit;
 
 pub use job::{EncodeJob, JobStatus};
+pub use keys::KeySpace;
 pub use redis::QueueManager;
+pub use spool::Spool;
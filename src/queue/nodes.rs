@@ -0,0 +1,78 @@
+//! Registration of the processes that encode jobs from a shared queue.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::encoder::throttle::ThrottleStatus;
+use crate::validation::SystemCapabilities;
+
+/// How a node was started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeMode {
+    /// Full pipeline: watchers, API and encoding.
+    Pipeline,
+    /// Encoding only, using the configuration cached in Redis.
+    Worker,
+}
+
+impl std::fmt::Display for NodeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pipeline => write!(f, "pipeline"),
+            Self::Worker => write!(f, "worker"),
+        }
+    }
+}
+
+/// Heartbeat published by each running node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeInfo {
+    /// Node ID, unique among the nodes sharing a queue.
+    pub id: String,
+    /// How the node was started.
+    pub mode: NodeMode,
+    /// Number of CPU cores.
+    pub cores: usize,
+    /// Maximum number of jobs the node runs at once.
+    pub max_jobs: usize,
+    /// Encoders available on the node.
+    pub capabilities: SystemCapabilities,
+    /// Pipeline version.
+    pub version: String,
+    /// When the node started.
+    pub started_at: DateTime<Utc>,
+    /// When the node last reported in.
+    pub heartbeat_at: DateTime<Utc>,
+    /// Whether the node is holding back new jobs.
+    #[serde(default)]
+    pub throttle: ThrottleStatus,
+}
+
+impl NodeInfo {
+    /// Describes the current process.
+    pub fn new(id: String, mode: NodeMode, capabilities: SystemCapabilities) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            mode,
+            cores: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            max_jobs: 1,
+            capabilities,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: now,
+            heartbeat_at: now,
+            throttle: ThrottleStatus::default(),
+        }
+    }
+}
+
+/// Returns the default node ID: the host name, or a random ID if unknown.
+pub fn default_node_id() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("node-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]))
+}
@@ -1,47 +1,243 @@
 //! Redis queue operations.
 
+use std::collections::HashMap;
+use std::path::Path;
+
 use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
 use redis::AsyncCommands;
+use serde::Serialize;
+use tracing::{error, info, warn};
 
+use super::connection;
 use super::job::{EncodeJob, JobStatus};
+use super::keys::KeySpace;
+use super::leader::lease_holder;
+use super::nodes::NodeInfo;
+use super::spool::{Spool, SpoolOp};
+use crate::config::model::{AppConfig, HistoryConfig, RedisConfig};
 use crate::error::QueueError;
+use crate::watcher::stability::WatcherSnapshot;
+
+/// Number of history entries scanned per batch when looking for recent completions.
+const HISTORY_SCAN_BATCH: isize = 100;
 
-const QUEUE_KEY: &str = "encode:queue";
-const PROCESSING_KEY: &str = "encode:processing";
-const DEAD_LETTER_KEY: &str = "encode:dead_letter";
-const JOB_PREFIX: &str = "encode:job:";
+/// How long a health check waits for Redis to answer.
+const PING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
 
 /// Manages the encoding queue in Redis.
 #[derive(Clone)]
 pub struct QueueManager {
-    connection: redis::aio::ConnectionManager,
+    connection: connection::RedisConnection,
+    keys: KeySpace,
+    history: HistoryConfig,
+    spool: Option<Spool>,
 }
 
 impl QueueManager {
-    /// Creates a new QueueManager connected to the specified Redis URL.
-    pub async fn new(redis_url: &str) -> Result<Self, QueueError> {
-        let client = redis::Client::open(redis_url).map_err(|e| QueueError::ConnectionFailed {
-            url: redis_url.to_string(),
-            message: e.to_string(),
-        })?;
+    /// Creates a new QueueManager connected to the configured Redis server.
+    pub async fn new(config: &RedisConfig) -> Result<Self, QueueError> {
+        let connection = connection::connect(config).await?;
 
-        let connection = client
-            .get_connection_manager()
-            .await
-            .map_err(|e| QueueError::ConnectionFailed {
-                url: redis_url.to_string(),
-                message: e.to_string(),
-            })?;
+        Ok(Self {
+            connection,
+            keys: KeySpace::from_config(config),
+            history: HistoryConfig::default(),
+            spool: None,
+        })
+    }
 
-        Ok(Self { connection })
+    /// Connects to the Redis server of a configuration, applying its history
+    /// retention.
+    pub async fn connect(config: &AppConfig) -> Result<Self, QueueError> {
+        Ok(Self::new(&config.global.redis)
+            .await?
+            .with_history(config.global.history.clone()))
+    }
+
+    /// Sets the retention applied when finished jobs are archived.
+    pub fn with_history(mut self, history: HistoryConfig) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Buffers job writes in the spool while Redis is unreachable.
+    pub fn with_spool(mut self, spool: Spool) -> Self {
+        self.spool = Some(spool);
+        self
+    }
+
+    /// Returns the spool, if one is attached.
+    pub fn spool(&self) -> Option<&Spool> {
+        self.spool.as_ref()
+    }
+
+    /// Returns true if Redis answers a PING.
+    pub async fn is_reachable(&mut self) -> bool {
+        let ping = redis::cmd("PING");
+        let reply = tokio::time::timeout(
+            PING_TIMEOUT,
+            ping.query_async::<_, String>(&mut self.connection),
+        );
+        matches!(reply.await, Ok(Ok(_)))
+    }
+
+    /// Returns true if writes would currently be spooled rather than fail.
+    pub async fn can_spool(&mut self) -> bool {
+        match self.spool.clone() {
+            Some(spool) => spool.is_degraded() || !self.is_reachable().await,
+            None => false,
+        }
     }
 
     /// Adds a job to the queue.
     pub async fn enqueue(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
-        let job_json =
-            serde_json::to_string(job).map_err(|e| QueueError::SerializationFailed(e.to_string()))?;
+        self.write(SpoolOp::Enqueue, job).await
+    }
+
+    /// Updates a job's data in Redis.
+    pub async fn update_job(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        self.write(SpoolOp::Update, job).await
+    }
+
+    /// Marks a job as completed and removes from processing.
+    pub async fn complete_job(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        self.write(SpoolOp::Complete, job).await
+    }
+
+    /// Moves a failed job back to the queue for retry.
+    pub async fn retry_job(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        self.write(SpoolOp::Retry, job).await
+    }
+
+    /// Moves a job to the dead letter queue.
+    pub async fn dead_letter(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        self.write(SpoolOp::DeadLetter, job).await
+    }
+
+    /// Records a cancelled in-progress job and removes it from processing.
+    pub async fn finish_cancelled(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        self.write(SpoolOp::FinishCancelled, job).await
+    }
+
+    /// Applies a write, spooling it if Redis is unreachable.
+    ///
+    /// While older writes are still spooled, new ones are spooled behind them
+    /// so that replay keeps their order.
+    async fn write(&mut self, op: SpoolOp, job: &EncodeJob) -> Result<(), QueueError> {
+        let Some(spool) = self.spool.clone() else {
+            return self.apply(op, job).await;
+        };
+
+        if spool.pending() > 0 {
+            return spool.push(op, job).await;
+        }
+
+        match self.apply(op, job).await {
+            Ok(()) => Ok(()),
+            Err(e) if self.is_reachable().await => Err(e),
+            Err(e) => {
+                if !spool.set_degraded(true) {
+                    warn!(error = %e, spool = ?spool.dir(), "Redis unreachable, spooling queue writes to disk");
+                }
+                spool.push(op, job).await
+            }
+        }
+    }
+
+    /// Applies a write directly to Redis.
+    async fn apply(&mut self, op: SpoolOp, job: &EncodeJob) -> Result<(), QueueError> {
+        match op {
+            SpoolOp::Enqueue => self.enqueue_now(job).await,
+            SpoolOp::Update => self.write_job(job).await,
+            SpoolOp::Complete => self.complete_now(job).await,
+            SpoolOp::Retry => self.retry_now(job).await,
+            SpoolOp::DeadLetter => self.dead_letter_now(job).await,
+            SpoolOp::FinishCancelled => self.finish_cancelled_now(job).await,
+        }
+    }
+
+    /// Replays spooled writes in order, returning how many were applied.
+    ///
+    /// Stops when Redis becomes unreachable again; the rest stay spooled for
+    /// the next attempt. Entries that are corrupt or that Redis rejects are
+    /// moved to the quarantine directory so they do not block the others. A
+    /// spooled enqueue is dropped if another job for the same file was queued
+    /// in the meantime.
+    pub async fn replay_spool(&mut self) -> Result<usize, QueueError> {
+        let Some(spool) = self.spool.clone() else {
+            return Ok(0);
+        };
+        if spool.pending() == 0 {
+            return Ok(0);
+        }
+
+        let _guard = spool.lock().await;
+        let mut replayed = 0;
+
+        for (path, entry) in spool.entries().await? {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(reason) => {
+                    let moved = spool.quarantine(&path).await?;
+                    error!(entry = ?moved, %reason, "Quarantined corrupt spool entry");
+                    continue;
+                }
+            };
 
-        let job_key = format!("{}{}", JOB_PREFIX, job.id);
+            let duplicate = match entry.op {
+                SpoolOp::Enqueue => self
+                    .find_active_job(&entry.job.input_path)
+                    .await?
+                    .filter(|existing| existing.id != entry.job.id),
+                _ => None,
+            };
+
+            match duplicate {
+                Some(existing) => warn!(
+                    job_id = %entry.job.id,
+                    existing = %existing.id,
+                    input = ?entry.job.input_path,
+                    "Dropping spooled job, file was queued again while Redis was unreachable"
+                ),
+                None => {
+                    if let Err(e) = self.apply(entry.op, &entry.job).await {
+                        if !self.is_reachable().await {
+                            return Err(e);
+                        }
+                        let moved = spool.quarantine(&path).await?;
+                        error!(
+                            job_id = %entry.job.id,
+                            op = ?entry.op,
+                            entry = ?moved,
+                            error = %e,
+                            "Quarantined spool entry rejected by Redis"
+                        );
+                        continue;
+                    }
+                }
+            }
+
+            spool.remove(&path).await?;
+            replayed += 1;
+        }
+
+        if spool.pending() == 0 && spool.set_degraded(false) {
+            info!(
+                replayed,
+                "Redis reachable again, spooled queue writes replayed"
+            );
+        }
+
+        Ok(replayed)
+    }
+
+    /// Stores a job and adds it to the queue.
+    async fn enqueue_now(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        let job_json = serde_json::to_string(job)
+            .map_err(|e| QueueError::SerializationFailed(e.to_string()))?;
+
+        let job_key = self.keys.job(&job.id);
 
         // Store the job data
         self.connection
@@ -49,9 +245,62 @@ impl QueueManager {
             .await
             .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
 
-        // Add job ID to the queue
+        // Add job ID to the queue, once even when replayed from the spool
         self.connection
-            .rpush::<_, _, ()>(QUEUE_KEY, &job.id)
+            .lrem::<_, _, ()>(self.keys.queue(), 0, &job.id)
+            .await
+            .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
+        self.insert_by_priority(job).await
+    }
+
+    /// Inserts a job ID into the queue ahead of all jobs with a lower priority.
+    async fn insert_by_priority(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        let job_ids: Vec<String> = self
+            .connection
+            .lrange(self.keys.queue(), 0, -1)
+            .await
+            .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
+
+        // Common case: the tail already has an equal or higher priority
+        let tail_priority = match job_ids.last() {
+            Some(id) => self.get_job(id).await?.map(|j| j.priority),
+            None => None,
+        };
+
+        if tail_priority.is_none_or(|p| p >= job.priority) {
+            self.connection
+                .rpush::<_, _, ()>(self.keys.queue(), &job.id)
+                .await
+                .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
+            return Ok(());
+        }
+
+        for id in &job_ids {
+            let lower = self
+                .get_job(id)
+                .await?
+                .is_some_and(|j| j.priority < job.priority);
+
+            if lower {
+                let inserted: i64 = redis::cmd("LINSERT")
+                    .arg(self.keys.queue())
+                    .arg("BEFORE")
+                    .arg(id)
+                    .arg(&job.id)
+                    .query_async(&mut self.connection)
+                    .await
+                    .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
+
+                // The pivot may have been dequeued in the meantime
+                if inserted > 0 {
+                    return Ok(());
+                }
+                break;
+            }
+        }
+
+        self.connection
+            .rpush::<_, _, ()>(self.keys.queue(), &job.id)
             .await
             .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
 
@@ -63,7 +312,7 @@ impl QueueManager {
         // Atomically move from queue to processing
         let job_id: Option<String> = self
             .connection
-            .lpop(QUEUE_KEY, None)
+            .lpop(self.keys.queue(), None)
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
 
@@ -74,7 +323,7 @@ impl QueueManager {
 
         // Add to processing set
         self.connection
-            .sadd::<_, _, ()>(PROCESSING_KEY, &job_id)
+            .sadd::<_, _, ()>(self.keys.processing(), &job_id)
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
 
@@ -82,9 +331,32 @@ impl QueueManager {
         self.get_job(&job_id).await
     }
 
+    /// Takes a specific pending job for processing (moves to processing set).
+    ///
+    /// Returns `None` if the job is no longer queued, e.g. because another
+    /// worker took it first.
+    pub async fn claim(&mut self, job_id: &str) -> Result<Option<EncodeJob>, QueueError> {
+        let removed: i64 = self
+            .connection
+            .lrem(self.keys.queue(), 1, job_id)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        if removed == 0 {
+            return Ok(None);
+        }
+
+        self.connection
+            .sadd::<_, _, ()>(self.keys.processing(), job_id)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        self.get_job(job_id).await
+    }
+
     /// Gets a job by its ID.
     pub async fn get_job(&mut self, job_id: &str) -> Result<Option<EncodeJob>, QueueError> {
-        let job_key = format!("{}{}", JOB_PREFIX, job_id);
+        let job_key = self.keys.job(job_id);
 
         let job_json: Option<String> = self
             .connection
@@ -102,12 +374,12 @@ impl QueueManager {
         }
     }
 
-    /// Updates a job's data in Redis.
-    pub async fn update_job(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
-        let job_json =
-            serde_json::to_string(job).map_err(|e| QueueError::SerializationFailed(e.to_string()))?;
+    /// Stores a job's data in Redis.
+    async fn write_job(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        let job_json = serde_json::to_string(job)
+            .map_err(|e| QueueError::SerializationFailed(e.to_string()))?;
 
-        let job_key = format!("{}{}", JOB_PREFIX, job.id);
+        let job_key = self.keys.job(&job.id);
 
         self.connection
             .set::<_, _, ()>(&job_key, &job_json)
@@ -117,65 +389,398 @@ impl QueueManager {
         Ok(())
     }
 
-    /// Marks a job as completed and removes from processing.
-    pub async fn complete_job(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+    /// Stores a completed job, removes it from processing and archives it.
+    async fn complete_now(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
         // Update job data
-        self.update_job(job).await?;
+        self.write_job(job).await?;
+
+        self.leave_processing(&job.id).await?;
 
-        // Remove from processing set
+        self.archive(job).await
+    }
+
+    /// Removes a job from the processing set, dropping any cancellation
+    /// requested while it ran.
+    async fn leave_processing(&mut self, job_id: &str) -> Result<(), QueueError> {
         self.connection
-            .srem::<_, _, ()>(PROCESSING_KEY, &job.id)
+            .srem::<_, _, ()>(self.keys.processing(), job_id)
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+        self.connection
+            .srem::<_, _, ()>(self.keys.cancel_requested(), job_id)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Adds a finished job to the history index and applies retention.
+    ///
+    /// Jobs that fall out of retention are deleted. Dead-lettered jobs are
+    /// kept for triage and do not count toward `max_entries`.
+    async fn archive(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        self.connection
+            .zadd::<_, _, _, ()>(self.keys.history(), &job.id, job.finished_at().timestamp())
+            .await
+            .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
+
+        if self.history.max_age_days == 0 && self.history.max_entries == 0 {
+            return Ok(());
+        }
+
+        let entries: Vec<(String, f64)> = self
+            .connection
+            .zrange_withscores(self.keys.history(), 0, -1)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        let dead_letter: Vec<String> = self
+            .connection
+            .lrange(self.keys.dead_letter(), 0, -1)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        let entries: Vec<(String, i64)> = entries
+            .into_iter()
+            .map(|(id, score)| (id, score as i64))
+            .collect();
+        for id in expired_history(&entries, &dead_letter, &self.history, Utc::now()) {
+            self.forget(&id).await?;
+        }
 
         Ok(())
     }
 
-    /// Moves a failed job back to the queue for retry.
-    pub async fn retry_job(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
-        // Update job data
-        self.update_job(job).await?;
+    /// Removes a job from every index and deletes its data.
+    async fn forget(&mut self, job_id: &str) -> Result<(), QueueError> {
+        self.connection
+            .lrem::<_, _, ()>(self.keys.queue(), 0, job_id)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
 
-        // Remove from processing set
         self.connection
-            .srem::<_, _, ()>(PROCESSING_KEY, &job.id)
+            .lrem::<_, _, ()>(self.keys.dead_letter(), 0, job_id)
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
 
-        // Add back to queue (at the front for immediate retry)
         self.connection
-            .lpush::<_, _, ()>(QUEUE_KEY, &job.id)
+            .zrem::<_, _, ()>(self.keys.history(), job_id)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        self.connection
+            .del::<_, ()>(self.keys.job(job_id))
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Lists archived jobs finished within a time range, newest first.
+    pub async fn list_history(
+        &mut self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<Vec<EncodeJob>, QueueError> {
+        let max = until.map_or("+inf".to_string(), |t| t.timestamp().to_string());
+        let min = since.map_or("-inf".to_string(), |t| t.timestamp().to_string());
+
+        let job_ids: Vec<String> = self
+            .connection
+            .zrevrangebyscore(self.keys.history(), max, min)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        let mut jobs = Vec::new();
+        for id in job_ids {
+            if let Some(job) = self.get_job(&id).await? {
+                jobs.push(job);
+            }
+        }
+        Ok(jobs)
+    }
+
+    /// Lists the most recently completed jobs, newest first.
+    pub async fn list_recent_completed(
+        &mut self,
+        limit: usize,
+    ) -> Result<Vec<EncodeJob>, QueueError> {
+        let mut jobs = Vec::new();
+        let mut start = 0;
+
+        while jobs.len() < limit {
+            let job_ids: Vec<String> = self
+                .connection
+                .zrevrange(self.keys.history(), start, start + HISTORY_SCAN_BATCH - 1)
+                .await
+                .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+            if job_ids.is_empty() {
+                break;
+            }
+            start += HISTORY_SCAN_BATCH;
+
+            for id in job_ids {
+                if let Some(job) = self.get_job(&id).await? {
+                    if job.status == JobStatus::Completed && jobs.len() < limit {
+                        jobs.push(job);
+                    }
+                }
+            }
+        }
+
+        Ok(jobs)
+    }
+
+    /// Publishes the watchers' tracking state, expiring after `ttl_secs`.
+    pub async fn publish_watcher_state(
+        &mut self,
+        state: &WatcherSnapshot,
+        ttl_secs: u64,
+    ) -> Result<(), QueueError> {
+        let json = serde_json::to_string(state)
+            .map_err(|e| QueueError::SerializationFailed(e.to_string()))?;
+        self.connection
+            .set_ex::<_, _, ()>(self.keys.watcher_state(), json, ttl_secs)
+            .await
+            .map_err(|e| QueueError::EnqueueFailed(e.to_string()))
+    }
+
+    /// Reads the watchers' tracking state, if a pipeline has published it recently.
+    pub async fn watcher_state(&mut self) -> Result<Option<WatcherSnapshot>, QueueError> {
+        let json: Option<String> = self
+            .connection
+            .get(self.keys.watcher_state())
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        json.map(|j| {
+            serde_json::from_str(&j).map_err(|e| QueueError::SerializationFailed(e.to_string()))
+        })
+        .transpose()
+    }
+
+    /// Moves a failed job from processing to the front of the queue.
+    async fn retry_now(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        // Update job data
+        self.write_job(job).await?;
+
+        self.leave_processing(&job.id).await?;
+
+        // Add back to queue (at the front for immediate retry), once even when replayed
+        self.connection
+            .lrem::<_, _, ()>(self.keys.queue(), 0, &job.id)
+            .await
+            .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
+        self.connection
+            .lpush::<_, _, ()>(self.keys.queue(), &job.id)
             .await
             .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
 
         Ok(())
     }
 
-    /// Moves a job to the dead letter queue.
-    pub async fn dead_letter(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+    /// Moves a job from processing to the dead letter queue.
+    async fn dead_letter_now(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
         // Update job data
-        self.update_job(job).await?;
+        self.write_job(job).await?;
+
+        self.leave_processing(&job.id).await?;
+
+        // Add to dead letter queue, once even when replayed
+        self.connection
+            .lrem::<_, _, ()>(self.keys.dead_letter(), 0, &job.id)
+            .await
+            .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
+        self.connection
+            .rpush::<_, _, ()>(self.keys.dead_letter(), &job.id)
+            .await
+            .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
+
+        self.archive(job).await
+    }
+
+    /// Publishes a node's heartbeat, which expires after `ttl_secs`.
+    pub async fn register_node(
+        &mut self,
+        node: &NodeInfo,
+        ttl_secs: u64,
+    ) -> Result<(), QueueError> {
+        let json = serde_json::to_string(node)
+            .map_err(|e| QueueError::SerializationFailed(e.to_string()))?;
+
+        self.connection
+            .set_ex::<_, _, ()>(self.keys.node(&node.id), json, ttl_secs)
+            .await
+            .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
 
-        // Remove from processing set
         self.connection
-            .srem::<_, _, ()>(PROCESSING_KEY, &job.id)
+            .zadd::<_, _, _, ()>(self.keys.nodes(), &node.id, node.heartbeat_at.timestamp())
+            .await
+            .map_err(|e| QueueError::EnqueueFailed(e.to_string()))
+    }
+
+    /// Removes a node that is shutting down.
+    pub async fn unregister_node(&mut self, node_id: &str) -> Result<(), QueueError> {
+        self.connection
+            .del::<_, ()>(self.keys.node(node_id))
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
 
-        // Add to dead letter queue
         self.connection
-            .rpush::<_, _, ()>(DEAD_LETTER_KEY, &job.id)
+            .zrem::<_, _, ()>(self.keys.nodes(), node_id)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))
+    }
+
+    /// Returns the registration of a live node.
+    pub async fn node(&mut self, node_id: &str) -> Result<Option<NodeInfo>, QueueError> {
+        let json: Option<String> = self
+            .connection
+            .get(self.keys.node(node_id))
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        json.map(|json| {
+            serde_json::from_str(&json).map_err(|e| QueueError::SerializationFailed(e.to_string()))
+        })
+        .transpose()
+    }
+
+    /// Lists live nodes, forgetting those whose heartbeat expired.
+    pub async fn list_nodes(&mut self) -> Result<Vec<NodeInfo>, QueueError> {
+        let ids: Vec<String> = self
+            .connection
+            .zrange(self.keys.nodes(), 0, -1)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        let mut nodes = Vec::new();
+        for id in ids {
+            let json: Option<String> = self
+                .connection
+                .get(self.keys.node(&id))
+                .await
+                .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+            match json {
+                Some(json) => nodes.push(
+                    serde_json::from_str(&json)
+                        .map_err(|e| QueueError::SerializationFailed(e.to_string()))?,
+                ),
+                None => self
+                    .connection
+                    .zrem::<_, _, ()>(self.keys.nodes(), &id)
+                    .await
+                    .map_err(|e| QueueError::DequeueFailed(e.to_string()))?,
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// Takes or renews the leader lease for a process's lease token, returning
+    /// whether it holds it.
+    pub async fn acquire_leadership(
+        &mut self,
+        token: &str,
+        ttl: std::time::Duration,
+    ) -> Result<bool, QueueError> {
+        let script = redis::Script::new(
+            r"
+            local current = redis.call('GET', KEYS[1])
+            if not current then
+                redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+                return 1
+            end
+            if current == ARGV[1] then
+                redis.call('PEXPIRE', KEYS[1], ARGV[2])
+                return 1
+            end
+            return 0
+            ",
+        );
+
+        let held: i64 = script
+            .key(self.keys.leader())
+            .arg(token)
+            .arg(ttl.as_millis() as u64)
+            .invoke_async(&mut self.connection)
             .await
             .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
+        Ok(held == 1)
+    }
+
+    /// Gives up the leader lease if the token holds it.
+    pub async fn release_leadership(&mut self, token: &str) -> Result<(), QueueError> {
+        let script = redis::Script::new(
+            r"
+            if redis.call('GET', KEYS[1]) == ARGV[1] then
+                redis.call('DEL', KEYS[1])
+            end
+            return 0
+            ",
+        );
 
+        script
+            .key(self.keys.leader())
+            .arg(token)
+            .invoke_async::<_, i64>(&mut self.connection)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
         Ok(())
     }
 
+    /// Returns the ID of the node holding the leader lease.
+    pub async fn leader(&mut self) -> Result<Option<String>, QueueError> {
+        let token: Option<String> = self
+            .connection
+            .get(self.keys.leader())
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+        Ok(token.map(|token| lease_holder(&token).to_string()))
+    }
+
+    /// Puts in-progress jobs of nodes that died back at the front of the queue.
+    ///
+    /// A job is orphaned when its node stopped sending heartbeats, or restarted
+    /// after the job started, and the job has not been updated for `grace`.
+    pub async fn reap_orphaned_jobs(
+        &mut self,
+        grace: Duration,
+    ) -> Result<Vec<EncodeJob>, QueueError> {
+        let nodes: HashMap<String, DateTime<Utc>> = self
+            .list_nodes()
+            .await?
+            .into_iter()
+            .map(|node| (node.id, node.started_at))
+            .collect();
+        let stale_before = Utc::now() - grace;
+
+        let mut reaped = Vec::new();
+        for mut job in self.list_processing().await? {
+            let Some(node) = &job.node else {
+                continue;
+            };
+            let orphaned = match nodes.get(node) {
+                Some(node_started) => job
+                    .started_at
+                    .is_some_and(|started| started < *node_started),
+                None => true,
+            };
+
+            if orphaned && job.updated_at < stale_before {
+                job.retry();
+                self.retry_job(&job).await?;
+                reaped.push(job);
+            }
+        }
+        Ok(reaped)
+    }
+
     /// Returns the number of jobs in the queue.
     pub async fn queue_length(&mut self) -> Result<usize, QueueError> {
         let len: usize = self
             .connection
-            .llen(QUEUE_KEY)
+            .llen(self.keys.queue())
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
         Ok(len)
@@ -185,7 +790,7 @@ impl QueueManager {
     pub async fn processing_count(&mut self) -> Result<usize, QueueError> {
         let count: usize = self
             .connection
-            .scard(PROCESSING_KEY)
+            .scard(self.keys.processing())
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
         Ok(count)
@@ -195,17 +800,36 @@ impl QueueManager {
     pub async fn dead_letter_count(&mut self) -> Result<usize, QueueError> {
         let len: usize = self
             .connection
-            .llen(DEAD_LETTER_KEY)
+            .llen(self.keys.dead_letter())
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
         Ok(len)
     }
 
+    /// Returns up to `count` queued job IDs in dequeue order, from `start`.
+    pub async fn queued_ids(
+        &mut self,
+        start: usize,
+        count: usize,
+    ) -> Result<Vec<String>, QueueError> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        self.connection
+            .lrange(
+                self.keys.queue(),
+                start as isize,
+                (start + count - 1) as isize,
+            )
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))
+    }
+
     /// Lists all jobs in the queue.
     pub async fn list_queue(&mut self) -> Result<Vec<EncodeJob>, QueueError> {
         let job_ids: Vec<String> = self
             .connection
-            .lrange(QUEUE_KEY, 0, -1)
+            .lrange(self.keys.queue(), 0, -1)
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
 
@@ -222,7 +846,7 @@ impl QueueManager {
     pub async fn list_dead_letter(&mut self) -> Result<Vec<EncodeJob>, QueueError> {
         let job_ids: Vec<String> = self
             .connection
-            .lrange(DEAD_LETTER_KEY, 0, -1)
+            .lrange(self.keys.dead_letter(), 0, -1)
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
 
@@ -235,39 +859,444 @@ impl QueueManager {
         Ok(jobs)
     }
 
-    /// Clears all jobs from the queue (does not affect processing or dead letter).
-    pub async fn clear_queue(&mut self) -> Result<usize, QueueError> {
-        let len = self.queue_length().await?;
+    /// Lists all jobs currently being processed.
+    pub async fn list_processing(&mut self) -> Result<Vec<EncodeJob>, QueueError> {
+        let job_ids: Vec<String> = self
+            .connection
+            .smembers(self.keys.processing())
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        let mut jobs = Vec::new();
+        for id in job_ids {
+            if let Some(job) = self.get_job(&id).await? {
+                jobs.push(job);
+            }
+        }
+        Ok(jobs)
+    }
+
+    /// Finds a pending or in-progress job for the given input path.
+    pub async fn find_active_job(
+        &mut self,
+        input_path: &Path,
+    ) -> Result<Option<EncodeJob>, QueueError> {
+        let mut jobs = self.list_processing().await?;
+        jobs.extend(self.list_queue().await?);
+        Ok(jobs.into_iter().find(|j| j.input_path == input_path))
+    }
+
+    /// Loads a job or returns `JobNotFound`.
+    async fn require_job(&mut self, job_id: &str) -> Result<EncodeJob, QueueError> {
+        self.get_job(job_id)
+            .await?
+            .ok_or_else(|| QueueError::JobNotFound {
+                job_id: job_id.to_string(),
+            })
+    }
+
+    /// Cancels a job.
+    ///
+    /// Pending jobs are removed from the queue immediately. In-progress jobs are
+    /// flagged and stopped by the worker that owns them.
+    pub async fn cancel_job(&mut self, job_id: &str) -> Result<EncodeJob, QueueError> {
+        let mut job = self.require_job(job_id).await?;
+
+        match job.status {
+            JobStatus::Pending => {
+                self.connection
+                    .lrem::<_, _, ()>(self.keys.queue(), 0, job_id)
+                    .await
+                    .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+                job.cancel();
+                self.write_job(&job).await?;
+                self.archive(&job).await?;
+            }
+            JobStatus::InProgress => {
+                self.connection
+                    .sadd::<_, _, ()>(self.keys.cancel_requested(), job_id)
+                    .await
+                    .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
+            }
+            status => {
+                return Err(QueueError::InvalidState {
+                    job_id: job_id.to_string(),
+                    action: "cancelled".to_string(),
+                    status: format!("{:?}", status),
+                });
+            }
+        }
+
+        Ok(job)
+    }
+
+    /// Returns true if cancellation was requested for an in-progress job.
+    pub async fn is_cancel_requested(&mut self, job_id: &str) -> Result<bool, QueueError> {
         self.connection
-            .del::<_, ()>(QUEUE_KEY)
+            .sismember(self.keys.cancel_requested(), job_id)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))
+    }
+
+    /// Stores a cancelled job, removes it from processing and archives it.
+    async fn finish_cancelled_now(&mut self, job: &EncodeJob) -> Result<(), QueueError> {
+        self.write_job(job).await?;
+
+        self.leave_processing(&job.id).await?;
+
+        self.archive(job).await
+    }
+
+    /// Puts a finished job (failed, cancelled, completed or dead-lettered) back in the queue.
+    pub async fn requeue_job(&mut self, job_id: &str) -> Result<EncodeJob, QueueError> {
+        let mut job = self.require_job(job_id).await?;
+
+        if matches!(job.status, JobStatus::Pending | JobStatus::InProgress) {
+            return Err(QueueError::InvalidState {
+                job_id: job_id.to_string(),
+                action: "retried".to_string(),
+                status: format!("{:?}", job.status),
+            });
+        }
+
+        self.connection
+            .lrem::<_, _, ()>(self.keys.dead_letter(), 0, job_id)
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
-        Ok(len)
+
+        self.connection
+            .zrem::<_, _, ()>(self.keys.history(), job_id)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        job.retry();
+        self.write_job(&job).await?;
+        self.insert_by_priority(&job).await?;
+
+        Ok(job)
     }
 
-    /// Moves a job from dead letter back to the queue.
-    pub async fn retry_dead_letter(&mut self, job_id: &str) -> Result<(), QueueError> {
-        // Remove from dead letter queue
+    /// Changes the priority of a pending job and repositions it in the queue.
+    pub async fn reprioritize(
+        &mut self,
+        job_id: &str,
+        priority: i32,
+    ) -> Result<EncodeJob, QueueError> {
+        let mut job = self.require_job(job_id).await?;
+
+        if job.status != JobStatus::Pending {
+            return Err(QueueError::InvalidState {
+                job_id: job_id.to_string(),
+                action: "reprioritized".to_string(),
+                status: format!("{:?}", job.status),
+            });
+        }
+
+        let removed: usize = self
+            .connection
+            .lrem(self.keys.queue(), 0, job_id)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        job.priority = priority;
+        self.write_job(&job).await?;
+
+        // Only reinsert if it was still waiting (a worker may have taken it)
+        if removed > 0 {
+            self.insert_by_priority(&job).await?;
+        }
+
+        Ok(job)
+    }
+
+    /// Deletes a job that is not currently being processed.
+    pub async fn delete_job(&mut self, job_id: &str) -> Result<(), QueueError> {
+        let job = self.require_job(job_id).await?;
+
+        if job.status == JobStatus::InProgress {
+            return Err(QueueError::InvalidState {
+                job_id: job_id.to_string(),
+                action: "deleted".to_string(),
+                status: format!("{:?}", job.status),
+            });
+        }
+
+        self.forget(job_id).await
+    }
+
+    /// Deletes every job in the dead letter queue.
+    pub async fn purge_dead_letter(&mut self) -> Result<usize, QueueError> {
+        let job_ids: Vec<String> = self
+            .connection
+            .lrange(self.keys.dead_letter(), 0, -1)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        for id in &job_ids {
+            self.connection
+                .zrem::<_, _, ()>(self.keys.history(), id)
+                .await
+                .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+            self.connection
+                .del::<_, ()>(self.keys.job(id))
+                .await
+                .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+        }
+
         self.connection
-            .lrem::<_, _, ()>(DEAD_LETTER_KEY, 1, job_id)
+            .del::<_, ()>(self.keys.dead_letter())
             .await
             .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
 
-        // Get the job and reset its status
-        if let Some(mut job) = self.get_job(job_id).await? {
-            job.retry();
-            self.update_job(&job).await?;
+        Ok(job_ids.len())
+    }
 
-            // Add back to queue
+    /// Clears all jobs from the queue (does not affect processing or dead letter).
+    ///
+    /// The data of the removed jobs is deleted as well.
+    pub async fn clear_queue(&mut self) -> Result<usize, QueueError> {
+        let job_ids: Vec<String> = self
+            .connection
+            .lrange(self.keys.queue(), 0, -1)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        self.connection
+            .del::<_, ()>(self.keys.queue())
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        for id in &job_ids {
             self.connection
-                .rpush::<_, _, ()>(QUEUE_KEY, job_id)
+                .del::<_, ()>(self.keys.job(id))
                 .await
-                .map_err(|e| QueueError::EnqueueFailed(e.to_string()))?;
-            Ok(())
-        } else {
-            Err(QueueError::JobNotFound {
+                .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+        }
+
+        Ok(job_ids.len())
+    }
+
+    /// Moves a job from dead letter back to the queue.
+    ///
+    /// Goes through `requeue_job`, so the job is placed by its priority.
+    pub async fn retry_dead_letter(&mut self, job_id: &str) -> Result<EncodeJob, QueueError> {
+        self.require_dead_letter(job_id).await?;
+        self.requeue_job(job_id).await
+    }
+
+    /// Deletes a job from the dead letter queue.
+    pub async fn delete_dead_letter(&mut self, job_id: &str) -> Result<(), QueueError> {
+        self.require_dead_letter(job_id).await?;
+        self.forget(job_id).await
+    }
+
+    /// Fails with `JobNotFound` unless the job is in the dead letter queue.
+    async fn require_dead_letter(&mut self, job_id: &str) -> Result<(), QueueError> {
+        let position: Option<usize> = redis::cmd("LPOS")
+            .arg(self.keys.dead_letter())
+            .arg(job_id)
+            .query_async(&mut self.connection)
+            .await
+            .map_err(|e| QueueError::DequeueFailed(e.to_string()))?;
+
+        match position {
+            Some(_) => Ok(()),
+            None => Err(QueueError::JobNotFound {
                 job_id: job_id.to_string(),
-            })
+            }),
         }
     }
+
+    /// Moves every key of another key space into this manager's key space.
+    ///
+    /// Keys whose destination already exists are left in place and reported
+    /// as conflicts. With `dry_run`, only reports what would move. Pipelines
+    /// using either key space should be stopped first.
+    pub async fn migrate_keys(
+        &mut self,
+        from: &KeySpace,
+        dry_run: bool,
+    ) -> Result<KeyMigration, QueueError> {
+        if from == &self.keys {
+            return Err(QueueError::MigrationFailed(
+                "source and destination namespaces are the same".to_string(),
+            ));
+        }
+
+        let mut sources = from.fixed_keys();
+        {
+            let mut iter: redis::AsyncIter<String> = self
+                .connection
+                .scan_match(from.job_pattern())
+                .await
+                .map_err(|e| QueueError::MigrationFailed(e.to_string()))?;
+            while let Some(key) = iter.next_item().await {
+                sources.push(key);
+            }
+        }
+
+        let mut migration = KeyMigration::default();
+
+        for source in sources {
+            let Some(destination) = from.translate(&source, &self.keys) else {
+                continue;
+            };
+
+            let exists: bool = self
+                .connection
+                .exists(&source)
+                .await
+                .map_err(|e| QueueError::MigrationFailed(e.to_string()))?;
+            if !exists {
+                continue;
+            }
+
+            let key_move = KeyMove {
+                from: source,
+                to: destination,
+            };
+
+            let moved = if dry_run {
+                !self
+                    .connection
+                    .exists::<_, bool>(&key_move.to)
+                    .await
+                    .map_err(|e| QueueError::MigrationFailed(e.to_string()))?
+            } else {
+                self.connection
+                    .rename_nx(&key_move.from, &key_move.to)
+                    .await
+                    .map_err(|e| QueueError::MigrationFailed(e.to_string()))?
+            };
+
+            if moved {
+                migration.moved.push(key_move);
+            } else {
+                migration.conflicts.push(key_move);
+            }
+        }
+
+        Ok(migration)
+    }
+}
+
+/// A key renamed by a migration.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyMove {
+    /// Key in the old namespace.
+    pub from: String,
+    /// Key in the new namespace.
+    pub to: String,
+}
+
+/// Result of moving keys between namespaces.
+#[derive(Debug, Default, Serialize)]
+pub struct KeyMigration {
+    /// Keys moved (or that would move, in a dry run).
+    pub moved: Vec<KeyMove>,
+    /// Keys left in place because the destination already exists.
+    pub conflicts: Vec<KeyMove>,
+}
+
+/// Selects the history entries that fall out of retention.
+///
+/// `entries` are `(job ID, finished timestamp)` pairs ordered oldest first.
+/// Dead-lettered jobs are never selected and do not count toward `max_entries`.
+fn expired_history(
+    entries: &[(String, i64)],
+    dead_letter: &[String],
+    history: &HistoryConfig,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let prunable: Vec<&(String, i64)> = entries
+        .iter()
+        .filter(|(id, _)| !dead_letter.contains(id))
+        .collect();
+
+    let cutoff = (now - Duration::days(i64::from(history.max_age_days))).timestamp();
+    let excess = match history.max_entries {
+        0 => 0,
+        max => prunable.len().saturating_sub(max),
+    };
+
+    prunable
+        .iter()
+        .enumerate()
+        .filter(|(i, (_, finished))| {
+            *i < excess || (history.max_age_days > 0 && *finished <= cutoff)
+        })
+        .map(|(_, (id, _))| id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(max_entries: usize, max_age_days: u32) -> HistoryConfig {
+        HistoryConfig {
+            max_entries,
+            max_age_days,
+        }
+    }
+
+    fn entries(now: DateTime<Utc>, ages_days: &[i64]) -> Vec<(String, i64)> {
+        ages_days
+            .iter()
+            .enumerate()
+            .map(|(i, age)| {
+                (
+                    format!("job-{}", i),
+                    (now - Duration::days(*age)).timestamp(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn expired_history_drops_the_oldest_entries_over_the_limit() {
+        let now = Utc::now();
+        let entries = entries(now, &[5, 4, 3, 2, 1]);
+
+        assert_eq!(
+            expired_history(&entries, &[], &history(3, 0), now),
+            vec!["job-0", "job-1"]
+        );
+    }
+
+    #[test]
+    fn expired_history_drops_entries_older_than_the_max_age() {
+        let now = Utc::now();
+        let entries = entries(now, &[40, 31, 29, 1]);
+
+        assert_eq!(
+            expired_history(&entries, &[], &history(0, 30), now),
+            vec!["job-0", "job-1"]
+        );
+    }
+
+    #[test]
+    fn expired_history_keeps_dead_lettered_jobs() {
+        let now = Utc::now();
+        let entries = entries(now, &[40, 31, 29, 1]);
+        let dead_letter = vec!["job-0".to_string(), "job-2".to_string()];
+
+        assert_eq!(
+            expired_history(&entries, &dead_letter, &history(1, 30), now),
+            vec!["job-1"]
+        );
+        assert_eq!(
+            expired_history(&entries, &dead_letter, &history(2, 0), now),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn expired_history_without_limits_keeps_everything() {
+        let now = Utc::now();
+        let entries = entries(now, &[400, 300, 1]);
+
+        assert!(expired_history(&entries, &[], &history(0, 0), now).is_empty());
+    }
 }
@@ -0,0 +1,247 @@
+//! On-disk buffer for queue writes made while Redis is unreachable.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, MutexGuard};
+
+use super::job::EncodeJob;
+use crate::error::QueueError;
+
+/// Subdirectory that receives entries replay could not apply.
+const QUARANTINE_DIR: &str = "quarantine";
+
+/// A queue write that could not reach Redis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpoolOp {
+    /// A new job to queue.
+    Enqueue,
+    /// Updated job data (status, progress).
+    Update,
+    /// A job that completed.
+    Complete,
+    /// A failed job to put back at the front of the queue.
+    Retry,
+    /// A job that exhausted its attempts.
+    DeadLetter,
+    /// An in-progress job that was cancelled.
+    FinishCancelled,
+}
+
+/// A spooled write, replayed in the order it was made.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpoolEntry {
+    /// The write to replay.
+    pub op: SpoolOp,
+    /// Job state at the time of the write.
+    pub job: EncodeJob,
+    /// When the write was spooled.
+    pub spooled_at: DateTime<Utc>,
+}
+
+/// Shared spool state.
+#[derive(Debug)]
+struct SpoolState {
+    /// Serializes writers with replay.
+    lock: Mutex<()>,
+    /// Number of entry files on disk.
+    pending: AtomicUsize,
+    /// Tie-breaker for entries written within the same nanosecond.
+    sequence: AtomicU64,
+    /// Whether Redis is currently considered unreachable.
+    degraded: AtomicBool,
+}
+
+/// Directory of spooled writes, shared by every clone.
+///
+/// Each entry is a JSON file whose name sorts in write order. Once any entry
+/// is pending, later writes are spooled too so replay preserves ordering.
+#[derive(Debug, Clone)]
+pub struct Spool {
+    dir: PathBuf,
+    state: Arc<SpoolState>,
+}
+
+impl Spool {
+    /// Opens (creating if needed) a spool directory and counts its entries.
+    pub fn open(dir: &Path) -> Result<Self, QueueError> {
+        std::fs::create_dir_all(dir).map_err(|e| spool_error(dir, e))?;
+        let pending = entry_files(dir)?.len();
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            state: Arc::new(SpoolState {
+                lock: Mutex::new(()),
+                pending: AtomicUsize::new(pending),
+                sequence: AtomicU64::new(0),
+                degraded: AtomicBool::new(pending > 0),
+            }),
+        })
+    }
+
+    /// Returns the spool directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Number of entries waiting to be replayed.
+    pub fn pending(&self) -> usize {
+        self.state.pending.load(Ordering::SeqCst)
+    }
+
+    /// Whether Redis is currently considered unreachable.
+    pub fn is_degraded(&self) -> bool {
+        self.state.degraded.load(Ordering::SeqCst)
+    }
+
+    /// Sets the degraded flag, returning its previous value.
+    pub fn set_degraded(&self, degraded: bool) -> bool {
+        self.state.degraded.swap(degraded, Ordering::SeqCst)
+    }
+
+    /// Appends a write to the spool.
+    ///
+    /// An older pending `Update` of the same job is dropped, since only the
+    /// latest job state matters.
+    pub async fn push(&self, op: SpoolOp, job: &EncodeJob) -> Result<(), QueueError> {
+        let _guard = self.state.lock.lock().await;
+
+        let entry = SpoolEntry {
+            op,
+            job: job.clone(),
+            spooled_at: Utc::now(),
+        };
+        let json = serde_json::to_vec(&entry)
+            .map_err(|e| QueueError::SerializationFailed(e.to_string()))?;
+
+        let name = format!(
+            "{:020}-{:06}-{}-{}.json",
+            entry.spooled_at.timestamp_nanos_opt().unwrap_or_default(),
+            self.state.sequence.fetch_add(1, Ordering::SeqCst) % 1_000_000,
+            op_name(op),
+            job.id
+        );
+
+        let dir = self.dir.clone();
+        let superseded = (op == SpoolOp::Update).then(|| format!("-update-{}.json", job.id));
+
+        let removed = blocking(move || {
+            let mut removed = 0;
+            if let Some(suffix) = superseded {
+                for path in entry_files(&dir)? {
+                    if path.to_string_lossy().ends_with(&suffix)
+                        && std::fs::remove_file(&path).is_ok()
+                    {
+                        removed += 1;
+                    }
+                }
+            }
+
+            // Write then rename so a crash never leaves a partial entry
+            let path = dir.join(name);
+            let partial = path.with_extension("tmp");
+            std::fs::write(&partial, json).map_err(|e| spool_error(&partial, e))?;
+            std::fs::rename(&partial, &path).map_err(|e| spool_error(&path, e))?;
+            Ok(removed)
+        })
+        .await?;
+
+        self.state.pending.fetch_sub(removed, Ordering::SeqCst);
+        self.state.pending.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Locks the spool against writers, for replay.
+    pub async fn lock(&self) -> MutexGuard<'_, ()> {
+        self.state.lock.lock().await
+    }
+
+    /// Reads the pending entries in write order.
+    ///
+    /// An entry that cannot be read or parsed is returned as an error
+    /// message, so the caller can quarantine it and carry on.
+    ///
+    /// Call while holding [`Spool::lock`].
+    pub async fn entries(&self) -> Result<Vec<(PathBuf, Result<SpoolEntry, String>)>, QueueError> {
+        let dir = self.dir.clone();
+        blocking(move || {
+            Ok(entry_files(&dir)?
+                .into_iter()
+                .map(|path| {
+                    let entry = std::fs::read(&path)
+                        .map_err(|e| e.to_string())
+                        .and_then(|data| serde_json::from_slice(&data).map_err(|e| e.to_string()));
+                    (path, entry)
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Removes a replayed entry.
+    ///
+    /// Call while holding [`Spool::lock`].
+    pub async fn remove(&self, path: &Path) -> Result<(), QueueError> {
+        let path = path.to_path_buf();
+        blocking(move || std::fs::remove_file(&path).map_err(|e| spool_error(&path, e))).await?;
+        self.state.pending.fetch_sub(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Moves an entry that cannot be replayed to the quarantine directory,
+    /// returning its new path.
+    ///
+    /// Call while holding [`Spool::lock`].
+    pub async fn quarantine(&self, path: &Path) -> Result<PathBuf, QueueError> {
+        let quarantine = self.dir.join(QUARANTINE_DIR);
+        let path = path.to_path_buf();
+        let moved = blocking(move || {
+            std::fs::create_dir_all(&quarantine).map_err(|e| spool_error(&quarantine, e))?;
+            let target = quarantine.join(path.file_name().unwrap_or_default());
+            std::fs::rename(&path, &target).map_err(|e| spool_error(&path, e))?;
+            Ok(target)
+        })
+        .await?;
+        self.state.pending.fetch_sub(1, Ordering::SeqCst);
+        Ok(moved)
+    }
+}
+
+/// Runs spool file I/O off the async runtime.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, QueueError> + Send + 'static,
+) -> Result<T, QueueError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| QueueError::SpoolFailed(format!("Spool task failed: {}", e)))?
+}
+
+/// Lists entry files sorted by name (write order).
+fn entry_files(dir: &Path) -> Result<Vec<PathBuf>, QueueError> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| spool_error(dir, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn op_name(op: SpoolOp) -> &'static str {
+    match op {
+        SpoolOp::Enqueue => "enqueue",
+        SpoolOp::Update => "update",
+        SpoolOp::Complete => "complete",
+        SpoolOp::Retry => "retry",
+        SpoolOp::DeadLetter => "dead_letter",
+        SpoolOp::FinishCancelled => "finish_cancelled",
+    }
+}
+
+fn spool_error(path: &Path, error: std::io::Error) -> QueueError {
+    QueueError::SpoolFailed(format!("'{}': {}", path.display(), error))
+}
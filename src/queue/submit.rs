@@ -0,0 +1,285 @@
+//! Shared path for turning a file into a queued encoding job.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use super::job::{EncodeJob, JobOverrides};
+use super::redis::QueueManager;
+use crate::config::model::{AppConfig, HookEvent, Profile};
+use crate::error::QueueError;
+use crate::notify::HookRunner;
+use crate::validation::encoder_params;
+
+/// A request to encode a single file.
+#[derive(Debug, Clone, Default)]
+pub struct SubmitRequest {
+    /// Path to the source file.
+    pub path: PathBuf,
+    /// Profile name. When absent, the profile whose input folder contains the path is used.
+    pub profile: Option<String>,
+    /// Queue priority; higher values are dequeued first.
+    pub priority: i32,
+    /// Per-job settings applied on top of the profile.
+    pub overrides: JobOverrides,
+}
+
+impl SubmitRequest {
+    /// Creates a request for a path with default settings.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            ..Default::default()
+        }
+    }
+}
+
+/// Resolves the profile, builds the job and enqueues it.
+///
+/// Every intake path (folder watchers, API, webhooks, CLI) goes through here
+/// so that jobs are deduplicated, built and announced identically. A path
+/// that already has a pending or in-progress job is rejected with
+/// `QueueError::DuplicateJob`.
+pub async fn submit(
+    queue: &mut QueueManager,
+    config: &Arc<RwLock<AppConfig>>,
+    hooks: &HookRunner,
+    request: SubmitRequest,
+) -> Result<EncodeJob, QueueError> {
+    match queue.find_active_job(&request.path).await {
+        Ok(Some(existing)) => {
+            return Err(QueueError::DuplicateJob {
+                path: request.path,
+                job_id: existing.id,
+            });
+        }
+        Ok(None) => {}
+        // Duplicates of a spooled job are dropped when the spool is replayed
+        Err(e) if queue.can_spool().await => {
+            warn!(path = ?request.path, error = %e, "Redis unreachable, skipping duplicate check");
+        }
+        Err(e) => return Err(e),
+    }
+
+    let mut job = {
+        let config = config.read().await;
+        let profile = resolve_profile(&config, &request.path, request.profile.as_deref())?;
+        check_overrides(&request.path, &request.overrides, profile)?;
+
+        let output_path = match &request.overrides.output_path {
+            Some(path) => path.clone(),
+            None => calculate_output_path(&request.path, profile),
+        };
+        EncodeJob::new(request.path.clone(), output_path, profile.name.clone())
+    };
+
+    job.priority = request.priority;
+    job.overrides = request.overrides;
+
+    queue.enqueue(&job).await?;
+    info!(job_id = %job.id, input = ?job.input_path, profile = %job.profile_name, "Enqueued encoding job");
+
+    // Enqueue hooks cannot fail the job, so intake does not wait for them
+    hooks.spawn_dispatch(HookEvent::Enqueued, job.clone());
+
+    Ok(job)
+}
+
+/// Rejects overrides that would write over the source file or that the
+/// profile's encoder would not accept.
+///
+/// Overrides can come from API and webhook callers, so they get the same
+/// checks as the configuration they replace.
+fn check_overrides(
+    input: &Path,
+    overrides: &JobOverrides,
+    profile: &Profile,
+) -> Result<(), QueueError> {
+    if let Some(output) = &overrides.output_path {
+        if !output.is_absolute() {
+            return Err(QueueError::InvalidOverrides(format!(
+                "output path '{}' must be absolute",
+                output.display()
+            )));
+        }
+        if resolve_path(output) == resolve_path(input) {
+            return Err(QueueError::InvalidOverrides(format!(
+                "output path '{}' is the input file",
+                output.display()
+            )));
+        }
+    }
+
+    if let Some(params) = &overrides.encoder_params {
+        let result = encoder_params::validate(&profile.encoder, params, "overrides.encoder_params");
+        let errors: Vec<&str> = result.errors().map(|e| e.message.as_str()).collect();
+        if !errors.is_empty() {
+            return Err(QueueError::InvalidOverrides(errors.join("; ")));
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves symlinks and `..` in a path whose file may not exist yet.
+fn resolve_path(path: &Path) -> PathBuf {
+    if let Ok(resolved) = path.canonicalize() {
+        return resolved;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|p| p.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Finds the named profile, or the profile whose input folder contains the path.
+pub fn resolve_profile<'a>(
+    config: &'a AppConfig,
+    path: &Path,
+    name: Option<&str>,
+) -> Result<&'a Profile, QueueError> {
+    match name {
+        Some(name) => config
+            .profiles
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| QueueError::ProfileNotFound {
+                name: name.to_string(),
+            }),
+        None => config
+            .profiles
+            .iter()
+            .find(|p| path.starts_with(&p.input_path))
+            .ok_or_else(|| QueueError::NoMatchingProfile {
+                path: path.to_path_buf(),
+            }),
+    }
+}
+
+/// Calculates the output path for a file based on profile settings.
+///
+/// Files outside the profile's input folder are placed directly in the output folder.
+pub fn calculate_output_path(input_path: &Path, profile: &Profile) -> PathBuf {
+    use crate::config::model::{FilenameMode, OutputStructure};
+
+    let relative_path = input_path
+        .strip_prefix(&profile.input_path)
+        .unwrap_or_else(|_| Path::new(input_path.file_name().unwrap_or_default()));
+
+    let mut output_path = profile.output_path.clone();
+
+    match profile.output_naming.structure {
+        OutputStructure::Mirror => {
+            if let Some(parent) = relative_path.parent() {
+                output_path = output_path.join(parent);
+            }
+        }
+        OutputStructure::Flat => {
+            // Just use the output directory directly
+        }
+    }
+
+    let filename = match profile.output_naming.filename {
+        FilenameMode::Preserve => {
+            let mut name = relative_path.file_stem().unwrap_or_default().to_os_string();
+
+            if let Some(suffix) = &profile.output_naming.suffix {
+                name.push(suffix);
+            }
+
+            name.push(".mkv");
+            PathBuf::from(name)
+        }
+        FilenameMode::Template => {
+            // Template processing would go here
+            // For now, fall back to preserve
+            let name = relative_path.file_name().unwrap_or_default();
+            PathBuf::from(name)
+        }
+    };
+
+    output_path.join(filename)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(encoder: &str) -> Profile {
+        serde_yaml::from_str(&format!(
+            "name: test\ninput_path: /in\noutput_path: /out\nencoder: {}\naudio: {{rules: []}}\nsubtitles: {{tracks: []}}\n",
+            encoder
+        ))
+        .unwrap()
+    }
+
+    fn output(path: impl Into<PathBuf>) -> JobOverrides {
+        JobOverrides {
+            output_path: Some(path.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn check_overrides_rejects_a_relative_output_path() {
+        let result = check_overrides(
+            Path::new("/in/movie.mkv"),
+            &output("out/movie.mkv"),
+            &profile("svt-av1"),
+        );
+
+        assert!(matches!(result, Err(QueueError::InvalidOverrides(_))));
+    }
+
+    #[test]
+    fn check_overrides_rejects_an_output_path_that_resolves_to_the_input() {
+        let dir = std::env::temp_dir().join(format!("submit-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        let input = dir.join("movie.mkv");
+        std::fs::write(&input, b"").unwrap();
+
+        let same = check_overrides(&input, &output(&input), &profile("svt-av1"));
+        let dotted = check_overrides(
+            &input,
+            &output(dir.join("sub/../movie.mkv")),
+            &profile("svt-av1"),
+        );
+        let other = check_overrides(
+            &input,
+            &output(dir.join("movie.av1.mkv")),
+            &profile("svt-av1"),
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(same, Err(QueueError::InvalidOverrides(_))));
+        assert!(matches!(dotted, Err(QueueError::InvalidOverrides(_))));
+        assert!(other.is_ok());
+    }
+
+    #[test]
+    fn check_overrides_rejects_encoder_params_the_profile_encoder_refuses() {
+        let overrides = JobOverrides {
+            encoder_params: Some("--preset warp --crf 99".to_string()),
+            ..Default::default()
+        };
+
+        let result = check_overrides(Path::new("/in/movie.mkv"), &overrides, &profile("x265"));
+
+        assert!(matches!(result, Err(QueueError::InvalidOverrides(_))));
+    }
+
+    #[test]
+    fn check_overrides_accepts_valid_encoder_params() {
+        let overrides = JobOverrides {
+            encoder_params: Some("--preset slow --crf 22".to_string()),
+            ..Default::default()
+        };
+
+        assert!(check_overrides(Path::new("/in/movie.mkv"), &overrides, &profile("x265")).is_ok());
+    }
+}
@@ -1,6 +1,6 @@
 //! Codec availability validation.
 
-use crate::config::model::{AppConfig, Encoder};
+use crate::config::model::{AppConfig, Profile, ProfileMode};
 
 use super::{SystemCapabilities, ValidationIssue, ValidationResult};
 
@@ -11,9 +11,11 @@ pub fn validate(config: &AppConfig, capabilities: &SystemCapabilities) -> Valida
     for (i, profile) in config.profiles.iter().enumerate() {
         let prefix = format!("profiles[{}]", i);
 
-        // Check video encoder availability
+        // Check video encoder availability (remux profiles never encode video)
         let encoder_name = profile.encoder.to_string();
-        if !capabilities.av1an_encoders.contains(&encoder_name) {
+        if profile.mode == ProfileMode::Encode
+            && !capabilities.av1an_encoders.contains(&encoder_name)
+        {
             result.add(
                 ValidationIssue::error(
                     format!("{}.encoder", prefix),
@@ -26,6 +28,26 @@ pub fn validate(config: &AppConfig, capabilities: &SystemCapabilities) -> Valida
             );
         }
 
+        // Check fallback encoders of the retry ladder
+        for (j, step) in profile.retry_ladder.iter().enumerate() {
+            let Some(encoder) = &step.encoder else {
+                continue;
+            };
+            let encoder_name = encoder.to_string();
+            if !capabilities.av1an_encoders.contains(&encoder_name) {
+                result.add(
+                    ValidationIssue::error(
+                        format!("{}.retry_ladder[{}].encoder", prefix, j),
+                        format!("Video encoder '{}' is not available", encoder_name),
+                    )
+                    .with_suggestion(format!(
+                        "Available encoders: {}",
+                        format_available(&capabilities.av1an_encoders)
+                    )),
+                );
+            }
+        }
+
         // Check audio codecs in transcode settings
         for (j, rule) in profile.audio.rules.iter().enumerate() {
             if let Some(transcode) = &rule.transcode {
@@ -59,6 +81,36 @@ pub fn validate(config: &AppConfig, capabilities: &SystemCapabilities) -> Valida
     result
 }
 
+/// Lists the video encoder and audio codecs of a profile that are not available.
+pub fn missing_codecs(profile: &Profile, capabilities: &SystemCapabilities) -> Vec<String> {
+    let mut missing = Vec::new();
+
+    let encoder_name = profile.encoder.to_string();
+    if profile.mode == ProfileMode::Encode && !capabilities.av1an_encoders.contains(&encoder_name) {
+        missing.push(encoder_name);
+    }
+
+    let audio = profile
+        .audio
+        .rules
+        .iter()
+        .flat_map(|rule| {
+            [
+                rule.transcode.as_ref().map(|t| &t.codec),
+                rule.downmix.as_ref().map(|d| &d.codec),
+            ]
+        })
+        .flatten();
+    for codec in audio {
+        let codec = normalize_codec_name(codec);
+        if !capabilities.available_encoders.contains(&codec) && !missing.contains(&codec) {
+            missing.push(codec);
+        }
+    }
+
+    missing
+}
+
 /// Normalizes codec names to FFmpeg encoder names.
 fn normalize_codec_name(codec: &str) -> String {
     match codec.to_lowercase().as_str() {
@@ -79,14 +131,18 @@ fn suggest_audio_codec(requested: &str, capabilities: &SystemCapabilities) -> St
 
     let available: Vec<&str> = common_codecs
         .iter()
-        .filter(|c| capabilities.available_encoders.contains(&c.to_string()))
+        .filter(|c| capabilities.available_encoders.contains(**c))
         .copied()
         .collect();
 
     if available.is_empty() {
         "No common audio encoders available".to_string()
     } else {
-        format!("Available alternatives: {}", available.join(", "))
+        format!(
+            "Available alternatives to '{}': {}",
+            requested,
+            available.join(", ")
+        )
     }
 }
 
@@ -94,5 +150,64 @@ fn suggest_audio_codec(requested: &str, capabilities: &SystemCapabilities) -> St
 fn format_available(items: &std::collections::HashSet<String>) -> String {
     let mut sorted: Vec<_> = items.iter().collect();
     sorted.sort();
-    sorted.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+    sorted
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::config::model::Profile;
+
+    fn profile(encoder: &str, audio_codec: &str) -> Profile {
+        serde_yaml::from_str(&format!(
+            "name: test\ninput_path: /in\noutput_path: /out\nencoder: {}\nsubtitles: {{tracks: []}}\naudio:\n  rules:\n    - match: {{}}\n      action: transcode\n      transcode: {{codec: {}, bitrate: 128k}}\n",
+            encoder, audio_codec
+        ))
+        .unwrap()
+    }
+
+    fn capabilities(av1an: &[&str], ffmpeg: &[&str]) -> SystemCapabilities {
+        let set = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<HashSet<_>>();
+        SystemCapabilities {
+            available_encoders: set(ffmpeg),
+            available_decoders: HashSet::new(),
+            av1an_encoders: set(av1an),
+        }
+    }
+
+    #[test]
+    fn node_with_the_encoder_and_audio_codec_can_encode() {
+        let node = capabilities(&["svt-av1"], &["libopus", "aac"]);
+
+        assert!(missing_codecs(&profile("svt-av1", "opus"), &node).is_empty());
+        assert!(node.can_encode(&profile("svt-av1", "aac")));
+    }
+
+    #[test]
+    fn node_without_the_video_encoder_cannot_encode() {
+        let node = capabilities(&["x265"], &["aac"]);
+
+        assert_eq!(
+            missing_codecs(&profile("svt-av1", "aac"), &node),
+            vec!["svt-av1"]
+        );
+        assert!(!node.can_encode(&profile("svt-av1", "aac")));
+    }
+
+    #[test]
+    fn node_without_the_audio_encoder_cannot_encode() {
+        let node = capabilities(&["svt-av1"], &["aac"]);
+
+        assert_eq!(
+            missing_codecs(&profile("svt-av1", "opus"), &node),
+            vec!["libopus"]
+        );
+        assert!(!node.can_encode(&profile("svt-av1", "opus")));
+    }
 }
@@ -8,69 +8,233 @@ use super::{ValidationIssue, ValidationResult, ValidationSeverity};
 
 /// Known x265 parameters.
 const X265_PARAMS: &[&str] = &[
-    "preset", "tune", "crf", "qp", "bitrate", "pass", "stats", "slow-firstpass",
-    "keyint", "min-keyint", "scenecut", "scenecut-bias", "hist-scenecut",
-    "bframes", "b-adapt", "b-pyramid", "ref", "limit-refs",
-    "deblock", "sao", "sao-non-deblock", "limit-sao",
-    "aq-mode", "aq-strength", "qg-size", "cutree",
-    "psy-rd", "psy-rdoq", "rdoq-level", "rd", "rskip", "rskip-edge-threshold",
-    "colorprim", "transfer", "colormatrix", "chromaloc",
-    "hdr10", "hdr10-opt", "dhdr10-info", "dhdr10-opt",
-    "repeat-headers", "aud", "hrd", "info",
-    "hash", "temporal-layers", "open-gop",
-    "rc-lookahead", "lookahead-slices", "lookahead-threads",
-    "pmode", "pme", "pools", "frame-threads", "wpp", "slices",
-    "log-level", "csv", "csv-log-level",
-    "sar", "overscan", "videoformat", "range", "master-display", "max-cll",
-    "vbv-bufsize", "vbv-maxrate", "vbv-init", "crf-max", "crf-min",
-    "ipratio", "pbratio", "qcomp", "qpstep", "qpmin", "qpmax",
-    "cbqpoffs", "crqpoffs", "nr-intra", "nr-inter",
-    "input-res", "input-depth", "input-csp", "interlace", "fps",
-    "profile", "level-idc", "high-tier", "uhd-bd",
-    "weightp", "weightb", "analyze-src-pics",
-    "strong-intra-smoothing", "constrained-intra", "psy-rd",
-    "rect", "amp", "early-skip", "fast-intra", "b-intra",
-    "cu-lossless", "tskip-fast", "rd-refine",
-    "max-merge", "me", "subme", "merange", "max-tu-size", "min-cu-size", "max-cu-size",
-    "dynamic-rd", "ssim-rd",
+    "preset",
+    "tune",
+    "crf",
+    "qp",
+    "bitrate",
+    "pass",
+    "stats",
+    "slow-firstpass",
+    "keyint",
+    "min-keyint",
+    "scenecut",
+    "scenecut-bias",
+    "hist-scenecut",
+    "bframes",
+    "b-adapt",
+    "b-pyramid",
+    "ref",
+    "limit-refs",
+    "deblock",
+    "sao",
+    "sao-non-deblock",
+    "limit-sao",
+    "aq-mode",
+    "aq-strength",
+    "qg-size",
+    "cutree",
+    "psy-rd",
+    "psy-rdoq",
+    "rdoq-level",
+    "rd",
+    "rskip",
+    "rskip-edge-threshold",
+    "colorprim",
+    "transfer",
+    "colormatrix",
+    "chromaloc",
+    "hdr10",
+    "hdr10-opt",
+    "dhdr10-info",
+    "dhdr10-opt",
+    "repeat-headers",
+    "aud",
+    "hrd",
+    "info",
+    "hash",
+    "temporal-layers",
+    "open-gop",
+    "rc-lookahead",
+    "lookahead-slices",
+    "lookahead-threads",
+    "pmode",
+    "pme",
+    "pools",
+    "frame-threads",
+    "wpp",
+    "slices",
+    "log-level",
+    "csv",
+    "csv-log-level",
+    "sar",
+    "overscan",
+    "videoformat",
+    "range",
+    "master-display",
+    "max-cll",
+    "vbv-bufsize",
+    "vbv-maxrate",
+    "vbv-init",
+    "crf-max",
+    "crf-min",
+    "ipratio",
+    "pbratio",
+    "qcomp",
+    "qpstep",
+    "qpmin",
+    "qpmax",
+    "cbqpoffs",
+    "crqpoffs",
+    "nr-intra",
+    "nr-inter",
+    "input-res",
+    "input-depth",
+    "input-csp",
+    "interlace",
+    "fps",
+    "profile",
+    "level-idc",
+    "high-tier",
+    "uhd-bd",
+    "weightp",
+    "weightb",
+    "analyze-src-pics",
+    "strong-intra-smoothing",
+    "constrained-intra",
+    "psy-rd",
+    "rect",
+    "amp",
+    "early-skip",
+    "fast-intra",
+    "b-intra",
+    "cu-lossless",
+    "tskip-fast",
+    "rd-refine",
+    "max-merge",
+    "me",
+    "subme",
+    "merange",
+    "max-tu-size",
+    "min-cu-size",
+    "max-cu-size",
+    "dynamic-rd",
+    "ssim-rd",
 ];
 
 /// Known x265 presets.
 const X265_PRESETS: &[&str] = &[
-    "ultrafast", "superfast", "veryfast", "faster", "fast",
-    "medium", "slow", "slower", "veryslow", "placebo",
+    "ultrafast",
+    "superfast",
+    "veryfast",
+    "faster",
+    "fast",
+    "medium",
+    "slow",
+    "slower",
+    "veryslow",
+    "placebo",
 ];
 
 /// Known x265 tunes.
 const X265_TUNES: &[&str] = &[
-    "psnr", "ssim", "grain", "fastdecode", "zerolatency", "animation",
+    "psnr",
+    "ssim",
+    "grain",
+    "fastdecode",
+    "zerolatency",
+    "animation",
 ];
 
 /// Known x264 parameters.
 const X264_PARAMS: &[&str] = &[
-    "preset", "tune", "profile", "level", "crf", "qp", "bitrate", "pass",
-    "keyint", "min-keyint", "scenecut", "bframes", "b-adapt", "b-pyramid",
-    "ref", "deblock", "aq-mode", "aq-strength", "psy-rd",
-    "colorprim", "transfer", "colormatrix",
-    "rc-lookahead", "mbtree", "threads", "lookahead-threads",
-    "vbv-bufsize", "vbv-maxrate", "crf-max",
-    "weightp", "weightb", "me", "subme", "merange",
-    "direct", "trellis", "fast-pskip", "dct-decimate",
-    "nr", "interlaced", "sar", "overscan", "videoformat", "range",
+    "preset",
+    "tune",
+    "profile",
+    "level",
+    "crf",
+    "qp",
+    "bitrate",
+    "pass",
+    "keyint",
+    "min-keyint",
+    "scenecut",
+    "bframes",
+    "b-adapt",
+    "b-pyramid",
+    "ref",
+    "deblock",
+    "aq-mode",
+    "aq-strength",
+    "psy-rd",
+    "colorprim",
+    "transfer",
+    "colormatrix",
+    "rc-lookahead",
+    "mbtree",
+    "threads",
+    "lookahead-threads",
+    "vbv-bufsize",
+    "vbv-maxrate",
+    "crf-max",
+    "weightp",
+    "weightb",
+    "me",
+    "subme",
+    "merange",
+    "direct",
+    "trellis",
+    "fast-pskip",
+    "dct-decimate",
+    "nr",
+    "interlaced",
+    "sar",
+    "overscan",
+    "videoformat",
+    "range",
 ];
 
 /// Known SVT-AV1 parameters.
 const SVT_AV1_PARAMS: &[&str] = &[
-    "preset", "crf", "qp", "keyint", "lookahead", "tile-rows", "tile-columns",
-    "enable-overlays", "scd", "film-grain", "film-grain-denoise",
-    "enable-qm", "qm-min", "qm-max", "hierarchical-levels",
-    "pred-struct", "enable-dlf", "enable-cdef", "enable-restoration",
-    "enable-tpl-la", "enable-tf", "enable-overlays",
-    "aq-mode", "lp", "pin", "ss", "irefresh-type",
-    "color-primaries", "transfer-characteristics", "matrix-coefficients",
-    "color-range", "chroma-sample-position",
-    "mastering-display", "content-light",
-    "input-depth", "profile", "level", "tier", "fast-decode",
+    "preset",
+    "crf",
+    "qp",
+    "keyint",
+    "lookahead",
+    "tile-rows",
+    "tile-columns",
+    "enable-overlays",
+    "scd",
+    "film-grain",
+    "film-grain-denoise",
+    "enable-qm",
+    "qm-min",
+    "qm-max",
+    "hierarchical-levels",
+    "pred-struct",
+    "enable-dlf",
+    "enable-cdef",
+    "enable-restoration",
+    "enable-tpl-la",
+    "enable-tf",
+    "enable-overlays",
+    "aq-mode",
+    "lp",
+    "pin",
+    "ss",
+    "irefresh-type",
+    "color-primaries",
+    "transfer-characteristics",
+    "matrix-coefficients",
+    "color-range",
+    "chroma-sample-position",
+    "mastering-display",
+    "content-light",
+    "input-depth",
+    "profile",
+    "level",
+    "tier",
+    "fast-decode",
 ];
 
 /// Validates encoder parameters for the given encoder.
@@ -102,14 +266,12 @@ fn validate_x265_params(params: &str, path: &str, result: &mut ValidationResult)
     for param in parsed {
         if !known.contains(param.name.as_str()) {
             let suggestion = find_similar_param(&param.name, &known);
-            result.add(
-                ValidationIssue {
-                    severity: ValidationSeverity::Warning,
-                    path: format!("{}.{}", path, param.name),
-                    message: format!("Unknown x265 parameter: '--{}'", param.name),
-                    suggestion: Some(format!("Did you mean '--{}'?", suggestion)),
-                },
-            );
+            result.add(ValidationIssue {
+                severity: ValidationSeverity::Warning,
+                path: format!("{}.{}", path, param.name),
+                message: format!("Unknown x265 parameter: '--{}'", param.name),
+                suggestion: Some(format!("Did you mean '--{}'?", suggestion)),
+            });
             continue;
         }
 
@@ -123,27 +285,23 @@ fn validate_x265_params(params: &str, path: &str, result: &mut ValidationResult)
 /// Validates a specific x265 parameter value.
 fn validate_x265_param_value(name: &str, value: &str, path: &str, result: &mut ValidationResult) {
     match name {
-        "preset" => {
-            if !X265_PRESETS.contains(&value) {
-                result.add(
-                    ValidationIssue::error(
-                        format!("{}.preset", path),
-                        format!("Invalid x265 preset: '{}'", value),
-                    )
-                    .with_suggestion(format!("Valid presets: {}", X265_PRESETS.join(", "))),
-                );
-            }
+        "preset" if !X265_PRESETS.contains(&value) => {
+            result.add(
+                ValidationIssue::error(
+                    format!("{}.preset", path),
+                    format!("Invalid x265 preset: '{}'", value),
+                )
+                .with_suggestion(format!("Valid presets: {}", X265_PRESETS.join(", "))),
+            );
         }
-        "tune" => {
-            if !X265_TUNES.contains(&value) {
-                result.add(
-                    ValidationIssue::error(
-                        format!("{}.tune", path),
-                        format!("Invalid x265 tune: '{}'", value),
-                    )
-                    .with_suggestion(format!("Valid tunes: {}", X265_TUNES.join(", "))),
-                );
-            }
+        "tune" if !X265_TUNES.contains(&value) => {
+            result.add(
+                ValidationIssue::error(
+                    format!("{}.tune", path),
+                    format!("Invalid x265 tune: '{}'", value),
+                )
+                .with_suggestion(format!("Valid tunes: {}", X265_TUNES.join(", "))),
+            );
         }
         "crf" => {
             if let Ok(crf) = value.parse::<f32>() {
@@ -295,7 +453,7 @@ fn parse_params(params: &str) -> Vec<ParsedParam> {
             Some(val)
         } else if chars.peek() == Some(&' ') {
             chars.next(); // consume space
-            // Peek ahead to see if next thing is another param
+                          // Peek ahead to see if next thing is another param
             if chars.peek() == Some(&'-') {
                 None
             } else {
@@ -307,7 +465,11 @@ fn parse_params(params: &str) -> Vec<ParsedParam> {
                     val.push(c);
                     chars.next();
                 }
-                if val.is_empty() { None } else { Some(val) }
+                if val.is_empty() {
+                    None
+                } else {
+                    Some(val)
+                }
             }
         } else {
             None
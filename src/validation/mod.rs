@@ -9,11 +9,14 @@ pub mod semantic;
 
 use std::collections::HashSet;
 
-use crate::config::model::AppConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::config::model::{AppConfig, Profile};
 use crate::error::CapabilityError;
 
 /// Severity level for validation issues.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ValidationSeverity {
     /// Blocks configuration loading.
     Error,
@@ -22,7 +25,7 @@ pub enum ValidationSeverity {
 }
 
 /// A validation issue found during configuration checking.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ValidationIssue {
     /// Severity of the issue.
     pub severity: ValidationSeverity,
@@ -86,7 +89,10 @@ impl ValidationResult {
 
     /// Returns true if there are no errors (warnings are allowed).
     pub fn is_valid(&self) -> bool {
-        !self.issues.iter().any(|i| i.severity == ValidationSeverity::Error)
+        !self
+            .issues
+            .iter()
+            .any(|i| i.severity == ValidationSeverity::Error)
     }
 
     /// Returns an iterator over error-level issues.
@@ -115,7 +121,7 @@ impl ValidationResult {
 }
 
 /// System capabilities detected at startup.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemCapabilities {
     /// Available FFmpeg encoders.
     pub available_encoders: HashSet<String>,
@@ -138,6 +144,11 @@ impl SystemCapabilities {
             av1an_encoders,
         })
     }
+
+    /// Returns true if the video encoder and audio codecs of a profile are available.
+    pub fn can_encode(&self, profile: &Profile) -> bool {
+        codec::missing_codecs(profile, self).is_empty()
+    }
 }
 
 /// Detects available FFmpeg encoders by parsing `ffmpeg -encoders`.
@@ -275,6 +286,29 @@ pub fn validate_config(config: &AppConfig, capabilities: &SystemCapabilities) ->
             &profile.encoder_params,
             &format!("profiles[{}].encoder_params", i),
         ));
+
+        // A step that only switches encoder runs it with the profile's params
+        for (j, step) in profile.retry_ladder.iter().enumerate() {
+            let (params, path) = match (&step.encoder_params, &step.encoder) {
+                (Some(params), _) => (
+                    params,
+                    format!("profiles[{}].retry_ladder[{}].encoder_params", i, j),
+                ),
+                (None, Some(_)) => (
+                    &profile.encoder_params,
+                    format!(
+                        "profiles[{}].encoder_params (used by retry_ladder[{}])",
+                        i, j
+                    ),
+                ),
+                (None, None) => continue,
+            };
+            result.extend(encoder_params::validate(
+                step.encoder.as_ref().unwrap_or(&profile.encoder),
+                params,
+                &path,
+            ));
+        }
     }
 
     result
@@ -1,6 +1,5 @@
 //! Path validation for configuration directories.
 
-use std::collections::HashSet;
 use std::path::Path;
 
 use crate::config::model::AppConfig;
@@ -21,10 +20,18 @@ pub fn validate(config: &AppConfig) -> ValidationResult {
         let prefix = format!("profiles[{}]", i);
 
         // Validate input path exists and is readable
-        validate_directory_readable(&profile.input_path, &format!("{}.input_path", prefix), &mut result);
+        validate_directory_readable(
+            &profile.input_path,
+            &format!("{}.input_path", prefix),
+            &mut result,
+        );
 
         // Validate output path exists and is writable
-        validate_directory_writable(&profile.output_path, &format!("{}.output_path", prefix), &mut result);
+        validate_directory_writable(
+            &profile.output_path,
+            &format!("{}.output_path", prefix),
+            &mut result,
+        );
 
         // Input and output paths should not be the same
         if profile.input_path == profile.output_path {
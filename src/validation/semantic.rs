@@ -2,7 +2,13 @@
 
 use std::collections::HashSet;
 
-use crate::config::model::{AppConfig, AudioAction, DownmixMode};
+use crate::config::model::{
+    AppConfig, AudioAction, DownmixMode, HookConfig, IoniceClass, ProcessLimits, Profile,
+    ProfileMode, SizeGuard, SizeGuardAction, SkipRule, TimeWindow,
+};
+use crate::encoder::limits::parse_cpu_list;
+use crate::encoder::throttle::parse_time;
+use crate::queue::keys::is_valid_namespace;
 
 use super::{ValidationIssue, ValidationResult};
 
@@ -15,8 +21,21 @@ const VALID_LANGUAGE_CODES: &[&str] = &[
 
 /// Valid audio codec names.
 const VALID_AUDIO_CODECS: &[&str] = &[
-    "aac", "ac3", "eac3", "dts", "truehd", "flac", "opus", "libopus", "mp3", "libmp3lame",
-    "vorbis", "libvorbis", "pcm_s16le", "pcm_s24le", "pcm_s32le",
+    "aac",
+    "ac3",
+    "eac3",
+    "dts",
+    "truehd",
+    "flac",
+    "opus",
+    "libopus",
+    "mp3",
+    "libmp3lame",
+    "vorbis",
+    "libvorbis",
+    "pcm_s16le",
+    "pcm_s24le",
+    "pcm_s32le",
 ];
 
 /// Validates semantic correctness of configuration values.
@@ -25,6 +44,12 @@ pub fn validate(config: &AppConfig) -> ValidationResult {
 
     // Validate global settings
     validate_global(&config.global, &mut result);
+    validate_concurrency(config, &mut result);
+    validate_throttle(config, &mut result);
+    validate_process_limits(&config.global.process, "global.process", &mut result);
+    validate_watchdog(config, &mut result);
+    validate_retry(config, &mut result);
+    validate_profile_rules(config, &mut result);
 
     // Check for duplicate profile names
     let mut seen_names = HashSet::new();
@@ -55,10 +80,7 @@ pub fn validate(config: &AppConfig) -> ValidationResult {
             result.add(
                 ValidationIssue::error(
                     format!("{}.vmaf_target", prefix),
-                    format!(
-                        "VMAF target {} is out of range",
-                        profile.vmaf_target
-                    ),
+                    format!("VMAF target {} is out of range", profile.vmaf_target),
                 )
                 .with_suggestion("VMAF target must be between 0 and 100"),
             );
@@ -72,9 +94,48 @@ pub fn validate(config: &AppConfig) -> ValidationResult {
             ));
         }
 
+        // Validate retry ladder
+        validate_retry_ladder(profile, &prefix, &mut result);
+
+        // Video settings have no effect when the video is kept
+        if profile.mode == ProfileMode::Remux {
+            for (field, set) in [
+                ("retry_ladder", !profile.retry_ladder.is_empty()),
+                ("size_guard", profile.size_guard.is_some()),
+            ] {
+                if set {
+                    result.add(
+                        ValidationIssue::warning(
+                            format!("{}.{}", prefix, field),
+                            "Setting is ignored because the profile remuxes without encoding video",
+                        )
+                        .with_suggestion("Remove it or use mode: encode"),
+                    );
+                }
+            }
+        }
+
+        // Validate skip rules
+        validate_skip_rules(&profile.skip_rules, &prefix, &mut result);
+
+        // Validate size rules
+        if let Some(guard) = &profile.size_guard {
+            validate_size_guard(guard, profile, &prefix, &mut result);
+        }
+
         // Validate audio rules
         validate_audio_rules(&profile.audio.rules, &prefix, &mut result);
 
+        // Validate profile hooks
+        validate_hooks(&profile.hooks, &format!("{}.hooks", prefix), &mut result);
+
+        // Validate subprocess limits
+        validate_process_limits(
+            &profile.process,
+            &format!("{}.process", prefix),
+            &mut result,
+        );
+
         // Validate language priority codes
         for (j, lang) in profile.audio.language_priority.iter().enumerate() {
             if !VALID_LANGUAGE_CODES.contains(&lang.as_str()) {
@@ -112,13 +173,28 @@ pub fn validate(config: &AppConfig) -> ValidationResult {
 }
 
 /// Validates global configuration settings.
+/// Checks that webhook profile rules name existing profiles.
+fn validate_profile_rules(config: &AppConfig, result: &mut ValidationResult) {
+    for (i, rule) in config.global.api.profile_rules.iter().enumerate() {
+        if !config.profiles.iter().any(|p| p.name == rule.profile) {
+            result.add(ValidationIssue::error(
+                format!("global.api.profile_rules[{}].profile", i),
+                format!("Unknown profile '{}'", rule.profile),
+            ));
+        }
+    }
+}
+
 fn validate_global(global: &crate::config::model::GlobalConfig, result: &mut ValidationResult) {
     // Validate log level
     let valid_levels = ["trace", "debug", "info", "warn", "error"];
     if !valid_levels.contains(&global.log_level.as_str()) {
         result.add(
-            ValidationIssue::error("global.log_level", format!("Invalid log level: '{}'", global.log_level))
-                .with_suggestion(format!("Valid levels: {}", valid_levels.join(", "))),
+            ValidationIssue::error(
+                "global.log_level",
+                format!("Invalid log level: '{}'", global.log_level),
+            )
+            .with_suggestion(format!("Valid levels: {}", valid_levels.join(", "))),
         );
     }
 
@@ -130,6 +206,66 @@ fn validate_global(global: &crate::config::model::GlobalConfig, result: &mut Val
         ));
     }
 
+    // Validate Redis connection options
+    let redis = &global.redis;
+    if let Some(url) = &redis.url {
+        let schemes = ["redis://", "rediss://", "redis+unix://", "unix://"];
+        if !schemes.iter().any(|s| url.starts_with(s)) {
+            result.add(
+                ValidationIssue::error("global.redis.url", "Unsupported Redis URL scheme")
+                    .with_suggestion(format!("Use one of: {}", schemes.join(", "))),
+            );
+        }
+    }
+
+    let targets = [
+        redis.url.is_some(),
+        redis.socket.is_some(),
+        redis.sentinel.is_some(),
+    ];
+    if targets.iter().filter(|t| **t).count() > 1 {
+        result.add(ValidationIssue::error(
+            "global.redis",
+            "Only one of 'url', 'socket' and 'sentinel' can be set",
+        ));
+    }
+
+    if let Some(sentinel) = &redis.sentinel {
+        if sentinel.nodes.is_empty() {
+            result.add(ValidationIssue::error(
+                "global.redis.sentinel.nodes",
+                "At least one sentinel address is required",
+            ));
+        }
+    }
+
+    if redis.tls.client_cert.is_some() != redis.tls.client_key.is_some() {
+        result.add(ValidationIssue::error(
+            "global.redis.tls",
+            "'client_cert' and 'client_key' must be set together",
+        ));
+    }
+
+    if redis.tls.enabled && redis.socket.is_some() {
+        result.add(ValidationIssue::warning(
+            "global.redis.tls.enabled",
+            "TLS is not used for Unix socket connections",
+        ));
+    }
+
+    // Validate Redis namespace
+    if let Some(namespace) = &global.redis.namespace {
+        if !is_valid_namespace(namespace) {
+            result.add(
+                ValidationIssue::error(
+                    "global.redis.namespace",
+                    format!("Invalid Redis namespace: '{}'", namespace),
+                )
+                .with_suggestion("Use only letters, digits, '_', '.' and '-', e.g. 'movies'"),
+            );
+        }
+    }
+
     // Validate stability check settings
     if global.stability_check.duration_seconds == 0 {
         result.add(ValidationIssue::error(
@@ -152,6 +288,503 @@ fn validate_global(global: &crate::config::model::GlobalConfig, result: &mut Val
             "Prometheus port cannot be 0 when enabled",
         ));
     }
+
+    // Validate API settings
+    if global.api.enabled {
+        if global.api.port == 0 {
+            result.add(ValidationIssue::error(
+                "global.api.port",
+                "API port cannot be 0 when enabled",
+            ));
+        } else if global.prometheus.enabled && global.api.port == global.prometheus.port {
+            result.add(ValidationIssue::error(
+                "global.api.port",
+                format!("API port {} is already used by Prometheus", global.api.port),
+            ));
+        }
+
+        if global.api.token.is_none() {
+            result.add(
+                ValidationIssue::warning(
+                    "global.api.token",
+                    "API is enabled without authentication",
+                )
+                .with_suggestion("Set a token to require 'Authorization: Bearer <token>'"),
+            );
+        }
+
+        for (i, mapping) in global.api.path_mappings.iter().enumerate() {
+            if !mapping.remote.is_absolute() || !mapping.local.is_absolute() {
+                result.add(ValidationIssue::error(
+                    format!("global.api.path_mappings[{}]", i),
+                    "Path mappings must use absolute paths for 'remote' and 'local'",
+                ));
+            }
+        }
+
+        for (i, rule) in global.api.profile_rules.iter().enumerate() {
+            if !rule.path.is_absolute() {
+                result.add(ValidationIssue::error(
+                    format!("global.api.profile_rules[{}].path", i),
+                    "Profile rules must use absolute paths",
+                ));
+            }
+        }
+    }
+
+    // Validate global hooks
+    validate_hooks(&global.hooks, "global.hooks", result);
+}
+
+/// Validates concurrency limits against the configured profiles.
+fn validate_concurrency(config: &AppConfig, result: &mut ValidationResult) {
+    let concurrency = &config.global.concurrency;
+
+    if concurrency.max_jobs == 0 {
+        result.add(ValidationIssue::error(
+            "global.concurrency.max_jobs",
+            "At least one concurrent job is required",
+        ));
+    }
+
+    for (name, limit) in &concurrency.profile_limits {
+        let path = format!("global.concurrency.profile_limits.{}", name);
+        if !config.profiles.iter().any(|p| &p.name == name) {
+            result.add(ValidationIssue::error(
+                path,
+                format!("Unknown profile: '{}'", name),
+            ));
+        } else if *limit == 0 {
+            result.add(
+                ValidationIssue::error(path, "Profile limit must be at least 1")
+                    .with_suggestion("Remove the profile's input folder to stop encoding it"),
+            );
+        }
+    }
+
+    if concurrency.worker_budget > 0 {
+        for (i, profile) in config.profiles.iter().enumerate() {
+            if profile.worker_cost() > concurrency.worker_budget {
+                result.add(ValidationIssue::warning(
+                    format!("profiles[{}].workers", i),
+                    format!(
+                        "{} workers exceed the worker budget of {}; these jobs only run when nothing else is",
+                        profile.workers, concurrency.worker_budget
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Validates throttle settings and every pause window.
+fn validate_throttle(config: &AppConfig, result: &mut ValidationResult) {
+    let throttle = &config.global.throttle;
+
+    validate_windows(
+        &throttle.pause_windows,
+        "global.throttle.pause_windows",
+        result,
+    );
+    for (i, profile) in config.profiles.iter().enumerate() {
+        validate_windows(
+            &profile.pause_windows,
+            &format!("profiles[{}].pause_windows", i),
+            result,
+        );
+    }
+
+    if throttle.max_load_average.is_some_and(|load| load <= 0.0) {
+        result.add(ValidationIssue::error(
+            "global.throttle.max_load_average",
+            "Load average limit must be greater than 0",
+        ));
+    }
+
+    if throttle
+        .playback_command
+        .as_ref()
+        .is_some_and(|c| c.is_empty())
+    {
+        result.add(
+            ValidationIssue::error(
+                "global.throttle.playback_command",
+                "Playback command cannot be empty",
+            )
+            .with_suggestion("Remove the setting to disable the playback check"),
+        );
+    }
+
+    if throttle.reduced_workers == 0 {
+        result.add(ValidationIssue::error(
+            "global.throttle.reduced_workers",
+            "Reduced workers must be at least 1",
+        ));
+    }
+
+    if throttle.check_interval_seconds == 0 {
+        result.add(ValidationIssue::error(
+            "global.throttle.check_interval_seconds",
+            "Check interval must be at least 1 second",
+        ));
+    }
+}
+
+/// Validates the start and end times of pause windows.
+fn validate_windows(windows: &[TimeWindow], prefix: &str, result: &mut ValidationResult) {
+    for (i, window) in windows.iter().enumerate() {
+        for (field, value) in [("start", &window.start), ("end", &window.end)] {
+            if parse_time(value).is_none() {
+                result.add(
+                    ValidationIssue::error(
+                        format!("{}[{}].{}", prefix, i, field),
+                        format!("Invalid time: '{}'", value),
+                    )
+                    .with_suggestion("Use 24-hour HH:MM, e.g. '22:30'"),
+                );
+            }
+        }
+
+        if window.start == window.end {
+            result.add(ValidationIssue::warning(
+                format!("{}[{}]", prefix, i),
+                "Window starts and ends at the same time and never applies",
+            ));
+        }
+    }
+}
+
+/// Validates the watchdog's phase time limits.
+fn validate_watchdog(config: &AppConfig, result: &mut ValidationResult) {
+    let timeouts = &config.global.watchdog.phase_timeouts;
+
+    for (phase, seconds) in [
+        ("extracting_subtitles", timeouts.extracting_subtitles),
+        ("encoding_video", timeouts.encoding_video),
+        ("burning_subtitles", timeouts.burning_subtitles),
+        ("processing_audio", timeouts.processing_audio),
+        ("muxing", timeouts.muxing),
+    ] {
+        if seconds == Some(0) {
+            result.add(
+                ValidationIssue::error(
+                    format!("global.watchdog.phase_timeouts.{}", phase),
+                    "Phase time limit must be at least 1 second",
+                )
+                .with_suggestion("Remove the setting for no limit"),
+            );
+        }
+    }
+}
+
+/// Validates the settings of a profile's retry steps.
+fn validate_retry_ladder(profile: &Profile, prefix: &str, result: &mut ValidationResult) {
+    for (i, step) in profile.retry_ladder.iter().enumerate() {
+        let step_prefix = format!("{}.retry_ladder[{}]", prefix, i);
+
+        if step
+            .vmaf_target
+            .is_some_and(|t| !(0.0..=100.0).contains(&t))
+        {
+            result.add(
+                ValidationIssue::error(
+                    format!("{}.vmaf_target", step_prefix),
+                    "VMAF target is out of range",
+                )
+                .with_suggestion("VMAF target must be between 0 and 100"),
+            );
+        }
+
+        if step.workers == Some(0) {
+            result.add(ValidationIssue::error(
+                format!("{}.workers", step_prefix),
+                "Workers must be at least 1",
+            ));
+        }
+
+        if step.encoder.as_ref().is_some_and(|e| e != &profile.encoder)
+            && step.encoder_params.is_none()
+        {
+            result.add(
+                ValidationIssue::warning(
+                    format!("{}.encoder_params", step_prefix),
+                    "Encoder changes but the profile's encoder parameters are kept",
+                )
+                .with_suggestion("Set encoder_params for the fallback encoder"),
+            );
+        }
+    }
+}
+
+/// Validates a profile's skip rules.
+fn validate_skip_rules(rules: &[SkipRule], prefix: &str, result: &mut ValidationResult) {
+    for (i, rule) in rules.iter().enumerate() {
+        let rule_prefix = format!("{}.skip_rules[{}]", prefix, i);
+
+        if rule.name.trim().is_empty() {
+            result.add(ValidationIssue::error(
+                format!("{}.name", rule_prefix),
+                "Skip rule name cannot be empty",
+            ));
+        }
+
+        if rule.codecs.is_empty()
+            && rule.max_bits_per_pixel.is_none()
+            && rule.below_height.is_none()
+            && !rule.provenance
+        {
+            result.add(
+                ValidationIssue::error(
+                    rule_prefix.clone(),
+                    "Skip rule has no conditions and never matches",
+                )
+                .with_suggestion("Set codecs, max_bits_per_pixel, below_height or provenance"),
+            );
+        }
+
+        if rule.max_bits_per_pixel.is_some_and(|bpp| bpp <= 0.0) {
+            result.add(ValidationIssue::error(
+                format!("{}.max_bits_per_pixel", rule_prefix),
+                "Bits per pixel threshold must be greater than 0",
+            ));
+        }
+
+        if rule.below_height == Some(0) {
+            result.add(ValidationIssue::error(
+                format!("{}.below_height", rule_prefix),
+                "Height floor must be at least 1",
+            ));
+        }
+    }
+}
+
+/// Validates a profile's output size rules.
+fn validate_size_guard(
+    guard: &SizeGuard,
+    profile: &Profile,
+    prefix: &str,
+    result: &mut ValidationResult,
+) {
+    let guard_prefix = format!("{}.size_guard", prefix);
+
+    if guard.max_ratio.is_none() && guard.min_saving_mb.is_none() {
+        result.add(
+            ValidationIssue::warning(
+                guard_prefix.clone(),
+                "Size guard has no rules and never triggers",
+            )
+            .with_suggestion("Set max_ratio or min_saving_mb"),
+        );
+    }
+
+    if guard.max_ratio.is_some_and(|r| r <= 0.0) {
+        result.add(ValidationIssue::error(
+            format!("{}.max_ratio", guard_prefix),
+            "Maximum output/input ratio must be greater than 0",
+        ));
+    }
+
+    if guard.action == SizeGuardAction::Retarget {
+        if guard.vmaf_step <= 0.0 {
+            result.add(ValidationIssue::error(
+                format!("{}.vmaf_step", guard_prefix),
+                "VMAF step must be greater than 0",
+            ));
+        }
+
+        if !(0.0..=100.0).contains(&guard.vmaf_floor) {
+            result.add(
+                ValidationIssue::error(
+                    format!("{}.vmaf_floor", guard_prefix),
+                    "VMAF floor is out of range",
+                )
+                .with_suggestion("VMAF floor must be between 0 and 100"),
+            );
+        } else if guard.vmaf_floor > profile.vmaf_target - guard.vmaf_step {
+            result.add(
+                ValidationIssue::warning(
+                    format!("{}.vmaf_floor", guard_prefix),
+                    format!(
+                        "VMAF floor {} leaves no room below the target {}, so the source is always kept",
+                        guard.vmaf_floor, profile.vmaf_target
+                    ),
+                )
+                .with_suggestion("Lower vmaf_floor or use another action"),
+            );
+        }
+    }
+}
+
+/// Validates the retry attempts, globally and per failure category.
+fn validate_retry(config: &AppConfig, result: &mut ValidationResult) {
+    let retry = &config.global.retry;
+
+    if retry.max_attempts == 0 {
+        result.add(ValidationIssue::error(
+            "global.retry.max_attempts",
+            "Maximum attempts must be at least 1",
+        ));
+    }
+
+    if retry.max_delay_secs < retry.delay_secs {
+        result.add(
+            ValidationIssue::warning(
+                "global.retry.max_delay_secs",
+                "Maximum retry delay is shorter than the retry delay",
+            )
+            .with_suggestion("Retries wait at most max_delay_secs"),
+        );
+    }
+
+    for (category, policy) in [
+        ("permanent", &retry.policies.permanent),
+        ("transient", &retry.policies.transient),
+        ("resource", &retry.policies.resource),
+    ] {
+        if policy.max_attempts == Some(0) {
+            result.add(
+                ValidationIssue::error(
+                    format!("global.retry.policies.{}.max_attempts", category),
+                    "Maximum attempts must be at least 1",
+                )
+                .with_suggestion("Use 1 to never retry failures of this kind"),
+            );
+        }
+    }
+}
+
+/// Validates subprocess scheduling and resource limits.
+fn validate_process_limits(limits: &ProcessLimits, prefix: &str, result: &mut ValidationResult) {
+    if let Some(nice) = limits.nice {
+        if !(-20..=19).contains(&nice) {
+            result.add(ValidationIssue::error(
+                format!("{}.nice", prefix),
+                format!("Niceness must be between -20 and 19, got {}", nice),
+            ));
+        }
+    }
+
+    if let Some(level) = limits.ionice_level {
+        if level > 7 {
+            result.add(ValidationIssue::error(
+                format!("{}.ionice_level", prefix),
+                format!("I/O priority level must be between 0 and 7, got {}", level),
+            ));
+        } else if limits.ionice == Some(IoniceClass::Idle) {
+            result.add(ValidationIssue::warning(
+                format!("{}.ionice_level", prefix),
+                "I/O priority level is ignored with the idle class",
+            ));
+        }
+    }
+
+    if let Some(list) = &limits.cpu_affinity {
+        if let Err(message) = parse_cpu_list(list) {
+            result.add(
+                ValidationIssue::error(format!("{}.cpu_affinity", prefix), message)
+                    .with_suggestion("Use CPU numbers and ranges, e.g. '0-7,16'"),
+            );
+        }
+    }
+
+    if limits.memory_ceiling_mb == Some(0) {
+        result.add(ValidationIssue::error(
+            format!("{}.memory_ceiling_mb", prefix),
+            "Memory ceiling must be greater than 0",
+        ));
+    }
+
+    if let Some(cgroup) = &limits.cgroup {
+        if cgroup.cpu_cores.is_some_and(|cores| cores <= 0.0) {
+            result.add(ValidationIssue::error(
+                format!("{}.cgroup.cpu_cores", prefix),
+                "CPU cores must be greater than 0",
+            ));
+        }
+        if cgroup.memory_max_mb == Some(0) {
+            result.add(ValidationIssue::error(
+                format!("{}.cgroup.memory_max_mb", prefix),
+                "Memory limit must be greater than 0",
+            ));
+        }
+        if let (Some(ceiling), Some(max)) = (limits.memory_ceiling_mb, cgroup.memory_max_mb) {
+            if ceiling >= max {
+                result.add(ValidationIssue::warning(
+                    format!("{}.memory_ceiling_mb", prefix),
+                    format!(
+                        "Memory ceiling of {} MiB is never reached before the cgroup limit of {} MiB",
+                        ceiling, max
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Validates lifecycle hook definitions.
+fn validate_hooks(hooks: &[HookConfig], prefix: &str, result: &mut ValidationResult) {
+    for (j, hook) in hooks.iter().enumerate() {
+        let hook_prefix = format!("{}[{}]", prefix, j);
+
+        if hook.name.trim().is_empty() {
+            result.add(ValidationIssue::error(
+                format!("{}.name", hook_prefix),
+                "Hook name cannot be empty",
+            ));
+        }
+
+        if hook.events.is_empty() {
+            result.add(
+                ValidationIssue::warning(
+                    format!("{}.events", hook_prefix),
+                    format!("Hook '{}' has no events and will never run", hook.name),
+                )
+                .with_suggestion(
+                    "Valid events: enqueued, started, succeeded, failed, dead_lettered",
+                ),
+            );
+        }
+
+        match (&hook.command, &hook.http) {
+            (Some(_), Some(_)) => {
+                result.add(ValidationIssue::error(
+                    hook_prefix.clone(),
+                    format!("Hook '{}' sets both 'command' and 'http'", hook.name),
+                ));
+            }
+            (None, None) => {
+                result.add(
+                    ValidationIssue::error(
+                        hook_prefix.clone(),
+                        format!("Hook '{}' needs a 'command' or 'http' target", hook.name),
+                    )
+                    .with_suggestion("Example: command: [\"/scripts/refresh.sh\"]"),
+                );
+            }
+            (Some(command), None) if command.is_empty() => {
+                result.add(ValidationIssue::error(
+                    format!("{}.command", hook_prefix),
+                    "Hook command cannot be empty",
+                ));
+            }
+            (None, Some(http))
+                if !http.url.starts_with("http://") && !http.url.starts_with("https://") =>
+            {
+                result.add(ValidationIssue::error(
+                    format!("{}.http.url", hook_prefix),
+                    format!("Invalid hook URL: '{}'", http.url),
+                ));
+            }
+            _ => {}
+        }
+
+        if hook.timeout_seconds == 0 {
+            result.add(ValidationIssue::error(
+                format!("{}.timeout_seconds", hook_prefix),
+                "Hook timeout must be at least 1 second",
+            ));
+        }
+    }
 }
 
 /// Validates audio processing rules.
@@ -184,7 +817,10 @@ fn validate_audio_rules(
                             format!("{}.match.languages[{}]", rule_prefix, k),
                             format!("Unknown language code: '{}'", lang),
                         )
-                        .with_suggestion(format!("Did you mean '{}'?", find_similar_language(lang))),
+                        .with_suggestion(format!(
+                            "Did you mean '{}'?",
+                            find_similar_language(lang)
+                        )),
                     );
                 }
             }
@@ -215,9 +851,17 @@ fn validate_audio_rules(
                         "Transcode settings required for this action",
                     ));
                 } else if let Some(transcode) = &rule.transcode {
-                    validate_bitrate(&transcode.bitrate, &format!("{}.transcode.bitrate", rule_prefix), result);
+                    validate_bitrate(
+                        &transcode.bitrate,
+                        &format!("{}.transcode.bitrate", rule_prefix),
+                        result,
+                    );
                     if let Some(lossless_bitrate) = &transcode.lossless_bitrate {
-                        validate_bitrate(lossless_bitrate, &format!("{}.transcode.lossless_bitrate", rule_prefix), result);
+                        validate_bitrate(
+                            lossless_bitrate,
+                            &format!("{}.transcode.lossless_bitrate", rule_prefix),
+                            result,
+                        );
                     }
                 }
             }
@@ -227,7 +871,11 @@ fn validate_audio_rules(
         // Validate downmix settings
         if let Some(downmix) = &rule.downmix {
             if !matches!(downmix.mode, DownmixMode::None) {
-                validate_bitrate(&downmix.bitrate, &format!("{}.downmix.bitrate", rule_prefix), result);
+                validate_bitrate(
+                    &downmix.bitrate,
+                    &format!("{}.downmix.bitrate", rule_prefix),
+                    result,
+                );
             }
         }
     }
@@ -282,3 +930,36 @@ fn find_similar_language(input: &str) -> &'static str {
         .copied()
         .unwrap_or("eng")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(namespace: &str) -> AppConfig {
+        let mut config: AppConfig =
+            serde_yaml::from_str("global:\n  redis: {}\nprofiles: []\n").unwrap();
+        config.global.redis.namespace = Some(namespace.to_string());
+        config
+    }
+
+    fn namespace_errors(namespace: &str) -> usize {
+        validate(&config(namespace))
+            .errors()
+            .filter(|e| e.path == "global.redis.namespace")
+            .count()
+    }
+
+    #[test]
+    fn namespace_rejects_glob_characters() {
+        for namespace in ["movies*", "movie?", "[mt]v", "a b", ""] {
+            assert_eq!(namespace_errors(namespace), 1, "namespace {:?}", namespace);
+        }
+    }
+
+    #[test]
+    fn namespace_accepts_plain_names() {
+        for namespace in ["movies", "tv-4k", "site_2.prod"] {
+            assert_eq!(namespace_errors(namespace), 0, "namespace {:?}", namespace);
+        }
+    }
+}
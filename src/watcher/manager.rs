@@ -6,13 +6,14 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use tokio::sync::{mpsc, RwLock};
-use tracing::{error, info};
+use tracing::{debug, error, info};
 
 use super::folder::{DetectedFile, FolderWatcher};
 use super::stability::StabilityChecker;
 use crate::config::model::{AppConfig, Profile};
-use crate::error::WatcherError;
-use crate::queue::job::EncodeJob;
+use crate::error::{QueueError, WatcherError};
+use crate::notify::HookRunner;
+use crate::queue::submit::{submit, SubmitRequest};
 use crate::queue::QueueManager;
 
 /// Manages all folder watchers and coordinates file detection.
@@ -31,6 +32,8 @@ pub struct WatcherManager {
     queue: QueueManager,
     /// Current configuration.
     config: Arc<RwLock<AppConfig>>,
+    /// Runner for user-defined lifecycle hooks.
+    hooks: Arc<HookRunner>,
 }
 
 impl WatcherManager {
@@ -40,6 +43,7 @@ impl WatcherManager {
         queue: QueueManager,
         stability_duration: Duration,
         poll_interval: Duration,
+        hooks: Arc<HookRunner>,
     ) -> Self {
         let (file_tx, file_rx) = mpsc::channel(100);
         let (ready_tx, ready_rx) = mpsc::channel(100);
@@ -54,6 +58,7 @@ impl WatcherManager {
             ready_rx,
             queue,
             config,
+            hooks,
         }
     }
 
@@ -109,8 +114,7 @@ impl WatcherManager {
         let files = watcher.scan_existing().await?;
 
         for file in files {
-            self.stability_checker
-                .track(file.path, file.profile_name);
+            self.stability_checker.track(file.path, file.profile_name);
         }
 
         Ok(())
@@ -137,43 +141,43 @@ impl WatcherManager {
                 // Periodic stability checks
                 _ = tokio::time::sleep(poll_interval) => {
                     self.stability_checker.check_all().await;
+                    self.publish_state(poll_interval).await;
                 }
             }
         }
     }
 
+    /// Publishes the tracking state for dashboards.
+    ///
+    /// The state expires if the pipeline stops publishing it.
+    async fn publish_state(&mut self, poll_interval: Duration) {
+        let snapshot = self.stability_checker.snapshot();
+        let ttl = (poll_interval.as_secs() * 3).max(30);
+
+        if let Err(e) = self.queue.publish_watcher_state(&snapshot, ttl).await {
+            debug!(error = %e, "Failed to publish watcher state");
+        }
+    }
+
     /// Enqueues a file for encoding.
     async fn enqueue_file(&mut self, path: PathBuf) -> Result<(), WatcherError> {
-        let config = self.config.read().await;
+        let request = SubmitRequest::new(path.clone());
 
-        // Find the profile for this file
-        let profile = config
-            .profiles
-            .iter()
-            .find(|p| path.starts_with(&p.input_path));
-
-        let profile = match profile {
-            Some(p) => p,
-            None => {
+        match submit(&mut self.queue, &self.config, &self.hooks, request).await {
+            Ok(_) => Ok(()),
+            Err(QueueError::NoMatchingProfile { .. }) => {
                 error!(?path, "No profile found for file");
-                return Ok(());
+                Ok(())
             }
-        };
-
-        // Calculate output path
-        let output_path = calculate_output_path(&path, profile);
-
-        let job = EncodeJob::new(path.clone(), output_path, profile.name.clone());
-
-        drop(config);
-
-        self.queue.enqueue(&job).await.map_err(|e| WatcherError::WatchFailed {
-            path,
-            message: format!("Failed to enqueue: {}", e),
-        })?;
-
-        info!(job_id = %job.id, "Enqueued encoding job");
-        Ok(())
+            Err(QueueError::DuplicateJob { job_id, .. }) => {
+                info!(?path, %job_id, "File is already queued, skipping");
+                Ok(())
+            }
+            Err(e) => Err(WatcherError::WatchFailed {
+                path,
+                message: format!("Failed to enqueue: {}", e),
+            }),
+        }
     }
 
     /// Reloads watchers after configuration change.
@@ -184,49 +188,3 @@ impl WatcherManager {
         Ok(())
     }
 }
-
-/// Calculates the output path for a file based on profile settings.
-fn calculate_output_path(input_path: &PathBuf, profile: &Profile) -> PathBuf {
-    use crate::config::model::{FilenameMode, OutputStructure};
-
-    let relative_path = input_path
-        .strip_prefix(&profile.input_path)
-        .unwrap_or(input_path.as_path());
-
-    let mut output_path = profile.output_path.clone();
-
-    match profile.output_naming.structure {
-        OutputStructure::Mirror => {
-            if let Some(parent) = relative_path.parent() {
-                output_path = output_path.join(parent);
-            }
-        }
-        OutputStructure::Flat => {
-            // Just use the output directory directly
-        }
-    }
-
-    let filename = match profile.output_naming.filename {
-        FilenameMode::Preserve => {
-            let mut name = relative_path
-                .file_stem()
-                .unwrap_or_default()
-                .to_os_string();
-
-            if let Some(suffix) = &profile.output_naming.suffix {
-                name.push(suffix);
-            }
-
-            name.push(".mkv");
-            PathBuf::from(name)
-        }
-        FilenameMode::Template => {
-            // Template processing would go here
-            // For now, fall back to preserve
-            let name = relative_path.file_name().unwrap_or_default();
-            PathBuf::from(name)
-        }
-    };
-
-    output_path.join(filename)
-}
@@ -4,6 +4,8 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
@@ -19,6 +21,30 @@ pub struct StabilityChecker {
     ready_tx: mpsc::Sender<PathBuf>,
 }
 
+/// Point-in-time view of the files being tracked, shared through Redis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatcherSnapshot {
+    /// When the snapshot was taken.
+    pub updated_at: DateTime<Utc>,
+    /// Required stable duration in seconds.
+    pub stability_seconds: u64,
+    /// Files waiting to become stable.
+    pub files: Vec<TrackedFileState>,
+}
+
+/// State of a single tracked file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedFileState {
+    /// File path.
+    pub path: PathBuf,
+    /// Profile the file was detected for.
+    pub profile: String,
+    /// Last recorded size in bytes.
+    pub size: u64,
+    /// Seconds the size has been unchanged (None while still growing).
+    pub stable_seconds: Option<u64>,
+}
+
 /// A file being tracked for stability.
 struct TrackedFile {
     /// Last recorded file size.
@@ -51,9 +77,7 @@ impl StabilityChecker {
             return;
         }
 
-        let size = std::fs::metadata(&path)
-            .map(|m| m.len())
-            .unwrap_or(0);
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
 
         info!(?path, size, "Started tracking file for stability");
 
@@ -67,6 +91,27 @@ impl StabilityChecker {
         );
     }
 
+    /// Returns a snapshot of the tracked files.
+    pub fn snapshot(&self) -> WatcherSnapshot {
+        let mut files: Vec<TrackedFileState> = self
+            .tracked_files
+            .iter()
+            .map(|(path, tracked)| TrackedFileState {
+                path: path.clone(),
+                profile: tracked.profile_name.clone(),
+                size: tracked.last_size,
+                stable_seconds: tracked.stable_since.map(|s| s.elapsed().as_secs()),
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        WatcherSnapshot {
+            updated_at: Utc::now(),
+            stability_seconds: self.stability_duration.as_secs(),
+            files,
+        }
+    }
+
     /// Stops tracking a file.
     pub fn untrack(&mut self, path: &Path) {
         self.tracked_files.remove(path);
@@ -94,14 +139,24 @@ impl StabilityChecker {
 
                 if let Some(stable_since) = tracked.stable_since {
                     if stable_since.elapsed() >= self.stability_duration {
-                        info!(?path, "File is ready (stable for {:?})", self.stability_duration);
+                        info!(
+                            ?path,
+                            profile = %tracked.profile_name,
+                            "File is ready (stable for {:?})",
+                            self.stability_duration
+                        );
                         ready_files.push(path.clone());
                     }
                 }
             } else {
                 // Size changed, reset stability tracking
                 if tracked.stable_since.is_some() {
-                    debug!(?path, old_size = tracked.last_size, new_size = current_size, "File size changed, resetting stability");
+                    debug!(
+                        ?path,
+                        old_size = tracked.last_size,
+                        new_size = current_size,
+                        "File size changed, resetting stability"
+                    );
                 }
                 tracked.last_size = current_size;
                 tracked.stable_since = None;